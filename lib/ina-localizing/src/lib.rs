@@ -24,11 +24,10 @@ use std::ops::Deref;
 use std::path::Path;
 use std::sync::Arc;
 
-use ina_threading::threads::invoker::{CallError, Stateful};
+use ina_threading::threads::invoker::CallError;
 use serde::{Deserialize, Serialize};
 use text::Text;
 use thread::{Request, Response};
-use tokio::sync::RwLock;
 use tracing::{debug, error, trace, trace_span, warn};
 
 use self::locale::Locale;
@@ -79,7 +78,7 @@ pub enum Error {
     ThreadSpawn(#[from] ina_threading::Error),
     /// An error from calling a function on the thread.
     #[error(transparent)]
-    ThreadCall(#[from] CallError<Stateful<RwLock<Localizer>, Request>, Response>),
+    ThreadCall(#[from] CallError<Request, Response>),
 }
 
 /// A value that stores and retrieves translated text.
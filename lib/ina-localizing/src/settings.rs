@@ -65,6 +65,13 @@ pub struct Settings {
     #[option(default = self::default_queue_capacity())]
     pub queue_capacity: NonZero<usize>,
 
+    /// The number of seconds to wait for the localizing thread to respond before a request fails.
+    ///
+    /// Default: `10`
+    #[arg(id = "LANG_CALL_TIMEOUT", long = "lang-call-timeout")]
+    #[option(default = self::default_call_timeout())]
+    pub call_timeout: NonZero<u64>,
+
     /// The amount of depth at which to search for a translation key in language files with inherited translations.
     ///
     /// Default: `2`
@@ -117,6 +124,13 @@ fn default_queue_capacity() -> NonZero<usize> {
     capacity
 }
 
+/// Returns the default number of seconds to wait for a response.
+fn default_call_timeout() -> NonZero<u64> {
+    let Some(timeout) = NonZero::new(10) else { unreachable!("the default timeout must be non-zero") };
+
+    timeout
+}
+
 /// Returns the default language file directory.
 fn default_directory() -> PathBuf {
     std::env::current_dir().map_or_else(|_| PathBuf::from("./res/lang/"), |v| v.join("res/lang"))
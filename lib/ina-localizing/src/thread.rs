@@ -14,9 +14,13 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
+use ina_threading::Handle;
+use ina_threading::metrics::Metrics;
 use ina_threading::statics::Static;
+use ina_threading::supervisor::{Restart, Supervisor};
 use ina_threading::threads::invoker::{Stateful, StatefulInvoker};
 use tokio::sync::RwLock;
 use tracing::error;
@@ -28,6 +32,10 @@ use crate::{Localizer, Result};
 
 /// The localization thread's handle.
 static THREAD: LocalizationThread = LocalizationThread::new();
+/// Restarts the localization thread if handling a request panics, so that later requests continue to be handled.
+const SUPERVISOR: Supervisor = Supervisor::new(Restart::OnPanic);
+/// The duration to wait for the localization thread to respond before a request fails.
+static TIMEOUT: Mutex<Duration> = Mutex::new(Duration::MAX);
 
 /// The localization thread's type.
 pub type LocalizationThread = Static<LocalizationThreadInner>;
@@ -72,14 +80,18 @@ pub enum Response {
 
 /// Creates a new localization thread.
 ///
+/// If handling a request panics, the thread is restarted and the request's caller receives an error. The restarted
+/// thread keeps the same localizer, so that loaded locales do not need to be loaded again.
+///
 /// # Errors
 ///
 /// This function will return an error if the thread fails to spawn.
 fn create(settings: Settings) -> Result<LocalizationThreadInner> {
     let capacity = settings.queue_capacity;
-    let localizer = RwLock::new(Localizer::new(settings));
+    let localizer = Arc::new(RwLock::new(Localizer::new(settings)));
+    let state = move || Arc::clone(&localizer);
 
-    Ok(StatefulInvoker::spawn_with_runtime("localizing", capacity, localizer, self::run)?)
+    Ok(StatefulInvoker::spawn_supervised_with_runtime("localizing", capacity, state, SUPERVISOR, self::run)?)
 }
 
 /// Starts the localization thread.
@@ -93,8 +105,12 @@ fn create(settings: Settings) -> Result<LocalizationThreadInner> {
 /// This function will return an error if the thread fails to spawn.
 #[tracing::instrument(level = "trace", name = "new_thread", skip_all)]
 pub async fn start(settings: Settings) -> Result<()> {
+    let timeout = Duration::from_secs(settings.call_timeout.get());
+
     THREAD.async_api().initialize(self::create(settings)?).await;
 
+    *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner) = timeout;
+
     Ok(())
 }
 
@@ -109,8 +125,12 @@ pub async fn start(settings: Settings) -> Result<()> {
 /// This function will return an error if the thread fails to spawn.
 #[tracing::instrument(level = "trace", name = "new_thread", skip_all)]
 pub fn blocking_start(settings: Settings) -> Result<()> {
+    let timeout = Duration::from_secs(settings.call_timeout.get());
+
     THREAD.sync_api().initialize(self::create(settings)?);
 
+    *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner) = timeout;
+
     Ok(())
 }
 
@@ -134,6 +154,26 @@ pub fn blocking_close() {
     THREAD.sync_api().close();
 }
 
+/// Returns the current runtime metrics of the localization thread.
+///
+/// # Panics
+///
+/// Panics if the localization thread is not initialized.
+pub async fn metrics() -> Option<Metrics> {
+    THREAD.async_api().get().await.metrics()
+}
+
+/// Returns the current runtime metrics of the localization thread.
+///
+/// This blocks the current thread.
+///
+/// # Panics
+///
+/// Panics if the localization thread is not initialized or if this is called in an asynchronous context.
+pub fn blocking_metrics() -> Option<Metrics> {
+    THREAD.sync_api().get().metrics()
+}
+
 /// Runs the thread's process.
 async fn run(Stateful { state, value }: Stateful<RwLock<Localizer>, Request>) -> Response {
     match value {
@@ -206,7 +246,8 @@ macro_rules! invoke {
     )*) => {$(
         $(#[$attribute])*
         pub async fn $name($($($input: $type),*)?) -> Result<$return> {
-            let response = THREAD.async_api().get_mut().await.call($($request)*).await?;
+            let timeout = *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner);
+            let response = THREAD.async_api().get().await.call_with_timeout($($request)*, timeout).await?;
 
             match response {
                 $($response)*
@@ -221,7 +262,8 @@ macro_rules! invoke {
         ///
         /// Panics if this is called from within a synchronous context.
         pub fn $blocking_name($($($input: $type),*)?) -> Result<$return> {
-            let response = THREAD.sync_api().get_mut().blocking_call($($request)*)?;
+            let timeout = *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner);
+            let response = THREAD.sync_api().get().blocking_call_with_timeout($($request)*, timeout)?;
 
             match response {
                 $($response)*
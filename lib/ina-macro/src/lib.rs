@@ -130,7 +130,81 @@ pub fn as_translation(input: TokenStream) -> TokenStream {
 ///     value: u64,
 /// }
 /// ```
-#[proc_macro_derive(Stored, attributes(data_path, data_format))]
+///
+/// Derive with a versioned schema, where each migration converts a value of the previous version into the next:
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use ina_macro::Stored;
+/// # use ina_storage::format::{Compress, Messagepack};
+/// #[derive(Serialize, Deserialize, Stored)]
+/// #[data_format(Compress<Messagepack>)]
+/// #[data_path(fmt = "dir/{}", args = [String], from = [name])]
+/// #[data_version(3, migrate = [migrate_v1_to_v2, migrate_v2_to_v3])]
+/// struct DataStructure {
+///     name: String,
+///     values: Vec<u64>,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct DataStructureV1 {
+///     name: String,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct DataStructureV2 {
+///     name: String,
+///     value: u64,
+/// }
+///
+/// fn migrate_v1_to_v2(DataStructureV1 { name }: DataStructureV1) -> DataStructureV2 {
+///     DataStructureV2 { name, value: 0 }
+/// }
+///
+/// fn migrate_v2_to_v3(DataStructureV2 { name, value }: DataStructureV2) -> DataStructure {
+///     DataStructure { name, values: vec![value] }
+/// }
+/// ```
+///
+/// Derive with a default lifetime, after which stored values expire unless they are written again:
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use ina_macro::Stored;
+/// # use ina_storage::format::{Compress, Messagepack};
+/// #[derive(Serialize, Deserialize, Stored)]
+/// #[data_format(Compress<Messagepack>)]
+/// #[data_path(fmt = "dir/{}", args = [String], from = [name])]
+/// #[data_expiry(days = 7)] // also accepts `seconds`, `minutes`, and `hours`
+/// struct DataStructure {
+///     name: String,
+///     value: u64,
+/// }
+/// ```
+///
+/// Derive with secondary indexes, each of which maps the keys returned by the method of the same name to the values
+/// that contain them:
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use ina_macro::Stored;
+/// # use ina_storage::format::{Compress, Messagepack};
+/// #[derive(Serialize, Deserialize, Stored)]
+/// #[data_format(Compress<Messagepack>)]
+/// #[data_path(fmt = "dir/{}", args = [String], from = [name])]
+/// #[data_index(tags)] // found using `DataStructure::async_api().find_by_index("tags", key)`
+/// struct DataStructure {
+///     name: String,
+///     values: Vec<(String, u64)>,
+/// }
+///
+/// impl DataStructure {
+///     fn tags(&self) -> impl Iterator<Item = &str> {
+///         self.values.iter().map(|(tag, _)| tag.as_str())
+///     }
+/// }
+/// ```
+#[proc_macro_derive(Stored, attributes(data_path, data_format, data_version, data_expiry, data_index))]
 pub fn stored(input: TokenStream) -> TokenStream {
     crate::stored::procedure(input)
 }
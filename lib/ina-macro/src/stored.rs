@@ -18,7 +18,9 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{Attribute, DeriveInput, Error, Expr, Ident, LitStr, Result, Token, Type, bracketed, parse_macro_input};
+use syn::{
+    Attribute, DeriveInput, Error, Expr, Ident, LitInt, LitStr, Path, Result, Token, Type, bracketed, parse_macro_input,
+};
 
 /// The `data_format` attribute.
 #[derive(Clone)]
@@ -121,6 +123,124 @@ impl StoredPathAttribute {
     }
 }
 
+/// The `data_version` attribute.
+#[derive(Clone)]
+pub struct StoredVersionAttribute {
+    /// The current data version.
+    pub version: u32,
+    /// The functions that migrate each previous version into the next, ending with the current version.
+    pub migrations: Punctuated<Path, Token![,]>,
+}
+
+impl StoredVersionAttribute {
+    /// Parses the attribute.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the attribute fails to be parsed.
+    pub fn parse(attribute: &Attribute) -> Result<Self> {
+        mod kw {
+            use syn::custom_keyword;
+
+            custom_keyword!(migrate);
+        }
+
+        attribute.parse_args_with(|input: ParseStream| {
+            let literal = input.parse::<LitInt>()?;
+            let version = literal.base10_parse::<u32>()?;
+
+            if version == 0 {
+                return Err(Error::new(literal.span(), "the data version must be at least `1`"));
+            }
+            if input.is_empty() {
+                return Ok(Self { version, migrations: Punctuated::new() });
+            }
+
+            input.parse::<Token![,]>()?;
+            input.parse::<kw::migrate>()?;
+            input.parse::<Token![=]>()?;
+
+            let migrations_input;
+
+            bracketed!(migrations_input in input);
+
+            let migrations = migrations_input.parse_terminated(Path::parse_mod_style, Token![,])?;
+
+            if migrations.len() >= version as usize {
+                return Err(Error::new(literal.span(), "there are more migrations than previous data versions"));
+            }
+
+            Ok(Self { version, migrations })
+        })
+    }
+}
+
+/// The `data_expiry` attribute.
+#[derive(Clone)]
+pub struct StoredExpiryAttribute {
+    /// The default lifetime of stored values in seconds.
+    pub seconds: u64,
+}
+
+impl StoredExpiryAttribute {
+    /// Parses the attribute.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the attribute fails to be parsed.
+    pub fn parse(attribute: &Attribute) -> Result<Self> {
+        attribute.parse_args_with(|input: ParseStream| {
+            let unit = input.parse::<Ident>()?;
+            let multiplier = match unit.to_string().as_str() {
+                "seconds" => 1,
+                "minutes" => 60,
+                "hours" => 60 * 60,
+                "days" => 60 * 60 * 24,
+                _ => return Err(Error::new(unit.span(), "expected `seconds`, `minutes`, `hours`, or `days`")),
+            };
+
+            input.parse::<Token![=]>()?;
+
+            let literal = input.parse::<LitInt>()?;
+            let amount = literal.base10_parse::<u64>()?;
+
+            if amount == 0 {
+                return Err(Error::new(literal.span(), "the data expiry must be at least `1`"));
+            }
+
+            let Some(seconds) = amount.checked_mul(multiplier) else {
+                return Err(Error::new(literal.span(), "the data expiry is too long"));
+            };
+
+            Ok(Self { seconds })
+        })
+    }
+}
+
+/// The `data_index` attribute.
+#[derive(Clone)]
+pub struct StoredIndexAttribute {
+    /// The names of the indexes, each of which is also the name of the method that returns a value's keys.
+    pub names: Punctuated<Ident, Token![,]>,
+}
+
+impl StoredIndexAttribute {
+    /// Parses the attribute.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the attribute fails to be parsed.
+    pub fn parse(attribute: &Attribute) -> Result<Self> {
+        let names = attribute.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+
+        if names.is_empty() {
+            return Err(Error::new_spanned(attribute, "expected at least one index name"));
+        }
+
+        Ok(Self { names })
+    }
+}
+
 /// Applies the procedural macro.
 pub fn procedure(input: TokenStream) -> TokenStream {
     let DeriveInput { attrs: attributes, ident: identifier, generics, .. } = parse_macro_input!(input as DeriveInput);
@@ -143,6 +263,30 @@ pub fn procedure(input: TokenStream) -> TokenStream {
     let path_arguments = path_arguments.iter().collect::<Box<[_]>>();
     let path_fields = path_fields.iter().collect::<Box<[_]>>();
 
+    let version_attribute = attributes.iter().find(|a| a.path().is_ident("data_version"));
+    let version_impl = match version_attribute.map(StoredVersionAttribute::parse).transpose() {
+        Ok(Some(StoredVersionAttribute { version, migrations })) => self::version_impl(version, &migrations),
+        Ok(None) => quote! {},
+        Err(error) => return error.into_compile_error().into(),
+    };
+
+    let expiry_attribute = attributes.iter().find(|a| a.path().is_ident("data_expiry"));
+    let expiry_impl = match expiry_attribute.map(StoredExpiryAttribute::parse).transpose() {
+        Ok(Some(StoredExpiryAttribute { seconds })) => quote! {
+            const DATA_EXPIRY: ::std::option::Option<::std::time::Duration> =
+                ::std::option::Option::Some(::std::time::Duration::from_secs(#seconds));
+        },
+        Ok(None) => quote! {},
+        Err(error) => return error.into_compile_error().into(),
+    };
+
+    let index_attribute = attributes.iter().find(|a| a.path().is_ident("data_index"));
+    let index_impl = match index_attribute.map(StoredIndexAttribute::parse).transpose() {
+        Ok(Some(StoredIndexAttribute { names })) => self::index_impl(&identifier, &names),
+        Ok(None) => quote! {},
+        Err(error) => return error.into_compile_error().into(),
+    };
+
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
     let path_format_arguments = (0 .. path_arguments.len()).map(|n| format_ident!("_{n}")).collect::<Box<[_]>>();
     let format_fn = format_call
@@ -155,6 +299,12 @@ pub fn procedure(input: TokenStream) -> TokenStream {
         {
             type PathArguments = (#(#path_arguments),*);
 
+            #version_impl
+
+            #expiry_impl
+
+            #index_impl
+
             fn data_format() -> impl ::ina_storage::format::DataFormat + ::std::marker::Send {
                 #format_fn
             }
@@ -182,3 +332,62 @@ pub fn procedure(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Returns the secondary index implementation for the given type and index names.
+///
+/// Each index's keys are returned by the method of the same name, which must return an iterator of displayable values.
+fn index_impl(identifier: &Ident, names: &Punctuated<Ident, Token![,]>) -> proc_macro2::TokenStream {
+    let names = names.iter().collect::<Box<[_]>>();
+    let strings = names.iter().map(|name| LitStr::new(&name.to_string(), name.span())).collect::<Box<[_]>>();
+    let kind = LitStr::new(&identifier.to_string(), identifier.span());
+
+    quote! {
+        const DATA_INDICES: &'static [&'static str] = &[#(#strings),*];
+
+        fn data_index_path(index: &str) -> ::std::boxed::Box<::std::path::Path> {
+            ::ina_storage::index::path_for(#kind, index)
+        }
+
+        fn data_index_keys(&self, index: &str) -> ::std::boxed::Box<[::std::boxed::Box<str>]> {
+            match index {
+                #(#strings => ::ina_storage::index::keys(self.#names()),)*
+                _ => ::std::boxed::Box::default(),
+            }
+        }
+    }
+}
+
+/// Returns the data version implementation for the given version and migration functions.
+///
+/// Each migration function accepts the value of the previous version, and the last migration returns the current
+/// version.
+fn version_impl(version: u32, migrations: &Punctuated<Path, Token![,]>) -> proc_macro2::TokenStream {
+    let migrations = migrations.iter().collect::<Box<[_]>>();
+    #[expect(clippy::cast_possible_truncation, reason = "the number of migrations is always less than `u32::MAX`")]
+    let first = version - migrations.len() as u32;
+    let arms = (0 .. migrations.len()).map(|index| {
+        #[expect(clippy::cast_possible_truncation, reason = "the number of migrations is always less than `u32::MAX`")]
+        let from = first + index as u32;
+        let chain = &migrations[index ..];
+
+        quote! {
+            #from => ::std::option::Option::Some(format.decode(bytes)#(.map(#chain))*),
+        }
+    });
+
+    quote! {
+        const DATA_VERSION: u32 = #version;
+
+        fn data_decode<F: ::ina_storage::format::DataDecode>(
+            format: &F,
+            version: u32,
+            bytes: &[u8],
+        ) -> ::std::option::Option<::std::result::Result<Self, <F as ::ina_storage::format::DataDecode>::Error>> {
+            match version {
+                #version => ::std::option::Option::Some(format.decode(bytes)),
+                #(#arms)*
+                _ => ::std::option::Option::None,
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+//! Benchmarks the throughput of the storage thread under many simultaneous requests.
+
+use std::hint::black_box;
+use std::num::NonZero;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_main};
+use ina_storage::batch::Operation;
+use ina_storage::settings::OptionalSettings;
+use ina_storage::system::DataStream;
+use ina_storage::{System, thread};
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+/// The number of requests sent during each iteration.
+const REQUESTS: usize = 256;
+
+/// Returns the path of the entry with the given index.
+fn path_for(index: usize) -> Box<Path> {
+    PathBuf::from(format!("bench/{index}")).into_boxed_path()
+}
+
+/// Returns a request that writes some bytes into the entry with the given index.
+fn write_for(index: usize) -> Box<[Operation]> {
+    Box::new([Operation::Write(self::path_for(index), Arc::from(index.to_le_bytes().as_slice()))])
+}
+
+/// Returns the path of an entry next to the benchmarked entry with the given index, and a stream of bytes to write
+/// into it.
+fn stream_for(index: usize) -> (Box<Path>, DataStream) {
+    let path = PathBuf::from(format!("bench/other/{index}")).into_boxed_path();
+
+    (path, DataStream::from_bytes(Arc::from(index.to_le_bytes().as_slice())))
+}
+
+/// Returns the directory in which benchmarked entries are stored.
+fn directory() -> PathBuf {
+    std::env::temp_dir().join(format!("ina-storage-bench-{}", std::process::id()))
+}
+
+/// Starts the storage thread using the given system, populating every benchmarked entry.
+///
+/// The cache is disabled so that every read is handled by the storage system.
+fn start(system: System) -> anyhow::Result<()> {
+    let Some(queue_capacity) = NonZero::new(REQUESTS) else { unreachable!() };
+    let mut settings = OptionalSettings::default().fill_defaults();
+
+    settings.system = system;
+    settings.directory = self::directory();
+    settings.queue_capacity = queue_capacity;
+    settings.cache_entries = 0;
+
+    thread::blocking_start(settings)?;
+    thread::blocking_batch((0 .. REQUESTS).flat_map(self::write_for).collect())?;
+
+    Ok(())
+}
+
+/// Benchmarks the given request when sent one at a time against when sent simultaneously.
+fn compare<F, O, T>(c: &mut Criterion, runtime: &Runtime, name: &str, request: F)
+where
+    F: Fn(usize) -> O,
+    O: Future<Output = anyhow::Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut group = c.benchmark_group(name);
+
+    group.throughput(Throughput::Elements(REQUESTS as u64));
+    group.bench_function(BenchmarkId::new("sequential", REQUESTS), |b| {
+        b.to_async(runtime).iter(|| async {
+            for index in 0 .. REQUESTS {
+                black_box(request(index).await.ok());
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("concurrent", REQUESTS), |b| {
+        b.to_async(runtime).iter(|| async {
+            // Each request is spawned as its own task, much like simultaneous interactions.
+            let mut tasks = (0 .. REQUESTS).map(&request).collect::<JoinSet<_>>();
+
+            while let Some(result) = tasks.join_next().await {
+                black_box(result.ok().and_then(Result::ok));
+            }
+        });
+    });
+    group.finish();
+}
+
+/// Benchmarks reads of the benchmarked entries while the same number of writes into other entries are handled.
+///
+/// Only the reads are timed, so this measures how long writes hold up requests that do not access the same data.
+fn contend(c: &mut Criterion, runtime: &Runtime, name: &str) {
+    let mut group = c.benchmark_group(name);
+
+    group.throughput(Throughput::Elements(REQUESTS as u64));
+    group.bench_function(BenchmarkId::new("reads", REQUESTS), |b| {
+        b.to_async(runtime).iter_custom(|iterations| async move {
+            let mut elapsed = Duration::ZERO;
+
+            for _ in 0 .. iterations {
+                let writes = (0 .. REQUESTS)
+                    .map(|index| {
+                        let (path, stream) = self::stream_for(index);
+
+                        thread::write_stream(path, stream)
+                    })
+                    .collect::<JoinSet<_>>();
+
+                let instant = Instant::now();
+                let mut reads =
+                    (0 .. REQUESTS).map(|index| thread::read_bytes(self::path_for(index))).collect::<JoinSet<_>>();
+
+                while let Some(result) = reads.join_next().await {
+                    black_box(result.ok().and_then(Result::ok));
+                }
+
+                elapsed += instant.elapsed();
+
+                black_box(writes.join_all().await);
+            }
+
+            elapsed
+        });
+    });
+    group.finish();
+}
+
+/// Benchmarks the throughput of reads and of writes into distinct paths using each storage system.
+#[expect(clippy::panic, reason = "nothing can be benchmarked without the storage thread")]
+fn throughput(c: &mut Criterion) {
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(error) => panic!("failed to build runtime: {error}"),
+    };
+
+    for system in [System::File, System::Memory] {
+        if let Err(error) = self::start(system) {
+            panic!("failed to start storage thread: {error}");
+        }
+
+        self::compare(c, &runtime, &format!("{system}/reads"), |index| thread::read_bytes(self::path_for(index)));
+        self::compare(c, &runtime, &format!("{system}/writes"), |index| thread::batch(self::write_for(index)));
+        self::contend(c, &runtime, &format!("{system}/contended"));
+
+        thread::blocking_close();
+    }
+
+    if let Err(error) = std::fs::remove_dir_all(self::directory()) {
+        eprintln!("failed to remove benchmark directory: {error}");
+    }
+}
+
+/// Contains the benchmark group, which is generated by criterion.
+mod group {
+    criterion::criterion_group!(benches, super::throughput);
+}
+
+criterion_main!(group::benches);
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::sync::Notify;
+
+/// The stored data that a request accesses while it is handled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Accesses no stored data.
+    Nothing,
+    /// Reads the data at the given paths.
+    Read(Box<[Box<Path>]>),
+    /// Modifies the data at the given paths.
+    Write(Box<[Box<Path>]>),
+    /// Reads any stored data.
+    ReadAll,
+    /// Modifies any stored data.
+    WriteAll,
+}
+
+impl Access {
+    /// Returns an access that reads the data at the given path.
+    #[must_use]
+    pub fn read(path: &Path) -> Self {
+        Self::Read(Box::new([path.into()]))
+    }
+
+    /// Returns an access that modifies the data at the given path.
+    #[must_use]
+    pub fn write(path: &Path) -> Self {
+        Self::Write(Box::new([path.into()]))
+    }
+
+    /// Returns whether this access and the given access may not be granted at the same time.
+    fn conflicts(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Nothing, _)
+            | (_, Self::Nothing)
+            | (Self::Read(_) | Self::ReadAll, Self::Read(_) | Self::ReadAll) => false,
+            (Self::WriteAll, _)
+            | (_, Self::WriteAll)
+            | (Self::ReadAll, Self::Write(_))
+            | (Self::Write(_), Self::ReadAll) => true,
+            (Self::Read(paths), Self::Write(other_paths))
+            | (Self::Write(paths), Self::Read(other_paths) | Self::Write(other_paths)) => {
+                paths.iter().any(|path| other_paths.contains(path))
+            }
+        }
+    }
+}
+
+/// The accesses that have been registered and not yet released, keyed by the order in which they were registered.
+#[derive(Debug, Default)]
+struct Queue {
+    /// The number of the next registered access.
+    next: u64,
+    /// The registered accesses.
+    accesses: BTreeMap<u64, Access>,
+}
+
+/// The locks that order access to stored paths.
+///
+/// Accesses are registered in the order that requests are admitted, and each access is granted once every earlier
+/// access that conflicts with it has been released. Reads of a path are granted concurrently, while modifications of a
+/// path wait for every earlier access of that path, and every later access of that path waits for them. An access of
+/// all stored data waits for, and holds up, every conflicting access of any path.
+///
+/// An access only ever waits for earlier accesses that conflict with it, so an access that is still waiting does not
+/// hold up later accesses of other paths.
+#[derive(Debug, Default)]
+pub(crate) struct Locks {
+    /// The accesses that have not yet been released.
+    queue: Arc<Mutex<Queue>>,
+    /// Notifies waiting accesses that an access was released.
+    released: Arc<Notify>,
+}
+
+impl Locks {
+    /// Registers the given access, returning a ticket that is granted once every conflicting access that was
+    /// registered before it is released.
+    pub fn register(&self, access: Access) -> Ticket {
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        let number = queue.next;
+
+        queue.next += 1;
+        queue.accesses.insert(number, access);
+
+        drop(queue);
+
+        Ticket { number, queue: Arc::clone(&self.queue), released: Arc::clone(&self.released) }
+    }
+}
+
+/// A registered access, which is released when dropped.
+#[derive(Debug)]
+pub(crate) struct Ticket {
+    /// The order in which the access was registered.
+    number: u64,
+    /// The accesses that have not yet been released.
+    queue: Arc<Mutex<Queue>>,
+    /// Notifies waiting accesses that an access was released.
+    released: Arc<Notify>,
+}
+
+impl Ticket {
+    /// Waits until every conflicting access that was registered before this access is released.
+    pub async fn granted(&self) {
+        loop {
+            let released = self.released.notified();
+            let mut released = std::pin::pin!(released);
+
+            // The notification is enabled before checking, so that no release is missed in between.
+            released.as_mut().enable();
+
+            if self.is_granted() {
+                return;
+            }
+
+            released.await;
+        }
+    }
+
+    /// Returns whether every conflicting access that was registered before this access has been released.
+    fn is_granted(&self) -> bool {
+        let queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(access) = queue.accesses.get(&self.number) else { return true };
+
+        !queue.accesses.range(.. self.number).any(|(_, earlier)| earlier.conflicts(access))
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner).accesses.remove(&self.number);
+        self.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Access, Locks};
+
+    #[test]
+    fn order_conflicting_access() -> anyhow::Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build()?;
+
+        runtime.block_on(async {
+            let locks = Locks::default();
+            let timeout = std::time::Duration::from_millis(10);
+
+            let first = locks.register(Access::read(Path::new("a")));
+            let second = locks.register(Access::read(Path::new("a")));
+            let other = locks.register(Access::write(Path::new("b")));
+            let write = locks.register(Access::Write(Box::new([Path::new("a").into(), Path::new("a").into()])));
+            let later = locks.register(Access::read(Path::new("c")));
+
+            assert!(tokio::time::timeout(timeout, first.granted()).await.is_ok());
+            assert!(tokio::time::timeout(timeout, second.granted()).await.is_ok());
+            assert!(tokio::time::timeout(timeout, other.granted()).await.is_ok());
+
+            // Modifications wait for earlier reads of the same path, without holding up later accesses of other paths.
+            assert!(tokio::time::timeout(timeout, write.granted()).await.is_err());
+            assert!(tokio::time::timeout(timeout, later.granted()).await.is_ok());
+
+            let all = locks.register(Access::ReadAll);
+
+            drop((first, second, later));
+
+            assert!(tokio::time::timeout(timeout, write.granted()).await.is_ok());
+
+            // Reads of all data wait for modifications of any path.
+            assert!(tokio::time::timeout(timeout, all.granted()).await.is_err());
+
+            drop((write, other));
+
+            assert!(tokio::time::timeout(timeout, all.granted()).await.is_ok());
+
+            let everything = locks.register(Access::WriteAll);
+            let nothing = locks.register(Access::Nothing);
+
+            // Accesses of no stored data never wait.
+            assert!(tokio::time::timeout(timeout, everything.granted()).await.is_err());
+            assert!(tokio::time::timeout(timeout, nothing.granted()).await.is_ok());
+
+            drop(all);
+
+            assert!(tokio::time::timeout(timeout, everything.granted()).await.is_ok());
+
+            drop((everything, nothing));
+
+            assert!(locks.queue.lock().is_ok_and(|queue| queue.accesses.is_empty()));
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::batch::Operation;
+
+/// The current version of the archive format.
+pub const VERSION: u32 = 1;
+
+/// The name of the manifest file within an archive.
+const MANIFEST_NAME: &str = "manifest.toml";
+/// The directory within an archive that holds each entry's data.
+const DATA_DIRECTORY: &str = "data";
+
+/// The data of each entry within an archive, keyed by its storage path.
+pub(crate) type EntryData = HashMap<Box<Path>, Vec<u8>>;
+
+/// An error that can be returned when exporting or importing an archive.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The manifest could not be serialized.
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+    /// The manifest could not be deserialized.
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+    /// The archive does not contain a manifest.
+    #[error("missing archive manifest")]
+    MissingManifest,
+    /// The archive uses a version that cannot be imported.
+    #[error("unsupported archive version {0}")]
+    UnsupportedVersion(u32),
+    /// An entry's path is not a relative path within the data directory.
+    #[error("invalid entry path '{0}'")]
+    InvalidPath(Box<Path>),
+    /// An entry listed within the manifest is missing from the archive.
+    #[error("missing entry '{0}'")]
+    MissingEntry(Box<Path>),
+    /// An entry's data does not match its checksum.
+    #[error("checksum mismatch for entry '{0}'")]
+    ChecksumMismatch(Box<Path>),
+    /// An entry was exported in plain text, but encryption is not enabled.
+    #[error("entry '{0}' must be encrypted, but encryption is not enabled")]
+    EncryptionDisabled(Box<Path>),
+}
+
+/// An archive's manifest, which describes every entry within the archive.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    /// The version of the archive format.
+    pub version: u32,
+    /// The time at which the archive was created, in seconds since the Unix epoch.
+    pub created: u64,
+    /// The archive's entries.
+    #[serde(default)]
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// A single entry within an archive's manifest.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ManifestEntry {
+    /// The entry's storage path, relative to the data directory.
+    pub path: Box<Path>,
+    /// The entry's format chain, ordered from the innermost to the outermost format.
+    pub formats: Box<[Box<str>]>,
+    /// The size of the entry's archived data in bytes.
+    pub size: u64,
+    /// The SHA-256 checksum of the entry's archived data.
+    pub checksum: Box<str>,
+    /// Whether the entry's outermost encryption was removed during export.
+    #[serde(default)]
+    pub decrypted: bool,
+}
+
+impl ManifestEntry {
+    /// Creates a new [`ManifestEntry`] describing the given archived data.
+    fn new(path: Box<Path>, bytes: &[u8], decrypted: bool) -> Self {
+        let formats = path
+            .file_name()
+            .map(|name| name.to_string_lossy().split('.').skip(1).map(Into::into).collect())
+            .unwrap_or_default();
+
+        Self { path, formats, size: bytes.len() as u64, checksum: self::checksum(bytes), decrypted }
+    }
+}
+
+/// Exports every stored entry into an archive at the given path.
+///
+/// If `decrypt` is set, encrypted entries are stored in plain text so that they can be imported using a different
+/// encryption key.
+///
+/// # Errors
+///
+/// This function will return an error if the data could not be read or the archive could not be written.
+pub async fn export(destination: &Path, decrypt: bool) -> anyhow::Result<Manifest> {
+    let paths = crate::thread::list(Path::new("").into()).await?;
+    let mut entries = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let bytes = crate::thread::read_bytes(path.clone()).await?;
+
+        entries.push(self::export_entry(path, bytes, decrypt)?);
+    }
+
+    let destination = destination.to_path_buf();
+
+    tokio::task::spawn_blocking(move || self::write_archive(&destination, &entries)).await?.map_err(Into::into)
+}
+
+/// Exports every stored entry into an archive at the given path.
+///
+/// If `decrypt` is set, encrypted entries are stored in plain text so that they can be imported using a different
+/// encryption key.
+///
+/// # Errors
+///
+/// This function will return an error if the data could not be read or the archive could not be written.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+pub fn blocking_export(destination: &Path, decrypt: bool) -> anyhow::Result<Manifest> {
+    let paths = crate::thread::blocking_list(Path::new("").into())?;
+    let mut entries = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let bytes = crate::thread::blocking_read_bytes(path.clone())?;
+
+        entries.push(self::export_entry(path, bytes, decrypt)?);
+    }
+
+    self::write_archive(destination, &entries).map_err(Into::into)
+}
+
+/// Imports every entry from the archive at the given path into the storage system.
+///
+/// Entries that were decrypted during export are encrypted using the current key. If `reencrypt` is set, entries that
+/// are still encrypted are also re-encrypted using the current key. The import is all-or-nothing.
+///
+/// # Errors
+///
+/// This function will return an error if the archive could not be read or verified, or if the data could not be
+/// written.
+pub async fn import(source: &Path, reencrypt: bool) -> anyhow::Result<Manifest> {
+    let source = source.to_path_buf();
+    let (manifest, entries) = tokio::task::spawn_blocking(move || self::read_archive(&source)).await??;
+    let operations = self::import_operations(&manifest, entries, reencrypt)?;
+
+    crate::thread::batch(operations).await?;
+
+    Ok(manifest)
+}
+
+/// Imports every entry from the archive at the given path into the storage system.
+///
+/// Entries that were decrypted during export are encrypted using the current key. If `reencrypt` is set, entries that
+/// are still encrypted are also re-encrypted using the current key. The import is all-or-nothing.
+///
+/// # Errors
+///
+/// This function will return an error if the archive could not be read or verified, or if the data could not be
+/// written.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+pub fn blocking_import(source: &Path, reencrypt: bool) -> anyhow::Result<Manifest> {
+    let (manifest, entries) = self::read_archive(source)?;
+    let operations = self::import_operations(&manifest, entries, reencrypt)?;
+
+    crate::thread::blocking_batch(operations)?;
+
+    Ok(manifest)
+}
+
+/// Returns the hexadecimal SHA-256 checksum of the given bytes.
+fn checksum(bytes: &[u8]) -> Box<str> {
+    format!("{:x}", Sha256::digest(bytes)).into_boxed_str()
+}
+
+/// Prepares the given stored bytes for export, decrypting them if requested.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be decrypted.
+#[cfg_attr(not(feature = "format-encryption"), expect(clippy::unnecessary_wraps, reason = "used when enabled"))]
+pub(crate) fn export_entry(
+    path: Box<Path>,
+    bytes: Arc<[u8]>,
+    decrypt: bool,
+) -> anyhow::Result<(ManifestEntry, Arc<[u8]>)> {
+    #[cfg(feature = "format-encryption")]
+    if decrypt && crate::format::encryption::is_encrypted(&path) {
+        let (header, inner) = crate::stored::split_header(&bytes);
+        let bytes: Arc<[u8]> = [header, &crate::format::encryption::decrypt(inner)?].concat().into();
+
+        return Ok((ManifestEntry::new(path, &bytes, true), bytes));
+    }
+    #[cfg(not(feature = "format-encryption"))]
+    let _ = decrypt;
+
+    Ok((ManifestEntry::new(path, &bytes, false), bytes))
+}
+
+/// Converts the given archived entries into the operations that write them into the storage system.
+///
+/// # Errors
+///
+/// This function will return an error if an entry could not be encrypted.
+pub(crate) fn import_operations(
+    manifest: &Manifest,
+    mut entries: EntryData,
+    reencrypt: bool,
+) -> anyhow::Result<Box<[Operation]>> {
+    let mut operations = Vec::with_capacity(manifest.entries.len());
+
+    for entry in &manifest.entries {
+        let Some(bytes) = entries.remove(&entry.path) else { unreachable!("entries are verified when read") };
+        let bytes = self::import_entry(entry, bytes, reencrypt)?;
+
+        operations.push(Operation::Write(entry.path.clone(), bytes));
+    }
+
+    debug!(count = operations.len(), "prepared archived entries");
+
+    Ok(operations.into_boxed_slice())
+}
+
+/// Prepares the given archived bytes for import, encrypting them if necessary.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be encrypted.
+fn import_entry(entry: &ManifestEntry, bytes: Vec<u8>, reencrypt: bool) -> anyhow::Result<Arc<[u8]>> {
+    #[cfg(feature = "format-encryption")]
+    if entry.decrypted || (reencrypt && crate::format::encryption::is_encrypted(&entry.path)) {
+        let (header, inner) = crate::stored::split_header(&bytes);
+        let inner = if entry.decrypted {
+            crate::format::encryption::encrypt(inner)?
+        } else {
+            crate::format::encryption::reencrypt(inner)?.unwrap_or_else(|| inner.to_vec())
+        };
+
+        return Ok([header, &inner].concat().into());
+    }
+    #[cfg(not(feature = "format-encryption"))]
+    {
+        let _ = reencrypt;
+
+        if entry.decrypted {
+            return Err(Error::EncryptionDisabled(entry.path.clone()).into());
+        }
+    }
+
+    Ok(bytes.into())
+}
+
+/// Writes the given entries into a new archive at the given path, returning the archive's manifest.
+///
+/// # Errors
+///
+/// This function will return an error if the archive could not be written.
+pub(crate) fn write_archive(destination: &Path, entries: &[(ManifestEntry, Arc<[u8]>)]) -> Result<Manifest, Error> {
+    let created = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    let manifest =
+        Manifest { version: VERSION, created, entries: entries.iter().map(|(entry, _)| entry.clone()).collect() };
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let encoder = GzEncoder::new(BufWriter::new(File::create(destination)?), Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    self::append(&mut builder, Path::new(MANIFEST_NAME), toml::to_string_pretty(&manifest)?.as_bytes(), created)?;
+
+    for (entry, bytes) in entries {
+        self::append(&mut builder, &Path::new(DATA_DIRECTORY).join(&entry.path), bytes, created)?;
+    }
+
+    builder.into_inner()?.finish()?;
+
+    debug!(path = ?destination, count = manifest.entries.len(), "wrote archive");
+
+    Ok(manifest)
+}
+
+/// Appends a file containing the given bytes to the given archive builder.
+///
+/// # Errors
+///
+/// This function will return an error if the file could not be appended.
+fn append<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &Path,
+    bytes: &[u8],
+    modified: u64,
+) -> Result<(), Error> {
+    let mut header = tar::Header::new_gnu();
+
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(modified);
+
+    builder.append_data(&mut header, path, bytes).map_err(Into::into)
+}
+
+/// Reads the archive at the given path, returning its manifest and the verified data of each entry.
+///
+/// # Errors
+///
+/// This function will return an error if the archive could not be read or any entry could not be verified.
+pub(crate) fn read_archive(source: &Path) -> Result<(Manifest, EntryData), Error> {
+    let decoder = GzDecoder::new(BufReader::new(File::open(source)?));
+    let mut archive = tar::Archive::new(decoder);
+    let mut manifest = None;
+    let mut entries = HashMap::new();
+
+    for file in archive.entries()? {
+        let mut file = file?;
+        let path = file.path()?.into_owned();
+        let mut bytes = Vec::with_capacity(usize::try_from(file.size()).unwrap_or_default());
+
+        file.read_to_end(&mut bytes)?;
+
+        if path == Path::new(MANIFEST_NAME) {
+            manifest = Some(toml::from_slice::<Manifest>(&bytes)?);
+        } else if let Ok(path) = path.strip_prefix(DATA_DIRECTORY) {
+            entries.insert(path.into(), bytes);
+        }
+    }
+
+    let manifest = manifest.ok_or(Error::MissingManifest)?;
+
+    if manifest.version != VERSION {
+        return Err(Error::UnsupportedVersion(manifest.version));
+    }
+
+    for entry in &manifest.entries {
+        if !entry.path.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(Error::InvalidPath(entry.path.clone()));
+        }
+
+        let Some(bytes) = entries.get(&entry.path) else {
+            return Err(Error::MissingEntry(entry.path.clone()));
+        };
+
+        if bytes.len() as u64 != entry.size || *self::checksum(bytes) != *entry.checksum {
+            return Err(Error::ChecksumMismatch(entry.path.clone()));
+        }
+    }
+
+    debug!(path = ?source, count = manifest.entries.len(), "read archive");
+
+    Ok((manifest, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::ManifestEntry;
+
+    #[test]
+    fn round_trip_archive() -> anyhow::Result<()> {
+        let destination = std::env::temp_dir().join(format!("ina-archive-{}.tar.gz", std::process::id()));
+        let bytes: Arc<[u8]> = Arc::from(&b"1N4V\x01\x00\x00\x00data"[..]);
+        let entry = ManifestEntry::new(Path::new("role/1/2.pack.gz").into(), &bytes, false);
+
+        assert_eq!(&*entry.formats, &["pack".into(), "gz".into()]);
+
+        let written = super::write_archive(&destination, &[(entry, Arc::clone(&bytes))])?;
+        let (read, entries) = super::read_archive(&destination)?;
+
+        std::fs::remove_file(&destination)?;
+
+        assert_eq!(written, read);
+        assert_eq!(entries.get(Path::new("role/1/2.pack.gz")).map(Vec::as_slice), Some(&*bytes));
+
+        Ok(())
+    }
+}
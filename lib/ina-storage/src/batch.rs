@@ -0,0 +1,376 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use tracing::{debug, warn};
+
+use crate::Storage;
+use crate::index::IndexChange;
+use crate::stored::Stored;
+use crate::system::{DataReader, DataWriter};
+
+/// A single operation within a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Writes bytes into the given path.
+    Write(Box<Path>, Arc<[u8]>),
+    /// Renames the bytes to be associated with a new path.
+    Rename(Box<Path>, Box<Path>),
+    /// Deletes the data at the given path.
+    Delete(Box<Path>),
+    /// Applies a change to the entry of the data at the given path within the given secondary index.
+    Index(Box<Path>, Box<Path>, IndexChange),
+}
+
+impl Operation {
+    /// Returns the paths that are modified by this operation.
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &Path> {
+        let (first, second) = match self {
+            Self::Write(path, _) | Self::Delete(path) | Self::Index(path, ..) => (&**path, None),
+            Self::Rename(from, into) => (&**from, Some(&**into)),
+        };
+
+        std::iter::once(first).chain(second)
+    }
+}
+
+/// A set of operations that are applied all-or-nothing by the storage thread.
+///
+/// If any operation fails, every path touched by the batch is restored to the state it was in before the batch was
+/// applied.
+#[must_use = "batches do nothing unless committed"]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Batch {
+    /// The batch's operations, in the order that they are applied.
+    operations: Vec<Operation>,
+}
+
+impl Batch {
+    /// Creates a new, empty [`Batch`].
+    pub const fn new() -> Self {
+        Self { operations: Vec::new() }
+    }
+
+    /// Returns the batch's operations.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns `true` if the batch contains no operations.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Adds the given operation to the batch.
+    pub fn push(mut self, operation: Operation) -> Self {
+        self.operations.push(operation);
+
+        self
+    }
+
+    /// Writes the given value at the path represented by the given path arguments.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value could not be encoded.
+    pub fn write<T: Stored>(self, arguments: T::PathArguments, value: &T) -> anyhow::Result<Self> {
+        let path = crate::stored::path_for::<T>(arguments);
+        let bytes = crate::stored::encode(value, crate::stored::expiry_after(T::DATA_EXPIRY))?;
+
+        Ok(self.write_at(&path, bytes, value))
+    }
+
+    /// Renames the value represented by the given path arguments.
+    pub fn rename<T: Stored>(self, from: T::PathArguments, into: T::PathArguments) -> Self {
+        self.rename_at::<T>(&crate::stored::path_for::<T>(from), &crate::stored::path_for::<T>(into))
+    }
+
+    /// Deletes the value represented by the given path arguments.
+    pub fn delete<T: Stored>(self, arguments: T::PathArguments) -> Self {
+        self.delete_at::<T>(&crate::stored::path_for::<T>(arguments))
+    }
+
+    /// Writes the given encoded value into the given path, updating its type's secondary indexes.
+    pub(crate) fn write_at<T: Stored>(self, path: &Path, bytes: Arc<[u8]>, value: &T) -> Self {
+        self.push(Operation::Write(path.into(), bytes))
+            .index::<T>(path, |index| IndexChange::Insert(value.data_index_keys(index)))
+    }
+
+    /// Renames the value at the given path, updating its type's secondary indexes.
+    pub(crate) fn rename_at<T: Stored>(self, from: &Path, into: &Path) -> Self {
+        self.push(Operation::Rename(from.into(), into.into())).index::<T>(from, |_| IndexChange::Rename(into.into()))
+    }
+
+    /// Deletes the value at the given path, updating its type's secondary indexes.
+    pub(crate) fn delete_at<T: Stored>(self, path: &Path) -> Self {
+        self.push(Operation::Delete(path.into())).index::<T>(path, |_| IndexChange::Remove)
+    }
+
+    /// Applies the change returned by the given function to the entry of the given path within each of the type's
+    /// secondary indexes.
+    fn index<T: Stored>(self, path: &Path, change: impl Fn(&str) -> IndexChange) -> Self {
+        T::DATA_INDICES.iter().fold(self, |batch, index| {
+            batch.push(Operation::Index(T::data_index_path(index), path.into(), change(index)))
+        })
+    }
+
+    /// Applies every operation within the batch.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or any operation failed, in which case
+    /// no changes are kept.
+    pub async fn commit(self) -> anyhow::Result<()> {
+        crate::thread::batch(self.operations.into_boxed_slice()).await
+    }
+
+    /// Applies every operation within the batch.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or any operation failed, in which case
+    /// no changes are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous context.
+    pub fn blocking_commit(self) -> anyhow::Result<()> {
+        crate::thread::blocking_batch(self.operations.into_boxed_slice())
+    }
+}
+
+/// The state of a path touched by a batch before it was applied.
+#[derive(Debug)]
+struct JournalEntry {
+    /// The touched path.
+    path: Box<Path>,
+    /// The original bytes of the path, or [`None`] if the path did not exist.
+    bytes: Option<Arc<[u8]>>,
+}
+
+/// The state of each path touched by a batch before it was applied.
+#[derive(Debug, Default)]
+struct Journal {
+    /// The journal's entries.
+    entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Returns `true` if the journal already contains the original state of the given path.
+    fn contains(&self, path: &Path) -> bool {
+        self.entries.iter().any(|entry| &*entry.path == path)
+    }
+}
+
+impl Storage {
+    /// Applies the given operations all-or-nothing.
+    ///
+    /// The original state of each touched path is only kept in memory, so a crash while the operations are applied
+    /// leaves them partially applied. Batches committed through the storage thread are recovered by replaying them from
+    /// its [journal](crate::journal) when the thread is next started; batches applied directly are not recovered.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any operation failed, in which case all applied operations are reverted.
+    /// Reverted data is restored regardless of its group's hard quota, as it already counted against the quota.
+    pub fn blocking_apply(&self, operations: &[Operation]) -> anyhow::Result<()> {
+        let mut journal = Journal::default();
+
+        for operation in operations {
+            for path in operation.paths() {
+                if journal.contains(path) {
+                    continue;
+                }
+
+                let bytes = if self.blocking_exists(path)? { Some(self.blocking_read(path)?) } else { None };
+
+                journal.entries.push(JournalEntry { path: path.into(), bytes });
+            }
+
+            let result = match operation {
+                Operation::Write(path, bytes) => self.blocking_write(path, bytes),
+                Operation::Rename(from, into) => self.blocking_rename(from, into),
+                Operation::Delete(path) => self.blocking_delete(path),
+                Operation::Index(index, path, change) => self.blocking_update_index(index, path, change.clone()),
+            };
+
+            if let Err(error) = result {
+                self.blocking_revert(journal);
+
+                return Err(error);
+            }
+        }
+
+        debug!(count = operations.len(), "applied batch");
+
+        Ok(())
+    }
+
+    /// Applies the given operations all-or-nothing.
+    ///
+    /// The original state of each touched path is only kept in memory, so a crash while the operations are applied
+    /// leaves them partially applied. Batches committed through the storage thread are recovered by replaying them from
+    /// its [journal](crate::journal) when the thread is next started; batches applied directly are not recovered.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any operation failed, in which case all applied operations are reverted.
+    /// Reverted data is restored regardless of its group's hard quota, as it already counted against the quota.
+    pub async fn apply(&self, operations: &[Operation]) -> anyhow::Result<()> {
+        let mut journal = Journal::default();
+
+        for operation in operations {
+            for path in operation.paths() {
+                if journal.contains(path) {
+                    continue;
+                }
+
+                let bytes = if self.exists(path).await? { Some(self.read(path).await?) } else { None };
+
+                journal.entries.push(JournalEntry { path: path.into(), bytes });
+            }
+
+            let result = match operation {
+                Operation::Write(path, bytes) => self.write(path, bytes).await,
+                Operation::Rename(from, into) => self.rename(from, into).await,
+                Operation::Delete(path) => self.delete(path).await,
+                Operation::Index(index, path, change) => self.update_index(index, path, change.clone()).await,
+            };
+
+            if let Err(error) = result {
+                self.revert(journal).await;
+
+                return Err(error);
+            }
+        }
+
+        debug!(count = operations.len(), "applied batch");
+
+        Ok(())
+    }
+
+    /// Restores every path within the given journal to its original state.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    fn blocking_revert(&self, journal: Journal) {
+        for JournalEntry { path, bytes } in journal.entries {
+            let result = match bytes {
+                Some(bytes) => self.blocking_write_with(&path, &bytes, false),
+                None if self.blocking_exists(&path).unwrap_or(true) => self.blocking_delete(&path),
+                None => Ok(()),
+            };
+
+            // Continue restoring the remaining paths so that as much data as possible is recovered.
+            if let Err(error) = result {
+                warn!(?path, %error, "failed to revert batched operation");
+            }
+        }
+
+        debug!("reverted batch");
+    }
+
+    /// Restores every path within the given journal to its original state.
+    async fn revert(&self, journal: Journal) {
+        for JournalEntry { path, bytes } in journal.entries {
+            let result = match bytes {
+                Some(bytes) => self.write_with(&path, &bytes, false).await,
+                None if self.exists(&path).await.unwrap_or(true) => self.delete(&path).await,
+                None => Ok(()),
+            };
+
+            // Continue restoring the remaining paths so that as much data as possible is recovered.
+            if let Err(error) = result {
+                warn!(?path, %error, "failed to revert batched operation");
+            }
+        }
+
+        debug!("reverted batch");
+    }
+}
+
+#[cfg(all(test, feature = "system-memory"))]
+mod tests {
+    use std::num::NonZero;
+    use std::path::Path;
+
+    use super::Operation;
+    use crate::Storage;
+    use crate::system::{DataReader, DataWriter};
+
+    #[test]
+    fn revert_failed_batch() -> anyhow::Result<()> {
+        let storage = Storage::new(crate::settings::test_settings("batch"));
+
+        storage.blocking_write(Path::new("a"), b"old")?;
+
+        let result = storage.blocking_apply(&[
+            Operation::Write(Path::new("a").into(), b"new".as_slice().into()),
+            Operation::Write(Path::new("b").into(), b"new".as_slice().into()),
+            Operation::Delete(Path::new("missing").into()),
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(&*storage.blocking_read(Path::new("a"))?, b"old");
+        assert!(!storage.blocking_exists(Path::new("b"))?);
+
+        storage.blocking_apply(&[Operation::Rename(Path::new("a").into(), Path::new("c").into())])?;
+
+        assert!(!storage.blocking_exists(Path::new("a"))?);
+        assert_eq!(&*storage.blocking_read(Path::new("c"))?, b"old");
+
+        Ok(())
+    }
+
+    #[test]
+    fn revert_regardless_of_quota() -> anyhow::Result<()> {
+        let mut settings = crate::settings::test_settings("batch-quota");
+
+        settings.hard_quota = NonZero::new(3);
+
+        let storage = Storage::new(settings);
+
+        storage.blocking_write(Path::new("role/1/a"), b"old")?;
+
+        // Shrinking the first value makes room for the second, so reverting the first would exceed the quota if it
+        // was enforced.
+        let result = storage.blocking_apply(&[
+            Operation::Write(Path::new("role/1/a").into(), b"n".as_slice().into()),
+            Operation::Write(Path::new("role/1/b").into(), b"ew".as_slice().into()),
+            Operation::Delete(Path::new("role/1/missing").into()),
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(&*storage.blocking_read(Path::new("role/1/a"))?, b"old");
+        assert!(!storage.blocking_exists(Path::new("role/1/b"))?);
+        assert_eq!(storage.blocking_usage("1")?.bytes, 3);
+
+        Ok(())
+    }
+}
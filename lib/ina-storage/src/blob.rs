@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::Storage;
+use crate::index::INDEX_DIRECTORY;
+use crate::system::{DataReader, DataWriter};
+
+/// The directory within which blobs are stored.
+pub const BLOB_DIRECTORY: &str = "blob";
+/// The name of the secondary index that records the blobs referenced by stored values.
+///
+/// A stored type references blobs by declaring a secondary index with this name, whose keys are the identifiers of the
+/// blobs that each value references.
+pub const BLOB_INDEX: &str = "blobs";
+
+/// An error returned when parsing a blob identifier.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("invalid blob identifier '{0}'")]
+pub struct InvalidBlobId(Box<str>);
+
+/// The identifier of a stored blob, which is the SHA-256 hash of its bytes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlobId([u8; 32]);
+
+impl BlobId {
+    /// Returns the identifier of the given bytes.
+    #[must_use]
+    pub fn of(bytes: &[u8]) -> Self {
+        Self(Sha256::digest(bytes).into())
+    }
+
+    /// Returns the identifier of the blob stored at the given path, or [`None`] if the path does not name a blob.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let parent = path.parent()?;
+
+        (parent == Path::new(BLOB_DIRECTORY)).then_some(())?;
+
+        path.file_name().and_then(OsStr::to_str)?.parse().ok()
+    }
+
+    /// Returns the path that the blob is stored at.
+    #[must_use]
+    pub fn path(&self) -> Box<Path> {
+        Path::new(BLOB_DIRECTORY).join(self.to_string()).into_boxed_path()
+    }
+}
+
+impl Display for BlobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl FromStr for BlobId {
+    type Err = InvalidBlobId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidBlobId(s.into());
+        let mut bytes = [0; 32];
+
+        if !s.is_ascii() || s.len() != bytes.len() * 2 {
+            return Err(invalid());
+        }
+
+        for (byte, digits) in bytes.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for BlobId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlobId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Box<str>>::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The blobs that were recently stored, which are not collected until they are old enough to have been referenced.
+#[derive(Debug, Default)]
+pub(crate) struct RecentBlobs {
+    /// The time at which each blob was last stored.
+    stored: HashMap<BlobId, Instant>,
+}
+
+impl RecentBlobs {
+    /// Marks the given blob as recently stored.
+    fn insert(&mut self, id: BlobId) {
+        self.stored.insert(id, Instant::now());
+    }
+
+    /// Forgets every blob that was stored longer ago than the given grace period, returning the remaining blobs.
+    fn retain_within(&mut self, grace: Duration) -> &HashMap<BlobId, Instant> {
+        self.stored.retain(|_, stored| stored.elapsed() < grace);
+
+        &self.stored
+    }
+}
+
+impl Storage {
+    /// Stores the given bytes as a blob, returning its identifier.
+    ///
+    /// Blobs are deduplicated, so storing bytes that are already stored does not write them again. Blobs that are not
+    /// referenced by any stored value are deleted by [`Storage::blocking_collect_blobs`] once their grace period ends.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blob could not be written.
+    pub fn blocking_store_blob(&mut self, bytes: &[u8]) -> anyhow::Result<BlobId> {
+        let id = BlobId::of(bytes);
+        let path = id.path();
+
+        if self.blocking_exists(&path)? {
+            debug!(%id, "blob is already stored");
+        } else {
+            self.blocking_write(&path, bytes)?;
+
+            debug!(%id, "stored blob");
+        }
+
+        self.blobs.insert(id);
+
+        Ok(id)
+    }
+
+    /// Stores the given bytes as a blob, returning its identifier.
+    ///
+    /// Blobs are deduplicated, so storing bytes that are already stored does not write them again. Blobs that are not
+    /// referenced by any stored value are deleted by [`Storage::collect_blobs`] once their grace period ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blob could not be written.
+    pub async fn store_blob(&mut self, bytes: &[u8]) -> anyhow::Result<BlobId> {
+        let id = BlobId::of(bytes);
+        let path = id.path();
+
+        if self.exists(&path).await? {
+            debug!(%id, "blob is already stored");
+        } else {
+            self.write(&path, bytes).await?;
+
+            debug!(%id, "stored blob");
+        }
+
+        self.blobs.insert(id);
+
+        Ok(id)
+    }
+
+    /// Returns the number of stored values that reference the given blob.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blob indexes could not be read.
+    pub fn blocking_blob_references(&self, id: BlobId) -> anyhow::Result<usize> {
+        let key = id.to_string();
+        let mut count = 0;
+
+        for index in self.blocking_list(Path::new(INDEX_DIRECTORY))? {
+            if index.file_name() != Some(OsStr::new(BLOB_INDEX)) {
+                continue;
+            }
+
+            for path in self.blocking_find_in_index(&index, &key)? {
+                count += usize::from(self.blocking_exists(&path)?);
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the number of stored values that reference the given blob.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blob indexes could not be read.
+    pub async fn blob_references(&self, id: BlobId) -> anyhow::Result<usize> {
+        let key = id.to_string();
+        let mut count = 0;
+
+        for index in self.list(Path::new(INDEX_DIRECTORY)).await? {
+            if index.file_name() != Some(OsStr::new(BLOB_INDEX)) {
+                continue;
+            }
+
+            for path in self.find_in_index(&index, &key).await? {
+                count += usize::from(self.exists(&path).await?);
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Deletes every blob that is not referenced by any stored value, returning the number of deleted blobs.
+    ///
+    /// Blobs stored within the given grace period are kept, as the values that reference them may not have been
+    /// written yet.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blobs or their indexes could not be listed, read, or deleted.
+    pub fn blocking_collect_blobs(&mut self, grace: Duration) -> anyhow::Result<usize> {
+        let mut referenced = HashSet::new();
+
+        for index in self.blocking_list(Path::new(INDEX_DIRECTORY))? {
+            if index.file_name() != Some(OsStr::new(BLOB_INDEX)) {
+                continue;
+            }
+
+            for (path, keys) in self.blocking_load_index(&index)?.iter() {
+                // Values deleted without updating their indexes no longer reference anything.
+                if self.blocking_exists(path)? {
+                    referenced.extend(keys.iter().filter_map(|key| key.parse::<BlobId>().ok()));
+                }
+            }
+        }
+
+        let recent = self.blobs.retain_within(grace).keys().copied().collect::<HashSet<_>>();
+        let mut count = 0;
+
+        for path in self.blocking_list(Path::new(BLOB_DIRECTORY))? {
+            let Some(id) = BlobId::from_path(&path) else { continue };
+
+            if referenced.contains(&id) || recent.contains(&id) {
+                continue;
+            }
+
+            self.blocking_delete(&path)?;
+
+            debug!(%id, "deleted unreferenced blob");
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Deletes every blob that is not referenced by any stored value, returning the number of deleted blobs.
+    ///
+    /// Blobs stored within the given grace period are kept, as the values that reference them may not have been
+    /// written yet.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the blobs or their indexes could not be listed, read, or deleted.
+    pub async fn collect_blobs(&mut self, grace: Duration) -> anyhow::Result<usize> {
+        let mut referenced = HashSet::new();
+
+        for index in self.list(Path::new(INDEX_DIRECTORY)).await? {
+            if index.file_name() != Some(OsStr::new(BLOB_INDEX)) {
+                continue;
+            }
+
+            for (path, keys) in self.load_index(&index).await?.iter() {
+                // Values deleted without updating their indexes no longer reference anything.
+                if self.exists(path).await? {
+                    referenced.extend(keys.iter().filter_map(|key| key.parse::<BlobId>().ok()));
+                }
+            }
+        }
+
+        let recent = self.blobs.retain_within(grace).keys().copied().collect::<HashSet<_>>();
+        let mut count = 0;
+
+        for path in self.list(Path::new(BLOB_DIRECTORY)).await? {
+            let Some(id) = BlobId::from_path(&path) else { continue };
+
+            if referenced.contains(&id) || recent.contains(&id) {
+                continue;
+            }
+
+            self.delete(&path).await?;
+
+            debug!(%id, "deleted unreferenced blob");
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Stores the given bytes as a blob, returning its identifier.
+///
+/// Blobs are deduplicated, so storing bytes that are already stored does not write them again. A blob is kept for as
+/// long as a stored value references it through the [`BLOB_INDEX`] secondary index.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or the blob could not be written.
+pub async fn store(bytes: impl Into<Arc<[u8]>>) -> anyhow::Result<BlobId> {
+    crate::thread::store_blob(bytes.into()).await
+}
+
+/// Stores the given bytes as a blob, returning its identifier.
+///
+/// Blobs are deduplicated, so storing bytes that are already stored does not write them again. A blob is kept for as
+/// long as a stored value references it through the [`BLOB_INDEX`] secondary index.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or the blob could not be written.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+pub fn blocking_store(bytes: impl Into<Arc<[u8]>>) -> anyhow::Result<BlobId> {
+    crate::thread::blocking_store_blob(bytes.into())
+}
+
+/// Returns the bytes of the given blob.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent, the blob could not be read, or its bytes do
+/// not match its identifier.
+pub async fn read(id: BlobId) -> anyhow::Result<Arc<[u8]>> {
+    let bytes = crate::thread::read_bytes(id.path()).await?;
+
+    self::verify(id, bytes)
+}
+
+/// Returns the bytes of the given blob.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent, the blob could not be read, or its bytes do
+/// not match its identifier.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+pub fn blocking_read(id: BlobId) -> anyhow::Result<Arc<[u8]>> {
+    let bytes = crate::thread::blocking_read_bytes(id.path())?;
+
+    self::verify(id, bytes)
+}
+
+/// Verifies that the given bytes match the given blob identifier.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes do not match.
+pub(crate) fn verify(id: BlobId, bytes: Arc<[u8]>) -> anyhow::Result<Arc<[u8]>> {
+    if BlobId::of(&bytes) == id { Ok(bytes) } else { Err(crate::Error::ChecksumMismatch(id.path()).into()) }
+}
+
+#[cfg(all(test, feature = "system-memory"))]
+mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use super::{BLOB_INDEX, BlobId};
+    use crate::Storage;
+    use crate::index::IndexChange;
+    use crate::system::{DataReader, DataWriter};
+
+    #[test]
+    fn collect_unreferenced_blobs() -> anyhow::Result<()> {
+        let mut storage = Storage::new(crate::settings::test_settings("blob"));
+
+        let kept = storage.blocking_store_blob(b"kept")?;
+        let dropped = storage.blocking_store_blob(b"dropped")?;
+
+        assert_eq!(storage.blocking_store_blob(b"kept")?, kept);
+        assert_eq!(kept.to_string().parse::<BlobId>()?, kept);
+        assert_eq!(BlobId::from_path(&kept.path()), Some(kept));
+
+        let index = crate::index::path_for("attachment", BLOB_INDEX);
+        let value = Path::new("attachment/1/2.pack");
+
+        storage.blocking_write(value, b"value")?;
+        storage.blocking_update_index(&index, value, IndexChange::Insert(crate::index::keys([kept])))?;
+
+        assert_eq!(storage.blocking_blob_references(kept)?, 1);
+        assert_eq!(storage.blocking_blob_references(dropped)?, 0);
+
+        // Recently stored blobs are kept until their grace period ends.
+        assert_eq!(storage.blocking_collect_blobs(Duration::from_mins(1))?, 0);
+        assert_eq!(storage.blocking_collect_blobs(Duration::ZERO)?, 1);
+
+        assert_eq!(&*super::verify(kept, storage.blocking_read(&kept.path())?)?, b"kept");
+        assert!(!storage.blocking_exists(&dropped.path())?);
+
+        storage.blocking_delete(value)?;
+
+        assert_eq!(storage.blocking_collect_blobs(Duration::ZERO)?, 1);
+        assert!(!storage.blocking_exists(&kept.path())?);
+
+        Ok(())
+    }
+}
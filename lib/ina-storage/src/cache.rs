@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing::trace;
+
+use crate::settings::Settings;
+
+/// The statistics of a storage cache.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of reads that were served from the cache.
+    pub hits: u64,
+    /// The number of reads that could not be served from the cache.
+    pub misses: u64,
+    /// The number of entries that were removed to stay within the cache's budget.
+    pub evictions: u64,
+    /// The number of entries that were removed because they expired or were modified externally.
+    pub invalidations: u64,
+    /// The number of entries currently held within the cache.
+    pub entries: usize,
+    /// The number of bytes currently held within the cache.
+    pub bytes: u64,
+}
+
+/// An entry within a storage cache.
+#[derive(Clone, Debug)]
+struct Entry {
+    /// The cached bytes.
+    bytes: Arc<[u8]>,
+    /// The time at which the entry was cached.
+    cached_at: Instant,
+    /// The time at which the cached data was last modified, if known.
+    modified: Option<SystemTime>,
+    /// The entry's position within the cache's usage order.
+    tick: u64,
+}
+
+/// A bounded storage cache that evicts its least recently used entries.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    /// The cached entries.
+    entries: HashMap<Box<Path>, Entry>,
+    /// The paths of the cached entries, ordered from least to most recently used.
+    usage: BTreeMap<u64, Box<Path>>,
+    /// The next usage tick.
+    tick: u64,
+    /// The maximum number of entries.
+    max_entries: usize,
+    /// The maximum number of bytes.
+    max_bytes: u64,
+    /// The duration after which entries expire.
+    ttl: Option<Duration>,
+    /// The cache's statistics.
+    stats: CacheStats,
+}
+
+impl Cache {
+    /// Creates a new [`Cache`] using the given settings.
+    #[must_use]
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+            tick: 0,
+            max_entries: settings.cache_entries,
+            max_bytes: settings.cache_bytes,
+            ttl: settings.cache_ttl.map(|seconds| Duration::from_secs(seconds.get())),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Returns the cache's statistics.
+    #[must_use]
+    pub const fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Returns the cached bytes for the given path.
+    ///
+    /// The entry is invalidated if it has expired or if the given modification time differs from the time at which the
+    /// data was last modified when it was cached.
+    pub fn get(&mut self, path: &Path, modified: Option<SystemTime>) -> Option<Arc<[u8]>> {
+        let Some(entry) = self.entries.get(path) else {
+            self.stats.misses += 1;
+
+            return None;
+        };
+
+        let is_expired = self.ttl.is_some_and(|ttl| entry.cached_at.elapsed() >= ttl);
+
+        if is_expired || entry.modified != modified {
+            self.remove(path);
+            self.stats.invalidations += 1;
+            self.stats.misses += 1;
+
+            trace!(?path, is_expired, "invalidated cache entry");
+
+            return None;
+        }
+
+        let tick = self.next_tick();
+        let Some(entry) = self.entries.get_mut(path) else { unreachable!("the entry was retrieved above") };
+
+        if let Some(path) = self.usage.remove(&entry.tick) {
+            self.usage.insert(tick, path);
+        }
+
+        entry.tick = tick;
+
+        self.stats.hits += 1;
+
+        Some(Arc::clone(&entry.bytes))
+    }
+
+    /// Inserts the given bytes into the cache, evicting the least recently used entries if the cache's budget is
+    /// exceeded.
+    ///
+    /// Bytes that would not fit within the cache on their own are not cached.
+    pub fn insert(&mut self, path: Box<Path>, bytes: Arc<[u8]>, modified: Option<SystemTime>) {
+        self.remove(&path);
+
+        if self.max_entries == 0 || bytes.len() as u64 > self.max_bytes {
+            return;
+        }
+
+        let tick = self.next_tick();
+
+        self.stats.entries += 1;
+        self.stats.bytes += bytes.len() as u64;
+
+        self.usage.insert(tick, path.clone());
+        self.entries.insert(path, Entry { bytes, cached_at: Instant::now(), modified, tick });
+
+        while self.stats.entries > self.max_entries || self.stats.bytes > self.max_bytes {
+            let Some((_, path)) = self.usage.pop_first() else { break };
+
+            self.remove(&path);
+            self.stats.evictions += 1;
+
+            trace!(?path, "evicted cache entry");
+        }
+    }
+
+    /// Moves the entry of the given path to a new path, if it is cached.
+    pub fn rename(&mut self, from: &Path, into: Box<Path>) {
+        let Some(entry) = self.entries.get(from).cloned() else { return };
+
+        self.remove(from);
+        self.remove(&into);
+
+        let tick = self.next_tick();
+
+        self.stats.entries += 1;
+        self.stats.bytes += entry.bytes.len() as u64;
+
+        self.usage.insert(tick, into.clone());
+        self.entries.insert(into, Entry { tick, ..entry });
+    }
+
+    /// Removes the entry of the given path, if it is cached.
+    pub fn remove(&mut self, path: &Path) {
+        let Some(entry) = self.entries.remove(path) else { return };
+
+        self.usage.remove(&entry.tick);
+
+        self.stats.entries -= 1;
+        self.stats.bytes -= entry.bytes.len() as u64;
+    }
+
+    /// Returns the next usage tick.
+    const fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+
+        self.tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::Cache;
+
+    fn cache(entries: usize, bytes: u64) -> Cache {
+        let mut settings = crate::settings::test_settings("cache");
+
+        settings.cache_entries = entries;
+        settings.cache_bytes = bytes;
+
+        Cache::new(&settings)
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let mut cache = self::cache(2, 1024);
+
+        cache.insert(Path::new("a").into(), Arc::from([0_u8; 4]), None);
+        cache.insert(Path::new("b").into(), Arc::from([0_u8; 4]), None);
+
+        assert!(cache.get(Path::new("a"), None).is_some());
+
+        cache.insert(Path::new("c").into(), Arc::from([0_u8; 4]), None);
+
+        assert!(cache.get(Path::new("b"), None).is_none());
+        assert!(cache.get(Path::new("a"), None).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().bytes, 8);
+    }
+
+    #[test]
+    fn evict_over_byte_budget() {
+        let mut cache = self::cache(16, 8);
+
+        cache.insert(Path::new("a").into(), Arc::from([0_u8; 4]), None);
+        cache.insert(Path::new("b").into(), Arc::from([0_u8; 6]), None);
+        cache.insert(Path::new("c").into(), Arc::from([0_u8; 16]), None);
+
+        assert!(cache.get(Path::new("a"), None).is_none());
+        assert!(cache.get(Path::new("b"), None).is_some());
+        assert!(cache.get(Path::new("c"), None).is_none());
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn invalidate_modified() {
+        let mut cache = self::cache(16, 1024);
+        let modified = std::time::SystemTime::UNIX_EPOCH;
+
+        cache.insert(Path::new("a").into(), Arc::from([0_u8; 4]), Some(modified));
+
+        assert!(cache.get(Path::new("a"), Some(modified)).is_some());
+        assert!(cache.get(Path::new("a"), None).is_none());
+        assert_eq!(cache.stats().invalidations, 1);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
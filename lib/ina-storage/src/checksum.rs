@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use crc32fast::Hasher;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// The magic byte sequence that ends a checksum trailer.
+const TRAILER_MAGIC: [u8; 8] = *b"1N4CRC32";
+/// The length of a checksum trailer in bytes.
+const TRAILER_LENGTH: usize = size_of::<u32>() + TRAILER_MAGIC.len();
+/// The number of bytes that a [`Verifying`] reader reads from its inner reader at a time.
+const CHUNK_LENGTH: usize = 8 * 1024;
+
+/// Returns the given bytes followed by a trailer containing their checksum.
+#[must_use]
+pub fn append(bytes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(bytes.len() + TRAILER_LENGTH);
+
+    output.extend_from_slice(bytes);
+    output.extend_from_slice(&self::trailer(crc32fast::hash(bytes)));
+
+    output
+}
+
+/// Returns the trailer containing the given checksum.
+const fn trailer(checksum: u32) -> [u8; TRAILER_LENGTH] {
+    let mut trailer = [0; TRAILER_LENGTH];
+    let (bytes, magic) = trailer.split_at_mut(size_of::<u32>());
+
+    bytes.copy_from_slice(&checksum.to_le_bytes());
+    magic.copy_from_slice(&TRAILER_MAGIC);
+
+    trailer
+}
+
+/// Splits the given bytes into their data and the checksum stored within their trailer.
+///
+/// Returns [`None`] if the bytes do not end with a checksum trailer.
+#[must_use]
+pub fn split(bytes: &[u8]) -> Option<(&[u8], u32)> {
+    let (bytes, trailer) = bytes.split_at_checked(bytes.len().checked_sub(TRAILER_LENGTH)?)?;
+    let (checksum, magic) = trailer.split_first_chunk::<{ size_of::<u32>() }>()?;
+
+    (magic == TRAILER_MAGIC).then(|| (bytes, u32::from_le_bytes(*checksum)))
+}
+
+/// Verifies the checksum trailer of the given bytes read from the given path, returning the bytes without their
+/// trailer.
+///
+/// Bytes without a checksum trailer are returned unchanged, unless a checksum is required.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes do not match their checksum, or if they have no checksum although
+/// one is required.
+pub fn verify(path: &Path, bytes: Arc<[u8]>, required: bool) -> crate::Result<Arc<[u8]>> {
+    let Some((data, checksum)) = self::split(&bytes) else {
+        return if required { Err(crate::Error::MissingChecksum(path.into())) } else { Ok(bytes) };
+    };
+
+    if crc32fast::hash(data) == checksum {
+        Ok(Arc::from(data))
+    } else {
+        Err(crate::Error::ChecksumMismatch(path.into()))
+    }
+}
+
+/// A reader that yields the bytes of its inner reader followed by a trailer containing their checksum.
+#[derive(Debug)]
+pub struct Appending<R> {
+    /// The inner reader.
+    inner: R,
+    /// The checksum of the bytes yielded so far.
+    hasher: Hasher,
+    /// The trailer, once the inner reader has been exhausted.
+    trailer: Option<[u8; TRAILER_LENGTH]>,
+    /// The number of trailer bytes that have been yielded.
+    position: usize,
+}
+
+impl<R> Appending<R> {
+    /// Creates a new [`Appending<R>`] reader.
+    pub fn new(inner: R) -> Self {
+        Self { inner, hasher: Hasher::new(), trailer: None, position: 0 }
+    }
+
+    /// Hashes the given bytes read from the inner reader, creating the trailer if the inner reader was exhausted.
+    fn consume(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            self.trailer = Some(self::trailer(self.hasher.clone().finalize()));
+        } else {
+            self.hasher.update(bytes);
+        }
+    }
+
+    /// Copies as many of the remaining trailer bytes as possible into the given buffer, returning their count.
+    fn drain(&mut self, buf: &mut [u8]) -> usize {
+        let Some(trailer) = &self.trailer else { return 0 };
+        let remaining = &trailer[self.position ..];
+        let length = remaining.len().min(buf.len());
+
+        buf[.. length].copy_from_slice(&remaining[.. length]);
+        self.position += length;
+
+        length
+    }
+}
+
+impl<R: Read> Read for Appending<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.trailer.is_none() {
+            let length = self.inner.read(buf)?;
+
+            self.consume(&buf[.. length]);
+
+            if length > 0 {
+                return Ok(length);
+            }
+        }
+
+        Ok(self.drain(buf))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Appending<R> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        if this.trailer.is_none() {
+            let start = buf.filled().len();
+
+            ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+
+            this.consume(&buf.filled()[start ..]);
+
+            if buf.filled().len() > start {
+                return Poll::Ready(Ok(()));
+            }
+        }
+
+        let length = this.drain(buf.initialize_unfilled());
+
+        buf.advance(length);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A reader that yields the bytes of its inner reader without their checksum trailer, failing once the inner reader
+/// is exhausted if the bytes do not match their checksum.
+///
+/// Bytes without a checksum trailer are yielded unchanged, unless a checksum is required, in which case the reader
+/// fails once the inner reader is exhausted.
+#[derive(Debug)]
+pub struct Verifying<R> {
+    /// The inner reader.
+    inner: R,
+    /// The path that the bytes were read from.
+    path: Box<Path>,
+    /// The checksum of the bytes yielded so far.
+    hasher: Hasher,
+    /// The bytes read from the inner reader that have not yet been yielded.
+    ///
+    /// Until the inner reader is exhausted, this always retains the final bytes that may form the trailer.
+    pending: Vec<u8>,
+    /// Whether the inner reader has been exhausted.
+    exhausted: bool,
+    /// Whether bytes without a checksum trailer fail to be read.
+    required: bool,
+}
+
+impl<R> Verifying<R> {
+    /// Creates a new [`Verifying<R>`] reader for bytes read from the given path, which fails if the bytes have no
+    /// checksum trailer and one is required.
+    pub fn new(path: &Path, inner: R, required: bool) -> Self {
+        Self { inner, path: path.into(), hasher: Hasher::new(), pending: Vec::new(), exhausted: false, required }
+    }
+
+    /// Returns `true` if more bytes must be read from the inner reader before any can be yielded.
+    const fn needs_input(&self) -> bool {
+        !self.exhausted && self.pending.len() <= TRAILER_LENGTH
+    }
+
+    /// Reserves space for the next chunk read from the inner reader, returning the index at which it starts.
+    fn reserve(&mut self) -> usize {
+        let start = self.pending.len();
+
+        self.pending.resize(start + CHUNK_LENGTH, 0);
+
+        start
+    }
+
+    /// Keeps the given number of bytes of the chunk starting at the given index, verifying the checksum if the inner
+    /// reader was exhausted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the bytes do not match their checksum, or if they have no checksum
+    /// although one is required.
+    fn commit(&mut self, start: usize, length: usize) -> std::io::Result<()> {
+        self.pending.truncate(start + length);
+
+        if length > 0 {
+            return Ok(());
+        }
+
+        self.exhausted = true;
+
+        let Some((data, checksum)) = self::split(&self.pending) else {
+            if self.required {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    crate::Error::MissingChecksum(self.path.clone()),
+                ));
+            }
+
+            return Ok(());
+        };
+
+        self.hasher.update(data);
+
+        if self.hasher.clone().finalize() != checksum {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, crate::Error::ChecksumMismatch(self.path.clone())));
+        }
+
+        self.pending.truncate(data.len());
+
+        Ok(())
+    }
+
+    /// Copies as many of the pending bytes as may be yielded into the given buffer, returning their count.
+    fn release(&mut self, buf: &mut [u8]) -> usize {
+        let available = if self.exhausted { self.pending.len() } else { self.pending.len() - TRAILER_LENGTH };
+        let length = available.min(buf.len());
+
+        buf[.. length].copy_from_slice(&self.pending[.. length]);
+
+        // Once exhausted, the remaining bytes have already been hashed.
+        if !self.exhausted {
+            self.hasher.update(&self.pending[.. length]);
+        }
+
+        self.pending.drain(.. length);
+
+        length
+    }
+}
+
+impl<R: Read> Read for Verifying<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.needs_input() {
+            let start = self.reserve();
+            let length = self.inner.read(&mut self.pending[start ..]).inspect_err(|_| self.pending.truncate(start));
+
+            self.commit(start, length?)?;
+        }
+
+        Ok(self.release(buf))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Verifying<R> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        while this.needs_input() {
+            let start = this.reserve();
+            let mut chunk = ReadBuf::new(&mut this.pending[start ..]);
+            let result = Pin::new(&mut this.inner).poll_read(cx, &mut chunk);
+            let length = chunk.filled().len();
+
+            match result {
+                Poll::Ready(Ok(())) => this.commit(start, length)?,
+                Poll::Ready(Err(error)) => {
+                    this.pending.truncate(start);
+
+                    return Poll::Ready(Err(error));
+                }
+                Poll::Pending => {
+                    this.pending.truncate(start);
+
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        let length = this.release(buf.initialize_unfilled());
+
+        buf.advance(length);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::{Appending, Verifying};
+
+    #[test]
+    fn verify_checksum_trailer() -> anyhow::Result<()> {
+        let path = Path::new("role/1/2.pack");
+        let bytes = super::append(b"data");
+
+        assert_eq!(&*super::verify(path, Arc::from(&*bytes), true)?, b"data");
+        assert_eq!(&*super::verify(path, Arc::from(&b"data"[..]), false)?, b"data");
+        assert!(matches!(super::verify(path, Arc::from(&b"data"[..]), true), Err(crate::Error::MissingChecksum(_))));
+
+        let mut corrupt = bytes;
+
+        corrupt[0] ^= 1;
+
+        assert!(matches!(super::verify(path, corrupt.into(), false), Err(crate::Error::ChecksumMismatch(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_checksum_trailer() -> anyhow::Result<()> {
+        let path = Path::new("role/1/2.pack");
+        let data = (0 .. 20_000_u32).map(|n| n.to_le_bytes()[0]).collect::<Vec<_>>();
+
+        let mut stored = Vec::new();
+
+        Appending::new(data.as_slice()).read_to_end(&mut stored)?;
+        assert_eq!(stored, super::append(&data));
+
+        let mut read = Vec::new();
+
+        Verifying::new(path, stored.as_slice(), true).read_to_end(&mut read)?;
+        assert_eq!(read, data);
+
+        let mut unchecked = Vec::new();
+
+        Verifying::new(path, data.as_slice(), false).read_to_end(&mut unchecked)?;
+        assert_eq!(unchecked, data);
+        assert!(Verifying::new(path, data.as_slice(), true).read_to_end(&mut Vec::new()).is_err());
+
+        stored[10_000] ^= 1;
+
+        assert!(Verifying::new(path, stored.as_slice(), false).read_to_end(&mut Vec::new()).is_err());
+
+        Ok(())
+    }
+}
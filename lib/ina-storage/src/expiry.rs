@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::path::Path;
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use crate::Storage;
+use crate::system::DataWriter;
+
+impl Storage {
+    /// Deletes the data at the given path if it has expired, returning whether it was deleted.
+    ///
+    /// Only the data's header is read, bypassing the cache.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be read or deleted.
+    pub fn blocking_remove_if_expired(&self, path: &Path) -> anyhow::Result<bool> {
+        if !crate::stored::is_expired(&self.blocking_read_prefix(path, crate::stored::HEADER_LENGTH)?) {
+            return Ok(false);
+        }
+
+        self.blocking_delete(path)?;
+
+        debug!(?path, "deleted expired data");
+
+        Ok(true)
+    }
+
+    /// Deletes the data at the given path if it has expired, returning whether it was deleted.
+    ///
+    /// Only the data's header is read, bypassing the cache.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be read or deleted.
+    pub async fn remove_if_expired(&self, path: &Path) -> anyhow::Result<bool> {
+        if !crate::stored::is_expired(&self.read_prefix(path, crate::stored::HEADER_LENGTH).await?) {
+            return Ok(false);
+        }
+
+        self.delete(path).await?;
+
+        debug!(?path, "deleted expired data");
+
+        Ok(true)
+    }
+}
+
+/// Returns whether the data at the given path is deleted by sweeps once it has expired.
+#[cfg_attr(
+    not(feature = "blob"),
+    expect(unused_variables, clippy::missing_const_for_fn, reason = "only blobs are excluded from sweeps")
+)]
+pub(crate) fn is_swept(path: &Path) -> bool {
+    // Blobs are stored without a header, and are instead deleted once they are no longer referenced.
+    #[cfg(feature = "blob")]
+    if path.starts_with(crate::blob::BLOB_DIRECTORY) {
+        return false;
+    }
+
+    true
+}
+
+/// A thread that periodically asks the storage thread to delete expired data and unreferenced blobs.
+#[derive(Debug)]
+pub(crate) struct Sweeper {
+    /// The sender that stops the sweeper when dropped.
+    sender: Sender<()>,
+    /// The sweeper's thread handle.
+    handle: JoinHandle<()>,
+}
+
+impl Sweeper {
+    /// Spawns a new [`Sweeper`] that sweeps expired data and unreferenced blobs at the given interval.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub(crate) fn spawn(interval: Duration) -> std::io::Result<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = std::thread::Builder::new().name("storage-sweeper".to_owned()).spawn(move || {
+            while receiver.recv_timeout(interval) == Err(RecvTimeoutError::Timeout) {
+                match crate::thread::blocking_sweep() {
+                    Ok(count) => debug!(count, "swept expired data"),
+                    Err(error) => warn!(%error, "failed to sweep expired data"),
+                }
+
+                // Blobs stored within the last interval may not be referenced yet, so they are given until the next.
+                #[cfg(feature = "blob")]
+                match crate::thread::blocking_collect_blobs(interval) {
+                    Ok(count) => debug!(count, "collected unreferenced blobs"),
+                    Err(error) => warn!(%error, "failed to collect unreferenced blobs"),
+                }
+            }
+        })?;
+
+        Ok(Self { sender, handle })
+    }
+
+    /// Stops the sweeper, blocking the current thread until any ongoing sweep has finished.
+    pub(crate) fn stop(self) {
+        drop(self.sender);
+
+        if self.handle.join().is_err() {
+            warn!("sweeper thread panicked");
+        }
+    }
+}
@@ -24,7 +24,7 @@ use flate2::read::{GzDecoder, GzEncoder};
 use serde::{Deserialize, Serialize};
 use tracing::trace;
 
-use super::{DataDecode, DataEncode, DataFormat};
+use super::{DataDecode, DataEncode, DataFormat, StreamFormat, StreamReader};
 
 /// A compression format error.
 #[derive(Debug, thiserror::Error)]
@@ -40,7 +40,10 @@ pub enum Error<F: Debug + DataFormat> {
     Decode(<F as DataDecode>::Error),
 }
 
-/// Compresses the wrapped format.
+/// The magic byte sequence that begins gzip-compressed data.
+pub const MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Compresses the wrapped format using gzip.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct Compress<F: Debug + DataFormat> {
     /// The inner format.
@@ -104,13 +107,34 @@ impl<F: Debug + DataFormat + 'static> DataDecode for Compress<F> {
     type Error = Error<F>;
 
     fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
-        let mut decoder = GzDecoder::new(bytes);
-        let mut buffer = Vec::with_capacity(bytes.len() * 3);
+        let buffer = super::magic::decompress(bytes).unwrap_or_else(|| self::decompress(bytes))?;
 
-        decoder.read_to_end(&mut buffer)?;
+        self.inner.decode(&buffer).map_err(Error::Decode)
+    }
+}
 
-        trace!(before = bytes.len(), after = buffer.len(), "inflated bytes");
+impl<F: Debug + StreamFormat + 'static> StreamFormat for Compress<F> {
+    fn encode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        Box::new(GzEncoder::new(self.inner.encode_stream(reader), self.level))
+    }
 
-        self.inner.decode(&buffer).map_err(Error::Decode)
+    fn decode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        self.inner.decode_stream(Box::new(GzDecoder::new(reader)))
     }
 }
+
+/// Decompresses the given gzip-compressed bytes.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be decompressed.
+pub(crate) fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(bytes);
+    let mut buffer = Vec::with_capacity(bytes.len() * 3);
+
+    decoder.read_to_end(&mut buffer)?;
+
+    trace!(before = bytes.len(), after = buffer.len(), "inflated bytes");
+
+    Ok(buffer)
+}
@@ -16,25 +16,51 @@
 
 use std::ffi::OsStr;
 use std::fmt::Debug;
-use std::io::Cursor;
+use std::io::{Cursor, ErrorKind, Read};
+use std::path::Path;
 use std::sync::{Arc, OnceLock};
 
 use argon2::password_hash::SaltString;
 use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::generic_array::GenericArray;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32, Nonce, StreamBE32};
 use chacha20poly1305::aead::{Aead, OsRng};
 use chacha20poly1305::{AeadCore, KeyInit, KeySizeUser, XChaCha20Poly1305};
 use serde::{Deserialize, Serialize};
 use tracing::trace;
 use zeroize::{Zeroize, Zeroizing};
 
-use super::{DataDecode, DataEncode, DataFormat};
+use super::{DataDecode, DataEncode, DataFormat, StreamFormat, StreamReader};
 
-/// The function used to resolve the encryption password at runtime.
-static PASSWORD_RESOLVER: OnceLock<fn() -> Option<String>> = OnceLock::new();
+/// The function used to resolve the encryption keys at runtime.
+static KEY_RESOLVER: OnceLock<fn() -> Option<KeyRing>> = OnceLock::new();
+
+/// The number of plain bytes within each chunk of streamed encrypted data.
+const CHUNK_LENGTH: usize = 64 * 1024;
+/// The number of bytes added to each chunk of streamed encrypted data by its authentication tag.
+const TAG_LENGTH: usize = 16;
+
+/// The nonce used to encrypt a stream of chunks.
+type StreamNonce = Nonce<XChaCha20Poly1305, StreamBE32<XChaCha20Poly1305>>;
 
 /// An encryption format error.
 #[derive(Debug, thiserror::Error)]
 pub enum Error<F: Debug + DataFormat> {
+    /// A cipher error.
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+    /// An encoding error.
+    #[error(transparent)]
+    Encode(<F as DataEncode>::Error),
+    /// A decoding error.
+    #[error(transparent)]
+    Decode(<F as DataDecode>::Error),
+}
+
+/// An error related to encrypting and decrypting raw bytes.
+#[derive(Debug, thiserror::Error)]
+pub enum CipherError {
     /// An IO error.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -44,15 +70,12 @@ pub enum Error<F: Debug + DataFormat> {
     /// A `ChaCha20Poly1305` error.
     #[error("failed to encrypt/decrypt data")]
     ChaCha20Poly1305(chacha20poly1305::Error),
-    /// An encoding error.
-    #[error(transparent)]
-    Encode(<F as DataEncode>::Error),
-    /// A decoding error.
-    #[error(transparent)]
-    Decode(<F as DataDecode>::Error),
     /// A password was not set.
     #[error("a password was not set")]
     MissingPassword,
+    /// The data was encrypted using a key that is not known.
+    #[error("unknown encryption key '{0}'")]
+    UnknownKey(Box<str>),
     /// A header-related error.
     #[error(transparent)]
     Header(#[from] HeaderError),
@@ -70,33 +93,140 @@ pub enum HeaderError {
     /// The header version did not match.
     #[error("invalid version number: expected {0:02X}, found {1:02X}")]
     InvalidVersion(u8, u8),
+    /// The key identifier was not valid UTF-8.
+    #[error("invalid key identifier")]
+    InvalidKey,
+}
+
+/// A password used to derive encryption keys, labelled with a unique identifier.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    /// The key's identifier, which is stored alongside data encrypted using this key.
+    id: Box<str>,
+    /// The key's password.
+    password: Zeroizing<String>,
+}
+
+impl EncryptionKey {
+    /// Creates a new [`EncryptionKey`].
+    pub fn new(id: impl Into<Box<str>>, password: impl Into<String>) -> Self {
+        Self { id: id.into(), password: Zeroizing::new(password.into()) }
+    }
+
+    /// Returns the key's identifier.
+    #[must_use]
+    pub const fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+/// The set of keys used to encrypt and decrypt data.
+///
+/// Data is always encrypted using the current key, while previous keys are only used to decrypt data that has not yet
+/// been re-encrypted.
+#[derive(Clone, Debug)]
+pub struct KeyRing {
+    /// The current key.
+    current: EncryptionKey,
+    /// The previously used keys.
+    previous: Vec<EncryptionKey>,
+}
+
+impl KeyRing {
+    /// Creates a new [`KeyRing`] with the given current key.
+    #[must_use]
+    pub const fn new(current: EncryptionKey) -> Self {
+        Self { current, previous: Vec::new() }
+    }
+
+    /// Adds a previously used key to the key ring.
+    #[must_use]
+    pub fn with_previous(mut self, key: EncryptionKey) -> Self {
+        self.previous.push(key);
+
+        self
+    }
+
+    /// Returns the current key.
+    #[must_use]
+    pub const fn current(&self) -> &EncryptionKey {
+        &self.current
+    }
+
+    /// Returns the key with the given identifier.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&EncryptionKey> {
+        self.iter().find(|key| &*key.id == id)
+    }
+
+    /// Returns an iterator over every key, starting with the current key.
+    pub fn iter(&self) -> impl Iterator<Item = &EncryptionKey> {
+        std::iter::once(&self.current).chain(&self.previous)
+    }
 }
 
 /// A header used for retaining encryption data.
 #[derive(Clone, Debug)]
 pub(crate) struct Header {
+    /// The identifier of the key used for encryption, or [`None`] if the data predates key identifiers.
+    pub key: Option<Box<str>>,
     /// The salt.
     pub salt: Box<[u8]>,
     /// The nonce.
     pub nonce: Box<[u8]>,
+    /// Whether the data was encrypted as a stream of chunks.
+    pub chunked: bool,
 }
 
 impl Header {
     /// The header's magic byte sequence.
     pub const MAGIC: [u8; 3] = *b"1N4";
     /// The header's format version.
-    pub const VERSION: u8 = 1;
+    pub const VERSION: u8 = 2;
+    /// The header's format version for data encrypted as a stream of chunks.
+    pub const VERSION_STREAM: u8 = 3;
+    /// The header's format version before key identifiers were introduced.
+    pub const VERSION_UNKEYED: u8 = 1;
 
     /// Creates a new [`Header`].
-    pub const fn new(salt: Box<[u8]>, nonce: Box<[u8]>) -> Self {
-        Self { salt, nonce }
+    pub const fn new(key: Option<Box<str>>, salt: Box<[u8]>, nonce: Box<[u8]>) -> Self {
+        Self { key, salt, nonce, chunked: false }
+    }
+
+    /// Creates a new [`Header`] for data encrypted as a stream of chunks.
+    pub const fn new_chunked(key: Box<str>, salt: Box<[u8]>, nonce: Box<[u8]>) -> Self {
+        Self { key: Some(key), salt, nonce, chunked: true }
     }
 
     /// Returns the total length of the header in bytes.
-    pub const fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         const USIZE: usize = (usize::BITS / u8::BITS) as usize;
 
-        Self::MAGIC.len() + 1 + USIZE + self.salt.len() + USIZE + self.nonce.len()
+        let key_len = self.key.as_ref().map_or(0, |key| USIZE + key.len());
+
+        Self::MAGIC.len() + 1 + key_len + USIZE + self.salt.len() + USIZE + self.nonce.len()
+    }
+
+    /// Reads a length-prefixed byte array from the given buffer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading fails.
+    fn read_array<R: std::io::Read>(f: &mut R) -> Result<Box<[u8]>, HeaderError> {
+        let mut len = [0_u8; (usize::BITS / u8::BITS) as usize];
+        f.read_exact(&mut len)?;
+        let len = usize::from_le_bytes(len);
+
+        let mut array = vec![0_u8; len];
+        f.read_exact(&mut array)?;
+
+        Ok(array.into_boxed_slice())
     }
 
     /// Reads a header from the given buffer.
@@ -117,27 +247,23 @@ impl Header {
         let mut version = [0_u8; 1];
         f.read_exact(&mut version)?;
 
-        if version[0] != Self::VERSION {
-            return Err(HeaderError::InvalidVersion(Self::VERSION, version[0]));
-        }
-
-        // Extract encryption hashing salt.
-        let mut salt_len = [0_u8; (usize::BITS / u8::BITS) as usize];
-        f.read_exact(&mut salt_len)?;
-        let salt_len = usize::from_le_bytes(salt_len);
+        // Extract the encryption key identifier, which older headers do not contain.
+        let key = match version[0] {
+            Self::VERSION | Self::VERSION_STREAM => {
+                let key = Self::read_array(f)?.into_vec();
 
-        let mut salt = vec![0_u8; salt_len];
-        f.read_exact(&mut salt)?;
+                Some(String::from_utf8(key).map_err(|_| HeaderError::InvalidKey)?.into_boxed_str())
+            }
+            Self::VERSION_UNKEYED => None,
+            version => return Err(HeaderError::InvalidVersion(Self::VERSION, version)),
+        };
 
+        // Extract encryption hashing salt.
+        let salt = Self::read_array(f)?;
         // Extract encryption encoding nonce.
-        let mut nonce_len = [0_u8; (usize::BITS / u8::BITS) as usize];
-        f.read_exact(&mut nonce_len)?;
-        let nonce_len = usize::from_le_bytes(nonce_len);
-
-        let mut nonce = vec![0_u8; nonce_len];
-        f.read_exact(&mut nonce)?;
+        let nonce = Self::read_array(f)?;
 
-        Ok(Self::new(salt.into_boxed_slice(), nonce.into_boxed_slice()))
+        Ok(Self { key, salt, nonce, chunked: version[0] == Self::VERSION_STREAM })
     }
 
     /// Writes this header into a given buffer.
@@ -147,7 +273,15 @@ impl Header {
     /// This function will return an error if writing fails.
     pub fn write_into<W: std::io::Write>(&self, f: &mut W) -> std::io::Result<()> {
         f.write_all(&Self::MAGIC)?;
-        f.write_all(&[Self::VERSION])?;
+
+        if let Some(key) = &self.key {
+            f.write_all(&[if self.chunked { Self::VERSION_STREAM } else { Self::VERSION }])?;
+            f.write_all(&key.len().to_le_bytes())?;
+            f.write_all(key.as_bytes())?;
+        } else {
+            f.write_all(&[Self::VERSION_UNKEYED])?;
+        }
+
         f.write_all(&self.salt.len().to_le_bytes())?;
         f.write_all(&self.salt)?;
         f.write_all(&self.nonce.len().to_le_bytes())?;
@@ -178,7 +312,7 @@ impl<F: Debug + DataFormat> Encrypt<F> {
 
 impl<F: Debug + DataFormat + 'static> DataFormat for Encrypt<F> {
     fn extension(&self) -> impl AsRef<OsStr> {
-        format!("{}.cha", self.inner.extension().as_ref().to_string_lossy())
+        format!("{}.{EXTENSION}", self.inner.extension().as_ref().to_string_lossy())
     }
 }
 
@@ -189,60 +323,474 @@ impl<F: Debug + DataFormat + 'static> DataEncode for Encrypt<F> {
         // Fully serialize the value.
         let bytes = self.inner.encode(value).map_err(Error::Encode)?;
 
-        // Hash the configured password.
-        let salt = SaltString::generate(OsRng).to_string().into_bytes();
-        let key = self::get_encryption_key(&salt)?;
+        Ok(self::encrypt(&bytes)?.into())
+    }
+}
 
-        // Encode the data using the password hash.
-        let nonce = XChaCha20Poly1305::generate_nonce(OsRng);
-        let bytes = XChaCha20Poly1305::new((**key).into()).encrypt(&nonce, &*bytes).map_err(Error::ChaCha20Poly1305)?;
+impl<F: Debug + DataFormat + 'static> DataDecode for Encrypt<F> {
+    type Error = Error<F>;
 
-        // Create the final output buffer.
-        let header = Zeroizing::new(Header::new(salt.into_boxed_slice(), (*nonce).into()));
-        let mut output = Vec::with_capacity(header.len() + bytes.len());
+    fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let bytes = self::decrypt(bytes)?;
 
-        header.write_into(&mut output)?;
-        output.extend_from_slice(&bytes);
+        self.inner.decode(&bytes).map_err(Error::Decode)
+    }
+}
 
-        trace!(bytes = bytes.len(), "encrypted bytes");
+impl<F: Debug + StreamFormat + 'static> StreamFormat for Encrypt<F> {
+    fn encode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        Box::new(Encrypting::new(self.inner.encode_stream(reader)))
+    }
 
-        Ok(output.into())
+    fn decode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        self.inner.decode_stream(Box::new(Decrypting::new(reader)))
     }
 }
 
-impl<F: Debug + DataFormat + 'static> DataDecode for Encrypt<F> {
-    type Error = Error<F>;
+/// The state of an [`Encrypting<R>`] reader.
+enum EncryptingState {
+    /// The header has not yet been written, optionally using a specific key.
+    Header(Option<EncryptionKey>),
+    /// Chunks are being encrypted.
+    Chunks(EncryptorBE32<XChaCha20Poly1305>),
+    /// Every chunk has been encrypted.
+    Finished,
+}
 
-    fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
-        // Extract the encryption data header.
-        let mut reader = Cursor::new(bytes);
-        let header = Zeroizing::new(Header::read_from(&mut reader)?);
+/// A reader that encrypts the bytes of its inner reader as a stream of authenticated chunks.
+///
+/// Only a single chunk is held in memory at a time, regardless of the total length of the data.
+pub struct Encrypting<R> {
+    /// The inner reader.
+    inner: R,
+    /// The reader's state.
+    state: EncryptingState,
+    /// The bytes read from the inner reader that have not yet been encrypted.
+    input: Zeroizing<Vec<u8>>,
+    /// The encrypted bytes that have not yet been yielded.
+    output: Vec<u8>,
+    /// The number of output bytes that have been yielded.
+    position: usize,
+}
 
-        // Hash the configured password.
-        let key = self::get_encryption_key(&header.salt)?;
+impl<R: Read> Encrypting<R> {
+    /// Creates a new [`Encrypting<R>`] reader that encrypts using the current key.
+    ///
+    /// The key is resolved once the reader is first read from.
+    pub fn new(inner: R) -> Self {
+        Self::new_with(inner, None)
+    }
 
-        // Decode the data using the password hash.
-        let bytes = &bytes[header.len() ..];
-        let bytes = XChaCha20Poly1305::new((**key).into())
-            .decrypt((*header.nonce).into(), bytes)
-            .map_err(Error::ChaCha20Poly1305)?;
+    /// Creates a new [`Encrypting<R>`] reader that encrypts using the given key.
+    pub fn with_key(inner: R, key: EncryptionKey) -> Self {
+        Self::new_with(inner, Some(key))
+    }
 
-        trace!(bytes = bytes.len(), "decrypted bytes");
+    /// Creates a new [`Encrypting<R>`] reader in its initial state.
+    fn new_with(inner: R, key: Option<EncryptionKey>) -> Self {
+        let input = Zeroizing::new(Vec::with_capacity(CHUNK_LENGTH + 1));
 
-        self.inner.decode(&bytes).map_err(Error::Decode)
+        Self { inner, state: EncryptingState::Header(key), input, output: Vec::new(), position: 0 }
+    }
+
+    /// Replaces the output buffer with the next encrypted bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the inner reader fails or encryption fails.
+    fn advance(&mut self) -> Result<(), CipherError> {
+        self.output.clear();
+        self.position = 0;
+
+        match std::mem::replace(&mut self.state, EncryptingState::Finished) {
+            EncryptingState::Header(key) => {
+                let key = match key {
+                    Some(key) => key,
+                    None => self::get_key_ring()?.current().clone(),
+                };
+
+                // Hash the configured password.
+                let salt = SaltString::generate(OsRng).to_string().into_bytes();
+                let hash = self::get_encryption_key(&key, &salt)?;
+
+                let mut nonce = StreamNonce::default();
+
+                OsRng.fill_bytes(&mut nonce);
+
+                let header = Zeroizing::new(Header::new_chunked(key.id, salt.into_boxed_slice(), (*nonce).into()));
+
+                header.write_into(&mut self.output)?;
+
+                let cipher = XChaCha20Poly1305::new((**hash).into());
+
+                self.state = EncryptingState::Chunks(EncryptorBE32::from_aead(cipher, &nonce));
+            }
+            EncryptingState::Chunks(mut encryptor) => {
+                // Reading one byte past the chunk determines whether this is the final chunk.
+                self::read_up_to(&mut self.inner, &mut self.input, CHUNK_LENGTH + 1)?;
+
+                if self.input.len() > CHUNK_LENGTH {
+                    let chunk = encryptor.encrypt_next(&self.input[.. CHUNK_LENGTH]);
+
+                    self.output = chunk.map_err(CipherError::ChaCha20Poly1305)?;
+                    self.input.drain(.. CHUNK_LENGTH);
+                    self.state = EncryptingState::Chunks(encryptor);
+                } else {
+                    let chunk = encryptor.encrypt_last(&self.input[..]);
+
+                    self.output = chunk.map_err(CipherError::ChaCha20Poly1305)?;
+                    self.input.clear();
+
+                    trace!("encrypted stream");
+                }
+            }
+            EncryptingState::Finished => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Encrypting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.output.len() && !matches!(self.state, EncryptingState::Finished) {
+            self.advance().map_err(self::into_io_error)?;
+        }
+
+        let remaining = &self.output[self.position ..];
+        let length = remaining.len().min(buf.len());
+
+        buf[.. length].copy_from_slice(&remaining[.. length]);
+        self.position += length;
+
+        Ok(length)
     }
 }
 
-/// Sets the password resolver of all [`Encrypt<F>`] formats.
+impl<R> Debug for Encrypting<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Encrypting").finish_non_exhaustive()
+    }
+}
+
+/// The state of a [`Decrypting<R>`] reader.
+enum DecryptingState {
+    /// The header has not yet been read, optionally using a specific key ring.
+    Header(Option<KeyRing>),
+    /// Chunks are being decrypted.
+    Chunks(DecryptorBE32<XChaCha20Poly1305>),
+    /// Every chunk has been decrypted.
+    Finished,
+}
+
+/// A reader that decrypts the bytes of its inner reader.
+///
+/// Data encrypted as a stream of chunks only holds a single chunk in memory at a time, while data that was encrypted
+/// in full is also decrypted in full.
+pub struct Decrypting<R> {
+    /// The inner reader.
+    inner: R,
+    /// The reader's state.
+    state: DecryptingState,
+    /// The bytes read from the inner reader that have not yet been decrypted.
+    input: Vec<u8>,
+    /// The decrypted bytes that have not yet been yielded.
+    output: Zeroizing<Vec<u8>>,
+    /// The number of output bytes that have been yielded.
+    position: usize,
+}
+
+impl<R: Read> Decrypting<R> {
+    /// Creates a new [`Decrypting<R>`] reader that decrypts using the configured keys.
+    ///
+    /// The keys are resolved once the reader is first read from.
+    pub fn new(inner: R) -> Self {
+        Self::new_with(inner, None)
+    }
+
+    /// Creates a new [`Decrypting<R>`] reader that decrypts using the given keys.
+    pub fn with_keys(inner: R, keys: KeyRing) -> Self {
+        Self::new_with(inner, Some(keys))
+    }
+
+    /// Creates a new [`Decrypting<R>`] reader in its initial state.
+    fn new_with(inner: R, keys: Option<KeyRing>) -> Self {
+        let input = Vec::with_capacity(CHUNK_LENGTH + TAG_LENGTH + 1);
+
+        Self { inner, state: DecryptingState::Header(keys), input, output: Zeroizing::default(), position: 0 }
+    }
+
+    /// Replaces the output buffer with the next decrypted bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the inner reader fails, the key is not known, or decryption fails.
+    fn advance(&mut self) -> Result<(), CipherError> {
+        self.output.clear();
+        self.position = 0;
+
+        match std::mem::replace(&mut self.state, DecryptingState::Finished) {
+            DecryptingState::Header(keys) => {
+                let keys = match keys {
+                    Some(keys) => keys,
+                    None => self::get_key_ring()?,
+                };
+                let header = Zeroizing::new(Header::read_from(&mut self.inner)?);
+
+                // Data that was encrypted in full can only be decrypted in full.
+                if !header.chunked {
+                    let mut bytes = Vec::with_capacity(header.len());
+
+                    header.write_into(&mut bytes)?;
+                    self.inner.read_to_end(&mut bytes)?;
+                    self.output = self::decrypt_with(&keys, &bytes)?.0;
+
+                    return Ok(());
+                }
+
+                let Some(id) = header.key.as_deref() else { return Err(HeaderError::InvalidKey.into()) };
+                let key = keys.get(id).ok_or_else(|| CipherError::UnknownKey(id.into()))?;
+                let hash = self::get_encryption_key(key, &header.salt)?;
+                let nonce = GenericArray::from_exact_iter(header.nonce.iter().copied())
+                    .ok_or(CipherError::ChaCha20Poly1305(chacha20poly1305::Error))?;
+                let nonce: StreamNonce = nonce;
+
+                let cipher = XChaCha20Poly1305::new((**hash).into());
+
+                self.state = DecryptingState::Chunks(DecryptorBE32::from_aead(cipher, &nonce));
+            }
+            DecryptingState::Chunks(mut decryptor) => {
+                const SEALED_LENGTH: usize = CHUNK_LENGTH + TAG_LENGTH;
+
+                // Reading one byte past the chunk determines whether this is the final chunk.
+                self::read_up_to(&mut self.inner, &mut self.input, SEALED_LENGTH + 1)?;
+
+                if self.input.len() > SEALED_LENGTH {
+                    let chunk = decryptor.decrypt_next(&self.input[.. SEALED_LENGTH]);
+
+                    self.output = Zeroizing::new(chunk.map_err(CipherError::ChaCha20Poly1305)?);
+                    self.input.drain(.. SEALED_LENGTH);
+                    self.state = DecryptingState::Chunks(decryptor);
+                } else {
+                    let chunk = decryptor.decrypt_last(&self.input[..]);
+
+                    self.output = Zeroizing::new(chunk.map_err(CipherError::ChaCha20Poly1305)?);
+                    self.input.clear();
+
+                    trace!("decrypted stream");
+                }
+            }
+            DecryptingState::Finished => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decrypting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.output.len() && !matches!(self.state, DecryptingState::Finished) {
+            self.advance().map_err(self::into_io_error)?;
+        }
+
+        let remaining = &self.output[self.position ..];
+        let length = remaining.len().min(buf.len());
+
+        buf[.. length].copy_from_slice(&remaining[.. length]);
+        self.position += length;
+
+        Ok(length)
+    }
+}
+
+impl<R> Debug for Decrypting<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Decrypting").finish_non_exhaustive()
+    }
+}
+
+/// The file extension added by the [`Encrypt<F>`] format.
+pub const EXTENSION: &str = "cha";
+
+/// Returns whether the outermost format of the data at the given path is encryption.
+#[must_use]
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new(EXTENSION))
+}
+
+/// Sets the key resolver of all [`Encrypt<F>`] formats.
 ///
 /// # Panics
 ///
 /// Panics if the resolver was already set.
 #[expect(clippy::expect_used, reason = "we should fail if the resolver is set multiple times")]
-pub fn set_password_resolver(f: fn() -> Option<String>) {
-    PASSWORD_RESOLVER.set(f).expect("the password resolver has already been set");
+pub fn set_key_resolver(f: fn() -> Option<KeyRing>) {
+    KEY_RESOLVER.set(f).expect("the key resolver has already been set");
 
-    trace!("updated encryption password resolver");
+    trace!("updated encryption key resolver");
+}
+
+/// Encrypts the given bytes using the current key.
+///
+/// # Errors
+///
+/// This function will return an error if the key is not set or encryption fails.
+pub fn encrypt(bytes: &[u8]) -> Result<Vec<u8>, CipherError> {
+    let keys = self::get_key_ring()?;
+
+    self::encrypt_with(keys.current(), bytes)
+}
+
+/// Decrypts the given bytes using whichever key they were encrypted with.
+///
+/// Data encrypted before key identifiers were introduced is decrypted by trying every known key in turn.
+///
+/// # Errors
+///
+/// This function will return an error if the key is not set or not known, or if decryption fails.
+pub fn decrypt(bytes: &[u8]) -> Result<Zeroizing<Vec<u8>>, CipherError> {
+    let keys = self::get_key_ring()?;
+
+    self::decrypt_with(&keys, bytes).map(|(bytes, _)| bytes)
+}
+
+/// Re-encrypts the given bytes using the current key.
+///
+/// Returns [`None`] if the bytes were already encrypted using the current key.
+///
+/// # Errors
+///
+/// This function will return an error if the key is not set or not known, or if decryption or encryption fails.
+pub fn reencrypt(bytes: &[u8]) -> Result<Option<Vec<u8>>, CipherError> {
+    let keys = self::get_key_ring()?;
+    let header = Zeroizing::new(Header::read_from(&mut Cursor::new(bytes))?);
+
+    if header.key.as_deref() == Some(keys.current().id()) {
+        return Ok(None);
+    }
+
+    let (bytes, key) = self::decrypt_with(&keys, bytes)?;
+
+    trace!(from = key, into = keys.current().id(), "re-encrypting bytes");
+
+    self::encrypt_with(keys.current(), &bytes).map(Some)
+}
+
+/// Encrypts the given bytes using the given key.
+///
+/// # Errors
+///
+/// This function will return an error if hashing or encryption fails.
+fn encrypt_with(key: &EncryptionKey, bytes: &[u8]) -> Result<Vec<u8>, CipherError> {
+    // Hash the configured password.
+    let salt = SaltString::generate(OsRng).to_string().into_bytes();
+    let hash = self::get_encryption_key(key, &salt)?;
+
+    // Encode the data using the password hash.
+    let nonce = XChaCha20Poly1305::generate_nonce(OsRng);
+    let bytes =
+        XChaCha20Poly1305::new((**hash).into()).encrypt(&nonce, bytes).map_err(CipherError::ChaCha20Poly1305)?;
+
+    // Create the final output buffer.
+    let header = Zeroizing::new(Header::new(Some(key.id.clone()), salt.into_boxed_slice(), (*nonce).into()));
+    let mut output = Vec::with_capacity(header.len() + bytes.len());
+
+    header.write_into(&mut output)?;
+    output.extend_from_slice(&bytes);
+
+    trace!(bytes = bytes.len(), key = key.id(), "encrypted bytes");
+
+    Ok(output)
+}
+
+/// Decrypts the given bytes using the matching key within the given key ring, returning the decrypted bytes and the
+/// identifier of the key that was used.
+///
+/// # Errors
+///
+/// This function will return an error if the key is not known, or if hashing or decryption fails.
+fn decrypt_with<'kr>(keys: &'kr KeyRing, bytes: &[u8]) -> Result<(Zeroizing<Vec<u8>>, &'kr str), CipherError> {
+    // Extract the encryption data header.
+    let header = Zeroizing::new(Header::read_from(&mut Cursor::new(bytes))?);
+
+    if header.chunked {
+        let Some(id) = header.key.as_deref() else { return Err(HeaderError::InvalidKey.into()) };
+        let key = keys.get(id).ok_or_else(|| CipherError::UnknownKey(id.into()))?;
+        let mut output = Zeroizing::new(Vec::with_capacity(bytes.len()));
+
+        Decrypting::with_keys(bytes, keys.clone()).read_to_end(&mut output).map_err(self::from_io_error)?;
+
+        return Ok((output, key.id()));
+    }
+
+    let bytes = &bytes[header.len() ..];
+
+    let candidates: Box<[&EncryptionKey]> = match header.key.as_deref() {
+        Some(id) => Box::new([keys.get(id).ok_or_else(|| CipherError::UnknownKey(id.into()))?]),
+        None => keys.iter().collect(),
+    };
+
+    let mut last_error = None;
+
+    for key in candidates {
+        // Hash the configured password.
+        let hash = self::get_encryption_key(key, &header.salt)?;
+
+        // Decode the data using the password hash.
+        match XChaCha20Poly1305::new((**hash).into()).decrypt((*header.nonce).into(), bytes) {
+            Ok(bytes) => {
+                trace!(bytes = bytes.len(), key = key.id(), "decrypted bytes");
+
+                return Ok((Zeroizing::new(bytes), key.id()));
+            }
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.map_or(CipherError::MissingPassword, CipherError::ChaCha20Poly1305))
+}
+
+/// Reads from the given reader until the given buffer contains the given number of bytes or the reader is exhausted.
+///
+/// # Errors
+///
+/// This function will return an error if reading fails.
+fn read_up_to(reader: &mut impl Read, buffer: &mut Vec<u8>, length: usize) -> std::io::Result<()> {
+    while buffer.len() < length {
+        let start = buffer.len();
+
+        buffer.resize(length, 0);
+
+        match reader.read(&mut buffer[start ..]) {
+            Ok(0) => {
+                buffer.truncate(start);
+
+                break;
+            }
+            Ok(read) => buffer.truncate(start + read),
+            Err(error) if error.kind() == ErrorKind::Interrupted => buffer.truncate(start),
+            Err(error) => {
+                buffer.truncate(start);
+
+                return Err(error);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts the given cipher error into an IO error, unwrapping errors that were already IO errors.
+fn into_io_error(error: CipherError) -> std::io::Error {
+    match error {
+        CipherError::Io(error) => error,
+        error => std::io::Error::new(ErrorKind::InvalidData, error),
+    }
+}
+
+/// Converts the given IO error into a cipher error, unwrapping errors that were originally cipher errors.
+fn from_io_error(error: std::io::Error) -> CipherError {
+    error.downcast::<CipherError>().unwrap_or_else(CipherError::Io)
 }
 
 /// Returns a new [`Argon2`].
@@ -250,21 +798,96 @@ fn create_argon2<'key>() -> Argon2<'key> {
     Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::default())
 }
 
-/// Returns the configured password if available.
-fn get_password<F: Debug + DataFormat>() -> Result<Zeroizing<String>, Error<F>> {
-    PASSWORD_RESOLVER.get().and_then(|f| f()).map(Zeroizing::new).ok_or(Error::MissingPassword)
+/// Returns the configured key ring if available.
+fn get_key_ring() -> Result<KeyRing, CipherError> {
+    KEY_RESOLVER.get().and_then(|f| f()).ok_or(CipherError::MissingPassword)
 }
 
-/// Returns an encryption key based on the given salt and the configured password.
+/// Returns an encryption key based on the given salt and the given key's password.
 ///
 /// # Errors
 ///
-/// This function will return an error if the password is not set or hashing fails.
-fn get_encryption_key<F: Debug + DataFormat>(salt: &[u8]) -> Result<Zeroizing<Box<[u8]>>, Error<F>> {
-    let mut key = vec![0_u8; XChaCha20Poly1305::key_size()];
-    let password = self::get_password()?;
+/// This function will return an error if hashing fails.
+fn get_encryption_key(key: &EncryptionKey, salt: &[u8]) -> Result<Zeroizing<Box<[u8]>>, CipherError> {
+    let mut hash = vec![0_u8; XChaCha20Poly1305::key_size()];
+
+    self::create_argon2().hash_password_into(key.password.as_bytes(), salt, &mut hash).map_err(CipherError::Argon2)?;
+
+    Ok(Zeroizing::new(hash.into_boxed_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use super::{CHUNK_LENGTH, CipherError, Decrypting, Encrypting, EncryptionKey, Header, KeyRing};
+
+    #[test]
+    fn read_unkeyed_header() -> anyhow::Result<()> {
+        let header = Header::new(None, Box::new([1, 2, 3]), Box::new([4, 5]));
+        let mut bytes = Vec::new();
+
+        header.write_into(&mut bytes)?;
+        assert_eq!(bytes[Header::MAGIC.len()], Header::VERSION_UNKEYED);
+        assert_eq!(bytes.len(), header.len());
+
+        let read = Header::read_from(&mut Cursor::new(&bytes))?;
+
+        assert_eq!(read.key, None);
+        assert_eq!(read.salt, header.salt);
+        assert_eq!(read.nonce, header.nonce);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_with_previous_key() -> anyhow::Result<()> {
+        let old = KeyRing::new(EncryptionKey::new("old", "hunter2"));
+        let new =
+            KeyRing::new(EncryptionKey::new("new", "hunter3")).with_previous(EncryptionKey::new("old", "hunter2"));
+
+        let bytes = super::encrypt_with(old.current(), b"secret")?;
+        let (decrypted, key) = super::decrypt_with(&new, &bytes)?;
 
-    self::create_argon2().hash_password_into(password.as_bytes(), salt, &mut key).map_err(Error::Argon2)?;
+        assert_eq!(&**decrypted, b"secret");
+        assert_eq!(key, "old");
 
-    Ok(Zeroizing::new(key.into_boxed_slice()))
+        let unknown = KeyRing::new(EncryptionKey::new("new", "hunter3"));
+
+        assert!(matches!(super::decrypt_with(&unknown, &bytes), Err(CipherError::UnknownKey(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_chunked_stream() -> anyhow::Result<()> {
+        let keys = KeyRing::new(EncryptionKey::new("current", "hunter2"));
+
+        // Cover data that ends both within a chunk and exactly at the end of a chunk.
+        for length in [CHUNK_LENGTH * 2 + 123, CHUNK_LENGTH * 2] {
+            let data = (0 .. length).map(|n| n.to_le_bytes()[0]).collect::<Vec<_>>();
+            let mut encrypted = Vec::new();
+
+            Encrypting::with_key(data.as_slice(), keys.current().clone()).read_to_end(&mut encrypted)?;
+            assert_eq!(encrypted[Header::MAGIC.len()], Header::VERSION_STREAM);
+
+            let mut decrypted = Vec::new();
+
+            Decrypting::with_keys(encrypted.as_slice(), keys.clone()).read_to_end(&mut decrypted)?;
+            assert_eq!(decrypted, data);
+            assert_eq!(&**super::decrypt_with(&keys, &encrypted)?.0, data);
+
+            let truncated = &encrypted[.. encrypted.len() - 1];
+
+            assert!(Decrypting::with_keys(truncated, keys.clone()).read_to_end(&mut Vec::new()).is_err());
+        }
+
+        let whole = super::encrypt_with(keys.current(), b"secret")?;
+        let mut decrypted = Vec::new();
+
+        Decrypting::with_keys(whole.as_slice(), keys).read_to_end(&mut decrypted)?;
+        assert_eq!(decrypted, b"secret");
+
+        Ok(())
+    }
 }
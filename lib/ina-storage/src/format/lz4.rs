@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use super::{DataDecode, DataEncode, DataFormat};
+
+/// The magic byte sequence that begins LZ4-compressed data.
+pub const MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+/// An LZ4 compression format error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error<F: Debug + DataFormat> {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An encoding error.
+    #[error(transparent)]
+    Encode(<F as DataEncode>::Error),
+    /// A decoding error.
+    #[error(transparent)]
+    Decode(<F as DataDecode>::Error),
+}
+
+/// Compresses the wrapped format using LZ4.
+///
+/// This trades compression ratio for speed, and has no configurable compression level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lz4<F: Debug + DataFormat> {
+    /// The inner format.
+    inner: F,
+}
+
+impl<F: Debug + DataFormat> Lz4<F> {
+    /// Creates a new [`Lz4<F>`] format.
+    pub const fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataFormat for Lz4<F> {
+    fn extension(&self) -> impl AsRef<OsStr> {
+        format!("{}.lz4", self.inner.extension().as_ref().to_string_lossy())
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataEncode for Lz4<F> {
+    type Error = Error<F>;
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Arc<[u8]>, Self::Error> {
+        let bytes = self.inner.encode(value).map_err(Error::Encode)?;
+        let mut encoder = FrameEncoder::new(Vec::with_capacity(bytes.len()));
+
+        encoder.write_all(&bytes)?;
+
+        let buffer = encoder.finish().map_err(std::io::Error::from)?;
+
+        trace!(before = bytes.len(), after = buffer.len(), "compressed bytes");
+
+        Ok(buffer.into())
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataDecode for Lz4<F> {
+    type Error = Error<F>;
+
+    fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let buffer = super::magic::decompress(bytes).unwrap_or_else(|| self::decompress(bytes))?;
+
+        self.inner.decode(&buffer).map_err(Error::Decode)
+    }
+}
+
+/// Decompresses the given LZ4-compressed bytes.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be decompressed.
+pub(crate) fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = FrameDecoder::new(bytes);
+    let mut buffer = Vec::with_capacity(bytes.len() * 3);
+
+    decoder.read_to_end(&mut buffer)?;
+
+    trace!(before = bytes.len(), after = buffer.len(), "decompressed bytes");
+
+    Ok(buffer)
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+/// Decompresses the given bytes using the compression format identified by their magic byte sequence.
+///
+/// Returns [`None`] if the bytes do not begin with the magic byte sequence of any enabled compression format.
+pub fn decompress(bytes: &[u8]) -> Option<std::io::Result<Vec<u8>>> {
+    #[cfg(feature = "format-compression")]
+    if bytes.starts_with(&super::compression::MAGIC) {
+        return Some(super::compression::decompress(bytes));
+    }
+    #[cfg(feature = "format-zstd")]
+    if bytes.starts_with(&super::zstd::MAGIC) {
+        return Some(super::zstd::decompress(bytes));
+    }
+    #[cfg(feature = "format-lz4")]
+    if bytes.starts_with(&super::lz4::MAGIC) {
+        return Some(super::lz4::decompress(bytes));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(
+        feature = "format-compression",
+        feature = "format-lz4",
+        feature = "format-zstd",
+        feature = "format-json"
+    ))]
+    #[test]
+    fn decode_other_compression_formats() -> anyhow::Result<()> {
+        use crate::format::{Compress, DataDecode, DataEncode, Json, Lz4, Zstd};
+
+        let value = vec![String::from("hello"); 16];
+
+        let bytes = Zstd::new_default(Json).encode(&value)?;
+        assert_eq!(Compress::new_default(Json).decode::<Vec<String>>(&bytes)?, value);
+
+        let bytes = Compress::new_default(Json).encode(&value)?;
+        assert_eq!(Lz4::new(Json).decode::<Vec<String>>(&bytes)?, value);
+
+        let bytes = Lz4::new(Json).encode(&value)?;
+        assert_eq!(Zstd::new_default(Json).decode::<Vec<String>>(&bytes)?, value);
+
+        Ok(())
+    }
+}
@@ -15,6 +15,8 @@
 // <https://www.gnu.org/licenses/>.
 
 use std::ffi::OsStr;
+use std::io::Read;
+use std::path::Path;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
@@ -25,12 +27,17 @@ pub use self::compression::Compress;
 pub use self::encryption::Encrypt;
 #[cfg(feature = "format-json")]
 pub use self::json::Json;
+#[cfg(feature = "format-lz4")]
+pub use self::lz4::Lz4;
 #[cfg(feature = "format-messagepack")]
 pub use self::messagepack::Messagepack;
 #[cfg(feature = "format-postcard")]
 pub use self::postcard::Postcard;
+pub use self::raw::Raw;
+#[cfg(feature = "format-zstd")]
+pub use self::zstd::Zstd;
 
-/// The compression format.
+/// The gzip compression format.
 #[cfg(feature = "format-compression")]
 pub mod compression;
 /// The encryption format.
@@ -39,12 +46,37 @@ pub mod encryption;
 /// The JSON format.
 #[cfg(feature = "format-json")]
 pub mod json;
+/// The LZ4 compression format.
+#[cfg(feature = "format-lz4")]
+pub mod lz4;
+/// Detects compression formats by their magic byte sequences.
+#[cfg(any(feature = "format-compression", feature = "format-lz4", feature = "format-zstd"))]
+pub(crate) mod magic;
 /// The Messagepack format.
 #[cfg(feature = "format-messagepack")]
 pub mod messagepack;
 /// The Postcard format.
 #[cfg(feature = "format-postcard")]
 pub mod postcard;
+/// The raw format.
+pub mod raw;
+/// The Zstandard compression format.
+#[cfg(feature = "format-zstd")]
+pub mod zstd;
+
+/// A reader of bytes that are being encoded or decoded by a [`StreamFormat`].
+pub type StreamReader<'r> = Box<dyn Read + Send + 'r>;
+
+/// The file extensions of every compression format, in the order that they are tried when looking for data that was
+/// written using a different compression format.
+const COMPRESSION_EXTENSIONS: &[&str] = &[
+    #[cfg(feature = "format-compression")]
+    "gz",
+    #[cfg(feature = "format-zstd")]
+    "zst",
+    #[cfg(feature = "format-lz4")]
+    "lz4",
+];
 
 /// A value that encodes and decodes generic data.
 pub trait DataFormat: DataDecode + DataEncode {
@@ -77,3 +109,57 @@ pub trait DataDecode {
     /// This function will return an error if the value cannot be decoded.
     fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error>;
 }
+
+/// A value that encodes and decodes streams of raw bytes, keeping memory usage bounded regardless of their length.
+pub trait StreamFormat: DataFormat {
+    /// Returns a reader that yields the encoded form of the bytes yielded by the given reader.
+    ///
+    /// Bytes are encoded as the returned reader is read from, so any errors are returned by its reads.
+    fn encode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r>;
+
+    /// Returns a reader that yields the decoded form of the bytes yielded by the given reader.
+    ///
+    /// Bytes are decoded as the returned reader is read from, so any errors are returned by its reads.
+    fn decode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r>;
+}
+
+/// Returns the given path with its compression extension replaced by that of each other enabled compression format.
+///
+/// If the path does not have a compression extension, an empty list is returned.
+pub(crate) fn alternate_paths(path: &Path) -> Vec<Box<Path>> {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else { return Vec::new() };
+    let parts = name.split('.').collect::<Vec<_>>();
+
+    // The first part is the file stem, which is never treated as an extension.
+    let Some(index) =
+        parts.iter().skip(1).position(|part| COMPRESSION_EXTENSIONS.contains(part)).map(|index| index + 1)
+    else {
+        return Vec::new();
+    };
+
+    COMPRESSION_EXTENSIONS
+        .iter()
+        .filter(|extension| **extension != parts[index])
+        .map(|extension| {
+            let mut parts = parts.clone();
+
+            parts[index] = extension;
+
+            path.with_file_name(parts.join(".")).into_boxed_path()
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "format-compression", feature = "format-zstd"))]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn replace_compression_extension() {
+        let paths = super::alternate_paths(Path::new("role/1/2.pack.gz.cha"));
+
+        assert!(paths.contains(&Path::new("role/1/2.pack.zst.cha").into()));
+        assert!(!paths.contains(&Path::new("role/1/2.pack.gz.cha").into()));
+        assert!(super::alternate_paths(Path::new("role/1/gz.pack")).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::ffi::OsStr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::{DataDecode, DataEncode, DataFormat, StreamFormat, StreamReader};
+
+/// An error returned when encoding or decoding values using the [`Raw`] format.
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error("raw data can only be streamed")]
+pub struct Error;
+
+/// The raw data format, which leaves bytes unchanged.
+///
+/// This is the innermost format of streamed data, and cannot be used to encode or decode values.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Raw;
+
+impl DataFormat for Raw {
+    fn extension(&self) -> impl AsRef<OsStr> {
+        "bin"
+    }
+}
+
+impl DataEncode for Raw {
+    type Error = Error;
+
+    fn encode<T: Serialize>(&self, _: &T) -> Result<Arc<[u8]>, Self::Error> {
+        Err(Error)
+    }
+}
+
+impl DataDecode for Raw {
+    type Error = Error;
+
+    fn decode<T: for<'de> Deserialize<'de>>(&self, _: &[u8]) -> Result<T, Self::Error> {
+        Err(Error)
+    }
+}
+
+impl StreamFormat for Raw {
+    fn encode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        reader
+    }
+
+    fn decode_stream<'r>(&self, reader: StreamReader<'r>) -> StreamReader<'r> {
+        reader
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use super::{DataDecode, DataEncode, DataFormat};
+
+/// The magic byte sequence that begins Zstandard-compressed data.
+pub const MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// A Zstandard compression format error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error<F: Debug + DataFormat> {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An encoding error.
+    #[error(transparent)]
+    Encode(<F as DataEncode>::Error),
+    /// A decoding error.
+    #[error(transparent)]
+    Decode(<F as DataDecode>::Error),
+}
+
+/// Compresses the wrapped format using Zstandard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Zstd<F: Debug + DataFormat> {
+    /// The inner format.
+    inner: F,
+    /// The compression level.
+    level: i32,
+}
+
+impl<F: Debug + DataFormat> Zstd<F> {
+    /// Creates a new [`Zstd<F>`] format.
+    ///
+    /// The given level should be within the range `1..=22`.
+    pub const fn new(inner: F, level: u8) -> Self {
+        Self { inner, level: level as i32 }
+    }
+
+    /// Creates a new [`Zstd<F>`] format using a fast level of compression.
+    pub const fn new_fast(inner: F) -> Self {
+        Self::new(inner, 1)
+    }
+
+    /// Creates a new [`Zstd<F>`] format using the default level of compression.
+    pub const fn new_default(inner: F) -> Self {
+        Self::new(inner, 3)
+    }
+
+    /// Creates a new [`Zstd<F>`] format using the best level of compression.
+    pub const fn new_best(inner: F) -> Self {
+        Self::new(inner, 19)
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataFormat for Zstd<F> {
+    fn extension(&self) -> impl AsRef<OsStr> {
+        format!("{}.zst", self.inner.extension().as_ref().to_string_lossy())
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataEncode for Zstd<F> {
+    type Error = Error<F>;
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Arc<[u8]>, Self::Error> {
+        let bytes = self.inner.encode(value).map_err(Error::Encode)?;
+        let buffer = zstd::encode_all(&(*bytes), self.level)?;
+
+        trace!(before = bytes.len(), after = buffer.len(), "compressed bytes");
+
+        Ok(buffer.into())
+    }
+}
+
+impl<F: Debug + DataFormat + 'static> DataDecode for Zstd<F> {
+    type Error = Error<F>;
+
+    fn decode<T: for<'de> Deserialize<'de>>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let buffer = super::magic::decompress(bytes).unwrap_or_else(|| self::decompress(bytes))?;
+
+        self.inner.decode(&buffer).map_err(Error::Decode)
+    }
+}
+
+/// Decompresses the given Zstandard-compressed bytes.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be decompressed.
+pub(crate) fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let buffer = zstd::decode_all(bytes)?;
+
+    trace!(before = bytes.len(), after = buffer.len(), "decompressed bytes");
+
+    Ok(buffer)
+}
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::Path;
+
+#[cfg(any(feature = "format-json", feature = "format-messagepack"))]
+use serde::de::IgnoredAny;
+use tracing::{debug, warn};
+
+#[cfg(any(feature = "format-json", feature = "format-messagepack"))]
+use crate::format::DataDecode;
+use crate::index::INDEX_DIRECTORY;
+
+/// The directory into which problematic data is moved when quarantined.
+pub const QUARANTINE_DIRECTORY: &str = "quarantine";
+
+/// A problem found while checking stored data.
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Problem {
+    /// The data at the given path could not be read or decoded using its format chain.
+    Corrupt(Box<Path>, Box<str>),
+    /// The data at the given path has a malformed format chain, such as one with an unknown outer format.
+    Orphaned(Box<Path>),
+}
+
+impl Problem {
+    /// Returns the path of the problematic data.
+    #[must_use]
+    pub const fn path(&self) -> &Path {
+        match self {
+            Self::Corrupt(path, _) | Self::Orphaned(path) => path,
+        }
+    }
+}
+
+/// The results of checking every stored entry.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Report {
+    /// The number of entries that were checked.
+    pub checked: usize,
+    /// The problems that were found.
+    pub problems: Vec<Problem>,
+    /// The paths that problematic data was moved into, if it was quarantined.
+    pub quarantined: Vec<Box<Path>>,
+}
+
+impl Report {
+    /// Returns `true` if no problems were found.
+    #[must_use]
+    pub const fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Checks whether every stored entry can be decoded using its format chain.
+///
+/// If `quarantine` is set, corrupt and orphaned data is moved into the [`QUARANTINE_DIRECTORY`], which is skipped when
+/// checking. Secondary indexes are also skipped, as they are not stored using a format chain.
+///
+/// # Errors
+///
+/// This function will return an error if the stored data could not be listed or quarantined.
+pub async fn check(quarantine: bool) -> anyhow::Result<Report> {
+    let mut report = Report::default();
+
+    for path in crate::thread::list(Path::new("").into()).await? {
+        if path.starts_with(QUARANTINE_DIRECTORY) || path.starts_with(INDEX_DIRECTORY) {
+            continue;
+        }
+
+        let result = crate::thread::read_bytes(path.clone()).await.and_then(|bytes| self::decode(&path, &bytes));
+
+        report.checked += 1;
+
+        let Some(problem) = self::into_problem(path, result) else { continue };
+
+        if quarantine {
+            let into = Path::new(QUARANTINE_DIRECTORY).join(problem.path()).into_boxed_path();
+
+            crate::thread::rename(problem.path().into(), into.clone()).await?;
+            report.quarantined.push(into);
+        }
+
+        report.problems.push(problem);
+    }
+
+    debug!(checked = report.checked, problems = report.problems.len(), "checked stored data");
+
+    Ok(report)
+}
+
+/// Checks whether every stored entry can be decoded using its format chain.
+///
+/// If `quarantine` is set, corrupt and orphaned data is moved into the [`QUARANTINE_DIRECTORY`], which is skipped when
+/// checking. Secondary indexes are also skipped, as they are not stored using a format chain.
+///
+/// # Errors
+///
+/// This function will return an error if the stored data could not be listed or quarantined.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+pub fn blocking_check(quarantine: bool) -> anyhow::Result<Report> {
+    let mut report = Report::default();
+
+    for path in crate::thread::blocking_list(Path::new("").into())? {
+        if path.starts_with(QUARANTINE_DIRECTORY) || path.starts_with(INDEX_DIRECTORY) {
+            continue;
+        }
+
+        let result = crate::thread::blocking_read_bytes(path.clone()).and_then(|bytes| self::decode(&path, &bytes));
+
+        report.checked += 1;
+
+        let Some(problem) = self::into_problem(path, result) else { continue };
+
+        if quarantine {
+            let into = Path::new(QUARANTINE_DIRECTORY).join(problem.path()).into_boxed_path();
+
+            crate::thread::blocking_rename(problem.path().into(), into.clone())?;
+            report.quarantined.push(into);
+        }
+
+        report.problems.push(problem);
+    }
+
+    debug!(checked = report.checked, problems = report.problems.len(), "checked stored data");
+
+    Ok(report)
+}
+
+/// Converts the result of decoding the data at the given path into a problem, if any.
+fn into_problem(path: Box<Path>, result: anyhow::Result<bool>) -> Option<Problem> {
+    match result {
+        Ok(true) => None,
+        Ok(false) => {
+            warn!(?path, "found orphaned data");
+
+            Some(Problem::Orphaned(path))
+        }
+        Err(error) => {
+            warn!(?path, %error, "found corrupt data");
+
+            Some(Problem::Corrupt(path, error.to_string().into_boxed_str()))
+        }
+    }
+}
+
+/// Decodes the given stored bytes using the format chain described by the extensions of the given path.
+///
+/// Returns `false` if the path's extensions form a malformed format chain, such as one with an unknown outer format.
+/// Data without a known format, such as raw streamed data, is not decoded. Formats that are not self-describing, like
+/// Postcard, can only be checked for their outer formats, and blobs are instead checked against their identifiers.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be decoded.
+fn decode(path: &Path, bytes: &[u8]) -> anyhow::Result<bool> {
+    // Blobs are stored as raw bytes, so they are checked against their identifier instead.
+    #[cfg(feature = "blob")]
+    if let Some(id) = crate::blob::BlobId::from_path(path) {
+        return crate::blob::verify(id, bytes.into()).map(|_| true);
+    }
+
+    let Some(name) = path.file_name().and_then(OsStr::to_str) else { return Ok(false) };
+    let extensions = name.split('.').skip(1).collect::<Vec<_>>();
+    // Data without a format extension is raw data, which has no structure to check.
+    let Some((base, outer)) = extensions.split_first() else { return Ok(true) };
+    let mut bytes = Cow::Borrowed(crate::stored::split_header(bytes).1);
+
+    for extension in outer.iter().rev() {
+        let Some(result) = self::decode_outer(extension, &bytes) else { return Ok(false) };
+
+        bytes = Cow::Owned(result?);
+    }
+
+    // A single unknown extension only names raw data, while an unknown base beneath outer formats is malformed.
+    Ok(self::decode_base(base, &bytes).transpose()?.is_some() || outer.is_empty())
+}
+
+/// Removes the outer format identified by the given extension from the given bytes.
+///
+/// Returns [`None`] if the extension does not identify an enabled outer format.
+fn decode_outer(extension: &str, bytes: &[u8]) -> Option<anyhow::Result<Vec<u8>>> {
+    if bytes.is_empty() {
+        return Some(Err(anyhow::anyhow!("data is empty")));
+    }
+
+    match extension {
+        #[cfg(feature = "format-encryption")]
+        crate::format::encryption::EXTENSION => {
+            Some(crate::format::encryption::decrypt(bytes).map(|bytes| bytes.to_vec()).map_err(Into::into))
+        }
+        #[cfg(any(feature = "format-compression", feature = "format-lz4", feature = "format-zstd"))]
+        "gz" | "zst" | "lz4" => Some(crate::format::magic::decompress(bytes).map_or_else(
+            || Err(anyhow::anyhow!("data is not compressed using any enabled format")),
+            |result| result.map_err(Into::into),
+        )),
+        _ => None,
+    }
+}
+
+/// Decodes the given bytes using the base format identified by the given extension.
+///
+/// Returns [`None`] if the extension does not identify an enabled base format.
+fn decode_base(extension: &str, bytes: &[u8]) -> Option<anyhow::Result<()>> {
+    // Raw data, such as streamed data, may hold any bytes, including none.
+    if extension == "bin" {
+        return Some(Ok(()));
+    }
+
+    if bytes.is_empty() {
+        return Some(Err(anyhow::anyhow!("data is empty")));
+    }
+
+    match extension {
+        #[cfg(feature = "format-json")]
+        "json" => Some(crate::format::Json.decode::<IgnoredAny>(bytes).map(|_| ()).map_err(Into::into)),
+        #[cfg(feature = "format-messagepack")]
+        "pack" => Some(crate::format::Messagepack.decode::<IgnoredAny>(bytes).map(|_| ()).map_err(Into::into)),
+        #[cfg(feature = "format-postcard")]
+        "card" => Some(Ok(())),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "format-compression", feature = "format-messagepack"))]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn decode_format_chains() -> anyhow::Result<()> {
+        use crate::format::{Compress, DataEncode, Messagepack};
+
+        let bytes = Compress::new_fast(Messagepack).encode(&vec![1_u8, 2, 3])?;
+
+        assert!(super::decode(Path::new("role/1/2.pack.gz"), &bytes)?);
+        assert!(super::decode(Path::new("role/1/2.pack.gz"), &bytes[.. bytes.len() / 2]).is_err());
+        assert!(!super::decode(Path::new("role/1/2.pack.xz"), &bytes)?);
+        assert!(!super::decode(Path::new("role/1/2.txt.gz"), &bytes)?);
+        assert!(super::decode(Path::new("role/1/2"), &bytes)?);
+        assert!(super::decode(Path::new("role/1/2.txt"), &bytes)?);
+        assert!(super::decode(Path::new("role/1/2.bin"), &[])?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::bail;
+use tracing::debug;
+
+use crate::Storage;
+use crate::system::{DataReader, DataWriter};
+
+/// The directory within which secondary indexes are stored.
+pub const INDEX_DIRECTORY: &str = "index";
+
+/// A change to the entry of a stored value within a secondary index.
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum IndexChange {
+    /// Sets the keys of the value.
+    Insert(Box<[Box<str>]>),
+    /// Moves the value's keys to the given path.
+    Rename(Box<Path>),
+    /// Removes the value from the index.
+    Remove,
+}
+
+/// A secondary index, mapping the paths of stored values to their keys.
+///
+/// Indexes are stored as plain text, with one line per value containing its path and keys separated by tabs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Index {
+    /// The keys of each indexed value.
+    entries: BTreeMap<Box<Path>, Box<[Box<str>]>>,
+}
+
+impl Index {
+    /// Parses an index from the given bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the bytes are not valid UTF-8.
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        let entries = std::str::from_utf8(bytes)?
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split('\t');
+                let path = Path::new(parts.next().filter(|path| !path.is_empty())?).into();
+
+                Some((path, parts.map(Box::from).collect()))
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    /// Returns the index encoded as bytes.
+    #[must_use]
+    pub fn to_bytes(&self) -> Box<[u8]> {
+        let mut output = String::new();
+
+        for (path, keys) in &self.entries {
+            output.push_str(&path.to_string_lossy());
+
+            for key in keys {
+                output.push('\t');
+                output.push_str(key);
+            }
+
+            output.push('\n');
+        }
+
+        output.into_bytes().into_boxed_slice()
+    }
+
+    /// Returns `true` if the index contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the paths of all values with the given key.
+    pub fn find(&self, key: &str) -> impl Iterator<Item = &Path> {
+        self.entries.iter().filter(move |(_, keys)| keys.iter().any(|k| &**k == key)).map(|(path, _)| &**path)
+    }
+
+    /// Returns the path and keys of every indexed value.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &[Box<str>])> {
+        self.entries.iter().map(|(path, keys)| (&**path, &**keys))
+    }
+
+    /// Applies the given change to the value at the given path.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a key or path contains a tab or newline.
+    pub fn apply(&mut self, path: &Path, change: IndexChange) -> anyhow::Result<()> {
+        match change {
+            IndexChange::Insert(keys) => {
+                if keys.iter().map(|key| &**key).chain(path.to_str()).any(self::is_invalid) {
+                    bail!("index entries for '{}' may not contain tabs or newlines", path.display());
+                }
+
+                self.entries.insert(path.into(), keys);
+            }
+            IndexChange::Rename(into) => {
+                if into.to_str().is_some_and(self::is_invalid) {
+                    bail!("index entries for '{}' may not contain tabs or newlines", into.display());
+                }
+
+                if let Some(keys) = self.entries.remove(path) {
+                    self.entries.insert(into, keys);
+                } else {
+                    self.entries.remove(&into);
+                }
+            }
+            IndexChange::Remove => {
+                self.entries.remove(path);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Storage {
+    /// Applies the given change to the entry of the value at the given path within the given index.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read or written.
+    pub fn blocking_update_index(&self, index: &Path, path: &Path, change: IndexChange) -> anyhow::Result<()> {
+        let mut entries = self.blocking_load_index(index)?;
+
+        entries.apply(path, change)?;
+
+        self.blocking_store_index(index, &entries)
+    }
+
+    /// Applies the given change to the entry of the value at the given path within the given index.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read or written.
+    pub async fn update_index(&self, index: &Path, path: &Path, change: IndexChange) -> anyhow::Result<()> {
+        let mut entries = self.load_index(index).await?;
+
+        entries.apply(path, change)?;
+
+        self.store_index(index, &entries).await
+    }
+
+    /// Removes the entries of values that no longer exist from the given index, returning the number of removed
+    /// entries.
+    ///
+    /// Values that are deleted without updating their indexes, such as expired values, leave their entries behind.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index or its values could not be read, or the index could not be
+    /// written.
+    pub fn blocking_prune_index(&self, index: &Path) -> anyhow::Result<usize> {
+        let mut entries = self.blocking_load_index(index)?;
+        let mut stale = Vec::new();
+
+        for (path, _) in entries.iter() {
+            if !self.blocking_exists(path)? {
+                stale.push(Box::<Path>::from(path));
+            }
+        }
+
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        for path in &stale {
+            entries.apply(path, IndexChange::Remove)?;
+        }
+
+        self.blocking_store_index(index, &entries)?;
+
+        debug!(?index, count = stale.len(), "pruned stale index entries");
+
+        Ok(stale.len())
+    }
+
+    /// Removes the entries of values that no longer exist from the given index, returning the number of removed
+    /// entries.
+    ///
+    /// Values that are deleted without updating their indexes, such as expired values, leave their entries behind.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index or its values could not be read, or the index could not be
+    /// written.
+    pub async fn prune_index(&self, index: &Path) -> anyhow::Result<usize> {
+        let mut entries = self.load_index(index).await?;
+        let mut stale = Vec::new();
+
+        for (path, _) in entries.iter() {
+            if !self.exists(path).await? {
+                stale.push(Box::<Path>::from(path));
+            }
+        }
+
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        for path in &stale {
+            entries.apply(path, IndexChange::Remove)?;
+        }
+
+        self.store_index(index, &entries).await?;
+
+        debug!(?index, count = stale.len(), "pruned stale index entries");
+
+        Ok(stale.len())
+    }
+
+    /// Returns the paths of all values with the given key within the given index.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read.
+    pub fn blocking_find_in_index(&self, index: &Path, key: &str) -> anyhow::Result<Box<[Box<Path>]>> {
+        Ok(self.blocking_load_index(index)?.find(key).map(Box::from).collect())
+    }
+
+    /// Returns the paths of all values with the given key within the given index.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read.
+    pub async fn find_in_index(&self, index: &Path, key: &str) -> anyhow::Result<Box<[Box<Path>]>> {
+        Ok(self.load_index(index).await?.find(key).map(Box::from).collect())
+    }
+
+    /// Returns the given index, or an empty index if it does not exist.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read.
+    pub(crate) fn blocking_load_index(&self, index: &Path) -> anyhow::Result<Index> {
+        if self.blocking_exists(index)? { Index::parse(&self.blocking_read(index)?) } else { Ok(Index::default()) }
+    }
+
+    /// Returns the given index, or an empty index if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be read.
+    pub(crate) async fn load_index(&self, index: &Path) -> anyhow::Result<Index> {
+        if self.exists(index).await? { Index::parse(&self.read(index).await?) } else { Ok(Index::default()) }
+    }
+
+    /// Writes the given entries into the given index, deleting the index if it is empty.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be written or deleted.
+    fn blocking_store_index(&self, index: &Path, entries: &Index) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            if self.blocking_exists(index)? { self.blocking_delete(index) } else { Ok(()) }
+        } else {
+            self.blocking_write(index, &entries.to_bytes())
+        }
+    }
+
+    /// Writes the given entries into the given index, deleting the index if it is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index could not be written or deleted.
+    async fn store_index(&self, index: &Path, entries: &Index) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            if self.exists(index).await? { self.delete(index).await } else { Ok(()) }
+        } else {
+            self.write(index, &entries.to_bytes()).await
+        }
+    }
+}
+
+/// Returns the path of the given secondary index of the given type.
+#[must_use]
+pub fn path_for(kind: &str, index: &str) -> Box<Path> {
+    Path::new(INDEX_DIRECTORY).join(kind).join(index).into_boxed_path()
+}
+
+/// Collects the given index keys.
+pub fn keys<I>(keys: I) -> Box<[Box<str>]>
+where
+    I: IntoIterator<Item: ToString>,
+{
+    keys.into_iter().map(|key| key.to_string().into_boxed_str()).collect()
+}
+
+/// Returns `true` if the given index key or path cannot be stored within an index.
+fn is_invalid(value: &str) -> bool {
+    value.contains(['\t', '\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Index, IndexChange};
+
+    #[test]
+    fn apply_index_changes() -> anyhow::Result<()> {
+        let mut index = Index::default();
+
+        index.apply(Path::new("role/1/2.pack"), IndexChange::Insert(super::keys([3, 4])))?;
+        index.apply(Path::new("role/1/5.pack"), IndexChange::Insert(super::keys([4])))?;
+
+        assert!(index.apply(Path::new("role/1/6.pack"), IndexChange::Insert(["a\tb".into()].into())).is_err());
+        assert_eq!(index.find("4").count(), 2);
+
+        index.apply(Path::new("role/1/2.pack"), IndexChange::Rename(Path::new("role/1/7.pack").into()))?;
+        index.apply(Path::new("role/1/5.pack"), IndexChange::Remove)?;
+
+        let index = Index::parse(&index.to_bytes())?;
+
+        assert_eq!(index.find("3").collect::<Vec<_>>(), [Path::new("role/1/7.pack")]);
+        assert_eq!(index.find("4").collect::<Vec<_>>(), [Path::new("role/1/7.pack")]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,620 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tracing::{debug, warn};
+
+use crate::Storage;
+use crate::batch::Operation;
+use crate::index::IndexChange;
+
+/// The operations of each journaled request that must be replayed, ordered from the oldest to the most recent.
+pub type Pending = Box<[Box<[Operation]>]>;
+
+/// The name of the journal file, which is created within the data directory.
+pub const JOURNAL_FILE: &str = ".journal";
+
+/// The length in bytes that the journal may reach before it is compacted, once every recorded request was applied.
+const COMPACT_LENGTH: u64 = 4 * 1024 * 1024;
+/// The length of a frame's header, which contains the length and checksum of its payload.
+const HEADER_LENGTH: usize = size_of::<u32>() * 2;
+
+/// The tag of a frame that records the operations of a request.
+const TAG_RECORD: u8 = 0;
+/// The tag of a frame that marks a recorded request as applied.
+const TAG_COMMIT: u8 = 1;
+
+/// An append-only journal of requests that modify stored data.
+///
+/// Each request is recorded before it is sent to the storage thread, and is marked as applied once the storage thread
+/// has handled it. As requests may be handled concurrently, they are not necessarily applied in the order that they
+/// were recorded. Requests that were recorded but never applied, such as those still queued when the process was
+/// killed, are replayed when the storage thread is next started.
+///
+/// Recording a request only buffers it in memory. The storage thread synchronizes the journal with the disk before it
+/// applies a request, writing every buffered frame at once, so that requests that are recorded together share a single
+/// write.
+#[derive(Debug)]
+pub struct Journal {
+    /// The frames that have not yet been written, alongside the state of every recorded request.
+    buffer: Mutex<Buffer>,
+    /// The journal's file, which is locked while it is written.
+    output: Mutex<Output>,
+    /// The number of bytes written to the journal's file.
+    length: AtomicU64,
+}
+
+/// The frames of a journal that have not yet been written, alongside the state of every recorded request.
+#[derive(Debug)]
+struct Buffer {
+    /// The frames that have not yet been written.
+    frames: Vec<u8>,
+    /// The sequence number of the most recently recorded request.
+    recorded: u64,
+    /// The sequence numbers of every recorded request that was not applied.
+    pending: BTreeSet<u64>,
+}
+
+/// The file of a journal.
+#[derive(Debug)]
+struct Output {
+    /// The journal's file handle.
+    file: File,
+    /// The sequence number of the most recently recorded request that was synchronized with the disk.
+    synced: u64,
+}
+
+impl Journal {
+    /// Opens the journal within the given directory, creating it if it does not exist.
+    ///
+    /// Returns the journal alongside the operations of every recorded request that was not applied, ordered from the
+    /// oldest to the most recent. These requests are considered applied by the returned journal, so they should be
+    /// replayed before the journal is compacted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the journal could not be opened or read.
+    pub fn open(directory: &Path) -> std::io::Result<(Self, Pending)> {
+        std::fs::create_dir_all(directory)?;
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(directory.join(JOURNAL_FILE))?;
+        let mut bytes = Vec::new();
+
+        file.read_to_end(&mut bytes)?;
+
+        let mut pending = BTreeMap::new();
+        let mut sequence = 0;
+        let mut remaining = &*bytes;
+
+        while let Some((frame, rest)) = self::split_frame(remaining) {
+            sequence = sequence.max(self::frame_sequence(&frame));
+
+            match frame {
+                Frame::Record(number, operations) => drop(pending.insert(number, operations)),
+                Frame::Commit(number) => drop(pending.remove(&number)),
+            }
+
+            remaining = rest;
+        }
+
+        let length = (bytes.len() - remaining.len()) as u64;
+
+        // A partially written frame is left behind if the process was killed while recording, so it is discarded.
+        if !remaining.is_empty() {
+            warn!(length = remaining.len(), "discarded incomplete journal frame");
+
+            file.set_len(length)?;
+        }
+
+        debug!(count = pending.len(), "opened journal");
+
+        let journal = Self {
+            buffer: Mutex::new(Buffer { frames: Vec::new(), recorded: sequence, pending: BTreeSet::new() }),
+            output: Mutex::new(Output { file, synced: sequence }),
+            length: AtomicU64::new(length),
+        };
+
+        Ok((journal, pending.into_values().collect()))
+    }
+
+    /// Records the given operations, returning their sequence number.
+    ///
+    /// The record is only buffered, and is written once the journal is synchronized.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the operations could not be encoded.
+    pub fn record(&self, operations: &[Operation]) -> std::io::Result<u64> {
+        let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+        let sequence = buffer.recorded + 1;
+
+        self::append(&mut buffer.frames, &self::encode_record(sequence, operations))?;
+
+        buffer.recorded = sequence;
+        buffer.pending.insert(sequence);
+
+        drop(buffer);
+
+        Ok(sequence)
+    }
+
+    /// Marks the recorded request with the given sequence number as applied.
+    ///
+    /// The mark is only buffered, and is written alongside the next synchronized records, as losing it only causes the
+    /// request to be replayed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the mark could not be encoded.
+    pub fn commit(&self, sequence: u64) -> std::io::Result<()> {
+        let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if buffer.pending.remove(&sequence) {
+            self::append(&mut buffer.frames, &self::encode_commit(sequence))?;
+        }
+
+        drop(buffer);
+
+        Ok(())
+    }
+
+    /// Returns whether the journal has grown large enough to be compacted, and every recorded request was applied.
+    pub fn is_compactable(&self) -> bool {
+        self.length.load(Ordering::Relaxed) >= COMPACT_LENGTH
+            && self.buffer.lock().unwrap_or_else(PoisonError::into_inner).pending.is_empty()
+    }
+
+    /// Writes every buffered frame and synchronizes the journal with the disk, unless the request with the given
+    /// sequence number has already been synchronized.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the journal could not be written.
+    pub fn sync(&self, sequence: u64) -> std::io::Result<()> {
+        let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if output.synced >= sequence {
+            return Ok(());
+        }
+
+        let result = self.write(&mut output);
+
+        drop(output);
+
+        result
+    }
+
+    /// Writes every buffered frame and synchronizes the journal with the disk.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the journal could not be written.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let result = self.write(&mut output);
+
+        drop(output);
+
+        result
+    }
+
+    /// Removes every frame from the journal if every recorded request has been applied, returning whether the journal
+    /// was compacted.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the journal could not be truncated.
+    pub fn compact(&self) -> std::io::Result<bool> {
+        let output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if !buffer.pending.is_empty() {
+            return Ok(false);
+        }
+
+        // Every buffered frame marks an applied request, as records are pending until they are applied.
+        buffer.frames.clear();
+
+        let sequence = buffer.recorded;
+
+        drop(buffer);
+
+        output.file.set_len(0)?;
+        output.file.sync_all()?;
+
+        self.length.store(0, Ordering::Relaxed);
+
+        drop(output);
+
+        debug!(sequence, "compacted journal");
+
+        Ok(true)
+    }
+
+    /// Writes every buffered frame into the given file and synchronizes it with the disk.
+    ///
+    /// If the frames could not be written, they are buffered again, so that they are retried by the next write.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frames could not be written.
+    fn write(&self, output: &mut Output) -> std::io::Result<()> {
+        let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+        let frames = std::mem::take(&mut buffer.frames);
+        let recorded = buffer.recorded;
+
+        drop(buffer);
+
+        if let Err(error) = output.file.write_all(&frames).and_then(|()| output.file.sync_data()) {
+            self.buffer.lock().unwrap_or_else(PoisonError::into_inner).frames.splice(0 .. 0, frames);
+
+            return Err(error);
+        }
+
+        output.synced = recorded;
+
+        self.length.fetch_add(frames.len() as u64, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+/// Appends a frame containing the given payload to the given frames.
+///
+/// # Errors
+///
+/// This function will return an error if the payload is too large.
+fn append(frames: &mut Vec<u8>, payload: &[u8]) -> std::io::Result<()> {
+    let Ok(length) = u32::try_from(payload.len()) else {
+        return Err(std::io::Error::other("journal frame is too large"));
+    };
+
+    frames.reserve(HEADER_LENGTH + payload.len());
+    frames.extend_from_slice(&length.to_le_bytes());
+    frames.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    frames.extend_from_slice(payload);
+
+    Ok(())
+}
+
+impl Storage {
+    /// Applies the given operations that were recorded within the journal, returning the number of replayed requests.
+    ///
+    /// Each request is applied all-or-nothing. Requests that fail to apply are skipped, as they may have already been
+    /// applied before the journal marked them as such.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    pub fn blocking_replay(&mut self, pending: &[Box<[Operation]>]) -> usize {
+        let mut count = 0;
+
+        for operations in pending {
+            match self.blocking_apply(operations) {
+                Ok(()) => count += 1,
+                Err(error) => warn!(%error, "skipped journaled request"),
+            }
+        }
+
+        debug!(count, "replayed journal");
+
+        count
+    }
+
+    /// Applies the given operations that were recorded within the journal, returning the number of replayed requests.
+    ///
+    /// Each request is applied all-or-nothing. Requests that fail to apply are skipped, as they may have already been
+    /// applied before the journal marked them as such.
+    pub async fn replay(&mut self, pending: &[Box<[Operation]>]) -> usize {
+        let mut count = 0;
+
+        for operations in pending {
+            match self.apply(operations).await {
+                Ok(()) => count += 1,
+                Err(error) => warn!(%error, "skipped journaled request"),
+            }
+        }
+
+        debug!(count, "replayed journal");
+
+        count
+    }
+}
+
+/// Returns `true` if the given path refers to the journal file.
+#[cfg(feature = "system-file")]
+pub(crate) fn is_journal(path: &Path) -> bool {
+    path.file_name() == Some(std::ffi::OsStr::new(JOURNAL_FILE))
+}
+
+/// A single frame within the journal.
+#[derive(Debug)]
+enum Frame {
+    /// Records the operations of the request with the given sequence number.
+    Record(u64, Box<[Operation]>),
+    /// Marks the request with the given sequence number as applied.
+    Commit(u64),
+}
+
+/// Returns the sequence number of the given frame.
+const fn frame_sequence(frame: &Frame) -> u64 {
+    match frame {
+        Frame::Record(sequence, _) | Frame::Commit(sequence) => *sequence,
+    }
+}
+
+/// Splits the first frame from the given bytes, returning the frame and the remaining bytes.
+///
+/// Returns [`None`] if the bytes do not start with a complete and valid frame.
+fn split_frame(bytes: &[u8]) -> Option<(Frame, &[u8])> {
+    let mut reader = Reader(bytes);
+    let length = usize::try_from(reader.u32()?).ok()?;
+    let checksum = reader.u32()?;
+    let mut payload = Reader(reader.take(length)?);
+
+    if crc32fast::hash(payload.0) != checksum {
+        return None;
+    }
+
+    let frame = match payload.u8()? {
+        TAG_RECORD => {
+            let sequence = payload.u64()?;
+            let count = payload.u32()?;
+
+            Frame::Record(sequence, (0 .. count).map(|_| payload.operation()).collect::<Option<_>>()?)
+        }
+        TAG_COMMIT => Frame::Commit(payload.u64()?),
+        _ => return None,
+    };
+
+    Some((frame, reader.0))
+}
+
+/// Encodes a frame payload that records the given operations.
+fn encode_record(sequence: u64, operations: &[Operation]) -> Vec<u8> {
+    let mut writer = Writer(vec![TAG_RECORD]);
+
+    writer.u64(sequence);
+    writer.length(operations.len());
+
+    for operation in operations {
+        writer.operation(operation);
+    }
+
+    writer.0
+}
+
+/// Encodes a frame payload that marks the request with the given sequence number as applied.
+fn encode_commit(sequence: u64) -> Vec<u8> {
+    let mut writer = Writer(vec![TAG_COMMIT]);
+
+    writer.u64(sequence);
+
+    writer.0
+}
+
+/// Encodes the values within a frame payload.
+struct Writer(Vec<u8>);
+
+impl Writer {
+    /// Encodes a length.
+    fn length(&mut self, length: usize) {
+        // Frames are limited to `u32::MAX` bytes, so any longer value is rejected when the frame is appended anyway.
+        self.0.extend_from_slice(&u32::try_from(length).unwrap_or(u32::MAX).to_le_bytes());
+    }
+
+    /// Encodes a number.
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Encodes a byte slice.
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.length(bytes.len());
+        self.0.extend_from_slice(bytes);
+    }
+
+    /// Encodes a path.
+    fn path(&mut self, path: &Path) {
+        self.bytes(path.to_string_lossy().as_bytes());
+    }
+
+    /// Encodes an operation.
+    fn operation(&mut self, operation: &Operation) {
+        match operation {
+            Operation::Write(path, bytes) => {
+                self.0.push(0);
+                self.path(path);
+                self.bytes(bytes);
+            }
+            Operation::Rename(from, into) => {
+                self.0.push(1);
+                self.path(from);
+                self.path(into);
+            }
+            Operation::Delete(path) => {
+                self.0.push(2);
+                self.path(path);
+            }
+            Operation::Index(index, path, change) => {
+                self.0.push(3);
+                self.path(index);
+                self.path(path);
+
+                match change {
+                    IndexChange::Insert(keys) => {
+                        self.0.push(0);
+                        self.length(keys.len());
+
+                        for key in keys {
+                            self.bytes(key.as_bytes());
+                        }
+                    }
+                    IndexChange::Rename(into) => {
+                        self.0.push(1);
+                        self.path(into);
+                    }
+                    IndexChange::Remove => self.0.push(2),
+                }
+            }
+        }
+    }
+}
+
+/// Decodes the values within a frame payload.
+struct Reader<'b>(&'b [u8]);
+
+impl<'b> Reader<'b> {
+    /// Decodes the given number of bytes.
+    fn take(&mut self, length: usize) -> Option<&'b [u8]> {
+        let (bytes, rest) = self.0.split_at_checked(length)?;
+
+        self.0 = rest;
+
+        Some(bytes)
+    }
+
+    /// Decodes a byte.
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1)?.first().copied()
+    }
+
+    /// Decodes a 32-bit number.
+    fn u32(&mut self) -> Option<u32> {
+        self.take(size_of::<u32>())?.try_into().ok().map(u32::from_le_bytes)
+    }
+
+    /// Decodes a 64-bit number.
+    fn u64(&mut self) -> Option<u64> {
+        self.take(size_of::<u64>())?.try_into().ok().map(u64::from_le_bytes)
+    }
+
+    /// Decodes a byte slice.
+    fn bytes(&mut self) -> Option<&'b [u8]> {
+        let length = usize::try_from(self.u32()?).ok()?;
+
+        self.take(length)
+    }
+
+    /// Decodes a string.
+    fn str(&mut self) -> Option<&'b str> {
+        std::str::from_utf8(self.bytes()?).ok()
+    }
+
+    /// Decodes a path.
+    fn path(&mut self) -> Option<Box<Path>> {
+        Some(Path::new(self.str()?).into())
+    }
+
+    /// Decodes an operation.
+    fn operation(&mut self) -> Option<Operation> {
+        Some(match self.u8()? {
+            0 => Operation::Write(self.path()?, Arc::from(self.bytes()?)),
+            1 => Operation::Rename(self.path()?, self.path()?),
+            2 => Operation::Delete(self.path()?),
+            3 => {
+                let index = self.path()?;
+                let path = self.path()?;
+                let change = match self.u8()? {
+                    0 => {
+                        let count = self.u32()?;
+
+                        IndexChange::Insert((0 .. count).map(|_| self.str().map(Box::from)).collect::<Option<_>>()?)
+                    }
+                    1 => IndexChange::Rename(self.path()?),
+                    2 => IndexChange::Remove,
+                    _ => return None,
+                };
+
+                Operation::Index(index, path, change)
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+    use std::sync::PoisonError;
+
+    use super::Journal;
+    use crate::batch::Operation;
+    use crate::index::IndexChange;
+
+    #[test]
+    fn replay_unapplied_requests() -> anyhow::Result<()> {
+        let directory = std::env::temp_dir().join(format!("ina-journal-{}", std::process::id()));
+        let write = Operation::Write(Path::new("a").into(), b"data".as_slice().into());
+        let index = Operation::Index(
+            Path::new("index/a/b").into(),
+            Path::new("a").into(),
+            IndexChange::Insert(crate::index::keys(["1", "2"])),
+        );
+
+        let (journal, pending) = Journal::open(&directory)?;
+
+        assert!(pending.is_empty());
+
+        let first = journal.record(std::slice::from_ref(&write))?;
+        let second = journal.record(&[Operation::Delete(Path::new("b").into()), index.clone()])?;
+
+        journal.commit(first)?;
+        // Synchronizing the first request also writes every frame that was buffered alongside it.
+        journal.sync(first)?;
+        journal.sync(second)?;
+
+        // Simulate a frame that was only partially written before the process was killed.
+        journal.output.lock().unwrap_or_else(PoisonError::into_inner).file.write_all(&[1, 2, 3])?;
+
+        drop(journal);
+
+        let (journal, pending) = Journal::open(&directory)?;
+
+        assert_eq!(&*pending, [vec![Operation::Delete(Path::new("b").into()), index].into_boxed_slice()]);
+        assert_eq!(journal.record(&[write])?, 3);
+        assert!(!journal.compact()?);
+
+        journal.commit(3)?;
+
+        assert!(journal.compact()?);
+
+        drop(journal);
+
+        let (_, pending) = Journal::open(&directory)?;
+
+        assert!(pending.is_empty());
+
+        std::fs::remove_dir_all(directory)?;
+
+        Ok(())
+    }
+}
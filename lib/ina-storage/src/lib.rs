@@ -16,34 +16,71 @@
 
 //! Provides data storage solutions for 1N4.
 
-#[cfg(feature = "caching")]
-use std::collections::HashMap;
+use std::borrow::Cow;
 use std::fmt::Display;
+use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-#[cfg(feature = "caching")]
-use tokio::sync::RwLock;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
 use tracing::debug;
 
+#[cfg(feature = "caching")]
+use crate::cache::{Cache, CacheStats};
+use crate::quota::Usage;
 use crate::settings::Settings;
-use crate::system::{DataReader, DataSystem, DataWriter};
+use crate::system::{BlockingDataStream, DataReader, DataStream, DataSystem, DataWriter};
+use crate::watch::{Event, Watchers};
 
-#[cfg(all(not(feature = "system-file"), not(feature = "system-memory")))]
+#[cfg(all(not(feature = "system-file"), not(feature = "system-memory"), not(feature = "system-sqlite")))]
 compile_error!("at least one storage system feature must be enabled");
 
+/// Defines ordered access to stored paths.
+pub mod access;
+/// Defines portable archives of the entire data store.
+#[cfg(feature = "archive")]
+pub mod archive;
+/// Defines batches of storage operations.
+pub mod batch;
+/// Defines content-addressed storage of deduplicated blobs.
+#[cfg(feature = "blob")]
+pub mod blob;
+/// Defines the storage cache.
+#[cfg(feature = "caching")]
+pub mod cache;
+/// Defines checksum trailers for stored data.
+pub mod checksum;
+/// Defines the expiry of stored data.
+pub mod expiry;
 /// Defines data storage formats.
 pub mod format;
+/// Defines checks of the integrity of stored data.
+pub mod fsck;
+/// Defines secondary indexes of stored values.
+pub mod index;
+/// Defines the write-ahead journal of the storage thread.
+pub mod journal;
+/// Defines per-group storage usage and quotas.
+pub mod quota;
 /// Defines the storage system's settings.
 pub mod settings;
+/// Defines point-in-time snapshots of all stored data.
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
 /// Defines a trait for stored values.
 pub mod stored;
+/// Defines streaming reads and writes of large data.
+pub mod stream;
 /// Defines data storage systems.
 pub mod system;
 /// Defines the library's thread implementation.
 pub mod thread;
+/// Defines notifications of changes to stored data.
+pub mod watch;
 
 /// A result alias with a defaulted error type.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -58,40 +95,154 @@ pub enum Error {
     /// An error from spawning the storage thread.
     #[error(transparent)]
     ThreadSpawn(#[from] ina_threading::Error),
+    /// An error from the `SQLite` system.
+    #[cfg(feature = "system-sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] crate::system::sqlite::Error),
+    /// An error from exporting or importing an archive.
+    #[cfg(feature = "archive")]
+    #[error(transparent)]
+    Archive(#[from] crate::archive::Error),
+    /// An error from watching the storage directory for external edits.
+    #[cfg(feature = "watch-external")]
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
+    /// Stored data does not match its checksum.
+    #[error("checksum mismatch for data at '{0}'")]
+    ChecksumMismatch(Box<Path>),
+    /// Stored data has no checksum, although one is required.
+    #[error("missing checksum for data at '{0}'")]
+    MissingChecksum(Box<Path>),
+    /// A write would exceed the hard quota of a group of stored data.
+    #[error("storage quota exceeded for '{group}' ({bytes} of {limit} bytes)")]
+    QuotaExceeded {
+        /// The group whose quota would be exceeded.
+        group: Box<str>,
+        /// The number of bytes that the group would use.
+        bytes: u64,
+        /// The group's hard quota in bytes.
+        limit: u64,
+    },
+    /// Stored data has expired.
+    #[error("data at '{0}' has expired")]
+    Expired(Box<Path>),
+    /// Stored data uses a version that cannot be migrated into the current version.
+    #[error("cannot migrate data from version {0} into version {1}")]
+    UnsupportedVersion(u32, u32),
+    /// The storage thread did not respond before the request timed out.
+    #[error("the storage thread did not respond within {0:?}")]
+    TimedOut(Duration),
 }
 
 /// A storage instance.
+///
+/// Data is written through a shared reference, so writes of different paths may be made concurrently. Writes of the
+/// same path are not ordered by the instance, and must be ordered by the caller, as the storage thread does.
 #[derive(Debug)]
 pub struct Storage {
     /// The storage instance's settings.
     settings: Settings,
     /// The storage instance's internal cache.
     #[cfg(feature = "caching")]
-    cache: RwLock<HashMap<Box<Path>, Arc<[u8]>>>,
+    cache: Mutex<Cache>,
+    /// The number of bytes used by each group of the storage instance's data.
+    usage: Mutex<Usage>,
+    /// The blobs recently stored by the storage instance.
+    #[cfg(feature = "blob")]
+    blobs: crate::blob::RecentBlobs,
+    /// The subscribers to changes of the storage instance's data.
+    watchers: Arc<Watchers>,
+    /// The watcher of edits made to the storage directory outside of the storage instance.
+    #[cfg(feature = "watch-external")]
+    external: Option<notify::RecommendedWatcher>,
 }
 
 impl Storage {
     /// Creates a new [`Storage`].
     #[must_use]
     pub fn new(settings: Settings) -> Self {
-        if settings.system == System::Memory {
-            debug!(caching = cfg!(feature = "caching"), "created new memory-based storage instance");
-        } else {
-            debug!(
+        match settings.system {
+            #[cfg(feature = "system-file")]
+            System::File => debug!(
                 path = ?settings.directory,
                 caching = cfg!(feature = "caching"),
                 "created new file-based storage instance",
-            );
+            ),
+            #[cfg(feature = "system-memory")]
+            System::Memory => debug!(caching = cfg!(feature = "caching"), "created new memory-based storage instance"),
+            #[cfg(feature = "system-sqlite")]
+            System::Sqlite => debug!(
+                path = ?settings.directory,
+                caching = cfg!(feature = "caching"),
+                "created new sqlite-based storage instance",
+            ),
         }
 
-        #[cfg(feature = "caching")]
-        {
-            Self { settings, cache: RwLock::new(HashMap::new()) }
+        Self {
+            #[cfg(feature = "caching")]
+            cache: Mutex::new(Cache::new(&settings)),
+            usage: Mutex::new(Usage::new(&settings)),
+            #[cfg(feature = "blob")]
+            blobs: crate::blob::RecentBlobs::default(),
+            settings,
+            watchers: Arc::default(),
+            #[cfg(feature = "watch-external")]
+            external: None,
         }
-        #[cfg(not(feature = "caching"))]
-        {
-            Self { settings }
+    }
+
+    /// Returns a receiver of every change made to data stored under the given path prefix.
+    pub fn subscribe(&self, prefix: &Path) -> tokio::sync::broadcast::Receiver<Event> {
+        self.watchers.subscribe(prefix)
+    }
+
+    /// Starts reporting edits made to the storage directory outside of this storage instance to subscribers.
+    ///
+    /// This does nothing unless the file system is used.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory could not be watched.
+    #[cfg(feature = "watch-external")]
+    pub fn watch_external(&mut self) -> Result<()> {
+        if self.settings.system == System::File && self.external.is_none() {
+            self.external = Some(crate::watch::external::watch(&self.settings.directory, Arc::clone(&self.watchers))?);
+
+            debug!(path = ?self.settings.directory, "watching storage directory for external edits");
         }
+
+        Ok(())
+    }
+
+    /// Returns the statistics of the storage instance's cache.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    #[cfg(feature = "caching")]
+    pub fn blocking_cache_stats(&self) -> CacheStats {
+        self.cache.blocking_lock().stats()
+    }
+
+    /// Returns the statistics of the storage instance's cache.
+    #[cfg(feature = "caching")]
+    pub async fn cache_stats(&self) -> CacheStats {
+        self.cache.lock().await.stats()
+    }
+
+    /// Returns `true` if data without a checksum fails to be read.
+    const fn requires_checksums(&self) -> bool {
+        self.settings.checksums && !self.settings.checksums_legacy
+    }
+
+    /// Removes the configured storage directory from the start of each of the given paths.
+    fn strip_directory(&self, paths: Box<[Box<Path>]>) -> Box<[Box<Path>]> {
+        paths
+            .into_iter()
+            .map(|path| path.strip_prefix(&self.settings.directory).map(Into::into).unwrap_or(path))
+            .collect()
     }
 }
 
@@ -107,6 +258,10 @@ pub enum System {
     #[cfg(feature = "system-memory")]
     #[cfg_attr(not(feature = "system-file"), default)]
     Memory,
+    /// The `SQLite` system, which stores all data within a single database file.
+    #[cfg(feature = "system-sqlite")]
+    #[cfg_attr(all(not(feature = "system-file"), not(feature = "system-memory")), default)]
+    Sqlite,
 }
 
 impl Display for System {
@@ -125,19 +280,15 @@ macro_rules! system_call {
             System::File => system_call!($($header)* $crate::system::FileSystem => $($call)*),
             #[cfg(feature = "system-memory")]
             System::Memory => system_call!($($header)* $crate::system::MemorySystem => $($call)*),
+            #[cfg(feature = "system-sqlite")]
+            System::Sqlite => system_call!($($header)* $crate::system::SqliteSystem => $($call)*),
         }
     };
     (async ref $type:ty => $($call:tt)*) => {
-        <$type>::get().await$($call)*.await.map_err(Into::into)
-    };
-    (async mut $type:ty => $($call:tt)*) => {
-        <$type>::get_mut().await$($call)*.await.map_err(Into::into)
+        <$type>::get().await$($call)*.await.map_err(Into::<anyhow::Error>::into)
     };
     (ref $type:ty => $($call:tt)*) => {
-        <$type>::blocking_get()$($call)*.map_err(Into::into)
-    };
-    (mut $type:ty => $($call:tt)*) => {
-        <$type>::blocking_get_mut()$($call)*.map_err(Into::into)
+        <$type>::blocking_get()$($call)*.map_err(Into::<anyhow::Error>::into)
     };
 }
 
@@ -148,13 +299,6 @@ impl DataReader for Storage {
     fn blocking_exists(&self, path: &Path) -> Result<bool, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        if self.cache.blocking_read().contains_key(&(*combined_path)) {
-            debug!("data found in cache");
-
-            return Ok(true);
-        }
-
         system_call!(match self.settings.system, ref => .blocking_exists(&combined_path))
             .inspect(|_| debug!("checked whether data exists"))
     }
@@ -163,13 +307,6 @@ impl DataReader for Storage {
     async fn exists(&self, path: &Path) -> Result<bool, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        if self.cache.read().await.contains_key(&(*combined_path)) {
-            debug!("data found in cache");
-
-            return Ok(true);
-        }
-
         system_call!(match self.settings.system, async ref => .exists(&combined_path))
             .inspect(|_| debug!("checked whether data exists"))
     }
@@ -178,13 +315,6 @@ impl DataReader for Storage {
     fn blocking_size(&self, path: &Path) -> Result<u64, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        if let Some(bytes) = self.cache.blocking_read().get(&(*combined_path)) {
-            debug!("size found in cache");
-
-            return Ok(bytes.len() as u64);
-        }
-
         system_call!(match self.settings.system, ref => .blocking_size(&combined_path))
             .inspect(|_| debug!("fetched size of data"))
     }
@@ -193,82 +323,158 @@ impl DataReader for Storage {
     async fn size(&self, path: &Path) -> Result<u64, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        {
-            let cache = self.cache.read().await;
+        system_call!(match self.settings.system, async ref => .size(&combined_path))
+            .inspect(|_| debug!("fetched size of data"))
+    }
 
-            if let Some(bytes) = cache.get(&(*combined_path)) {
-                debug!("size found in cache");
+    #[tracing::instrument(level = "debug", name = "modified", skip(self))]
+    fn blocking_modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        let combined_path = self.settings.directory.join(path);
 
-                return Ok(bytes.len() as u64);
-            }
-        }
+        system_call!(match self.settings.system, ref => .blocking_modified(&combined_path))
+            .inspect(|_| debug!("fetched modification time of data"))
+    }
 
-        system_call!(match self.settings.system, async ref => .size(&combined_path))
-            .inspect(|_| debug!("fetched size of data"))
+    #[tracing::instrument(level = "debug", name = "modified", skip(self))]
+    async fn modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        let combined_path = self.settings.directory.join(path);
+
+        system_call!(match self.settings.system, async ref => .modified(&combined_path))
+            .inspect(|_| debug!("fetched modification time of data"))
     }
 
     #[tracing::instrument(level = "debug", name = "read", skip(self))]
     fn blocking_read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
+        // If the data was modified externally, the cached entry will be invalidated.
         #[cfg(feature = "caching")]
-        {
-            let cache = self.cache.blocking_read();
+        let modified = self.blocking_modified(path).ok().flatten();
 
-            if let Some(bytes) = cache.get(&(*combined_path)).cloned() {
-                debug!("data found in cache");
+        #[cfg(feature = "caching")]
+        let cached = self.cache.blocking_lock().get(&combined_path, modified);
 
-                return Ok(bytes);
-            }
+        #[cfg(feature = "caching")]
+        if let Some(bytes) = cached {
+            debug!("data found in cache");
 
-            drop(cache);
+            return Ok(bytes);
+        }
 
-            system_call!(match self.settings.system, ref => .blocking_read(&combined_path)).inspect(|bytes| {
-                debug!("read data");
+        let bytes = system_call!(match self.settings.system, ref => .blocking_read(&combined_path))?;
+        let bytes = crate::checksum::verify(path, bytes, self.requires_checksums())?;
 
-                self.cache.blocking_write().insert(combined_path.into_boxed_path(), Arc::clone(bytes));
+        debug!("read data");
 
-                debug!("wrote data to cache");
-            })
-        }
-        #[cfg(not(feature = "caching"))]
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, ref => .blocking_read(&path)).inspect(|_| debug!("read data"))
+            self.cache.blocking_lock().insert(combined_path.into_boxed_path(), Arc::clone(&bytes), modified);
+
+            debug!("wrote data to cache");
         }
+
+        Ok(bytes)
     }
 
     #[tracing::instrument(level = "debug", name = "read", skip(self))]
     async fn read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
+        // If the data was modified externally, the cached entry will be invalidated.
+        #[cfg(feature = "caching")]
+        let modified = self.modified(path).await.ok().flatten();
+
+        #[cfg(feature = "caching")]
+        let cached = self.cache.lock().await.get(&combined_path, modified);
+
+        #[cfg(feature = "caching")]
+        if let Some(bytes) = cached {
+            debug!("data found in cache");
+
+            return Ok(bytes);
+        }
+
+        let bytes = system_call!(match self.settings.system, async ref => .read(&combined_path))?;
+        let bytes = crate::checksum::verify(path, bytes, self.requires_checksums())?;
+
+        debug!("read data");
+
         #[cfg(feature = "caching")]
         {
-            let cache = self.cache.read().await;
+            self.cache.lock().await.insert(combined_path.into_boxed_path(), Arc::clone(&bytes), modified);
 
-            if let Some(bytes) = cache.get(&(*combined_path)).cloned() {
-                debug!("data found in cache");
+            debug!("wrote data to cache");
+        }
 
-                return Ok(bytes);
-            }
+        Ok(bytes)
+    }
 
-            drop(cache);
+    #[tracing::instrument(level = "debug", name = "read_stream", skip(self))]
+    fn blocking_read_stream(&self, path: &Path) -> Result<BlockingDataStream, Self::Error> {
+        let combined_path = self.settings.directory.join(path);
 
-            let result = system_call!(match self.settings.system, async ref => .read(&combined_path))
-                .inspect(|_| debug!("read data"));
+        #[cfg(feature = "caching")]
+        let modified = self.blocking_modified(path).ok().flatten();
 
-            if let Ok(bytes) = result.as_ref().cloned() {
-                self.cache.write().await.insert(combined_path.into_boxed_path(), bytes);
+        #[cfg(feature = "caching")]
+        let cached = self.cache.blocking_lock().get(&combined_path, modified);
 
-                debug!("wrote data to cache");
-            }
+        #[cfg(feature = "caching")]
+        if let Some(bytes) = cached {
+            debug!("data found in cache");
 
-            result
+            return Ok(BlockingDataStream::from_bytes(bytes));
         }
-        #[cfg(not(feature = "caching"))]
-        {
-            system_call!(match self.settings.system, async ref => .read(&path)).inspect(|_| debug!("read data"))
+
+        let stream = system_call!(match self.settings.system, ref => .blocking_read_stream(&combined_path))?;
+
+        debug!("opened data stream");
+
+        // Streamed data is never held in memory in full, so it is not inserted into the cache.
+        Ok(BlockingDataStream::new(crate::checksum::Verifying::new(path, stream, self.requires_checksums())))
+    }
+
+    #[tracing::instrument(level = "debug", name = "read_stream", skip(self))]
+    async fn read_stream(&self, path: &Path) -> Result<DataStream, Self::Error> {
+        let combined_path = self.settings.directory.join(path);
+
+        #[cfg(feature = "caching")]
+        let modified = self.modified(path).await.ok().flatten();
+
+        #[cfg(feature = "caching")]
+        let cached = self.cache.lock().await.get(&combined_path, modified);
+
+        #[cfg(feature = "caching")]
+        if let Some(bytes) = cached {
+            debug!("data found in cache");
+
+            return Ok(DataStream::from_bytes(bytes));
         }
+
+        let stream = system_call!(match self.settings.system, async ref => .read_stream(&combined_path))?;
+
+        debug!("opened data stream");
+
+        // Streamed data is never held in memory in full, so it is not inserted into the cache.
+        Ok(DataStream::new(crate::checksum::Verifying::new(path, stream, self.requires_checksums())))
+    }
+
+    #[tracing::instrument(level = "debug", name = "list", skip(self))]
+    fn blocking_list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        let combined_prefix = self.settings.directory.join(prefix);
+
+        system_call!(match self.settings.system, ref => .blocking_list(&combined_prefix))
+            .map(|paths| self.strip_directory(paths))
+            .inspect(|paths| debug!(count = paths.len(), "listed data"))
+    }
+
+    #[tracing::instrument(level = "debug", name = "list", skip(self))]
+    async fn list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        let combined_prefix = self.settings.directory.join(prefix);
+
+        system_call!(match self.settings.system, async ref => .list(&combined_prefix))
+            .map(|paths| self.strip_directory(paths))
+            .inspect(|paths| debug!(count = paths.len(), "listed data"))
     }
 }
 
@@ -276,153 +482,405 @@ impl DataWriter for Storage {
     type Error = anyhow::Error;
 
     #[tracing::instrument(level = "debug", name = "write", skip(self, bytes))]
-    fn blocking_write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+    fn blocking_write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.blocking_write_with(path, bytes, true)
+    }
+
+    #[tracing::instrument(level = "debug", name = "write", skip(self, bytes))]
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.write_with(path, bytes, true).await
+    }
+
+    #[tracing::instrument(level = "debug", name = "write_stream", skip(self, stream))]
+    fn blocking_write_stream(&self, path: &Path, stream: BlockingDataStream) -> Result<u64, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        {
-            system_call!(match self.settings.system, mut => .blocking_write(&combined_path, bytes)).inspect(|&()| {
-                debug!("wrote data");
+        self.blocking_load_usage()?;
 
-                self.cache.blocking_write().insert(combined_path.into_boxed_path(), Arc::from(bytes));
+        let previous = self.blocking_size(path).unwrap_or(0);
+        let stream = if self.settings.checksums {
+            BlockingDataStream::new(crate::checksum::Appending::new(stream))
+        } else {
+            stream
+        };
+        let (stream, reservation) = self.usage.blocking_lock().limit(path, previous, stream);
+        let stream = BlockingDataStream::new(stream);
 
-                debug!("wrote data to cache");
-            })
-        }
-        #[cfg(not(feature = "caching"))]
+        let event = Event::Written(path.into());
+
+        self.watchers.expect(&event);
+
+        let result = system_call!(match self.settings.system, ref => .blocking_write_stream(&combined_path, stream));
+        let mut usage = self.usage.blocking_lock();
+
+        reservation.release(&mut usage);
+
+        let size = result?;
+
+        // The stream was limited to the reserved bytes, so its size is always within the group's hard quota.
+        usage.reserve(|usage| usage.plan_write(path, previous, size))?.commit();
+
+        drop(usage);
+
+        debug!(size, "wrote data from stream");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, mut => .blocking_write(&path, bytes))
-                .inspect(|()| debug!("wrote data"))
+            self.cache.blocking_lock().remove(&combined_path);
+
+            debug!("removed data from cache");
         }
+
+        Ok(size)
     }
 
-    #[tracing::instrument(level = "debug", name = "write", skip(self, bytes))]
-    async fn write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+    #[tracing::instrument(level = "debug", name = "write_stream", skip(self, stream))]
+    async fn write_stream(&self, path: &Path, stream: DataStream) -> Result<u64, Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
-        #[cfg(feature = "caching")]
-        {
-            let result = system_call!(match self.settings.system, async mut => .write(&combined_path, bytes));
+        self.load_usage().await?;
 
-            if result.is_ok() {
-                debug!("wrote data");
+        let previous = self.size(path).await.unwrap_or(0);
+        let stream =
+            if self.settings.checksums { DataStream::new(crate::checksum::Appending::new(stream)) } else { stream };
+        let (stream, reservation) = self.usage.lock().await.limit(path, previous, stream);
+        let stream = DataStream::new(stream);
 
-                self.cache.write().await.insert(combined_path.into_boxed_path(), Arc::from(bytes));
+        let event = Event::Written(path.into());
 
-                debug!("wrote data to cache");
-            }
+        self.watchers.expect(&event);
 
-            result
-        }
-        #[cfg(not(feature = "caching"))]
+        let result = system_call!(match self.settings.system, async ref => .write_stream(&combined_path, stream));
+        let mut usage = self.usage.lock().await;
+
+        reservation.release(&mut usage);
+
+        let size = result?;
+
+        // The stream was limited to the reserved bytes, so its size is always within the group's hard quota.
+        usage.reserve(|usage| usage.plan_write(path, previous, size))?.commit();
+
+        drop(usage);
+
+        debug!(size, "wrote data from stream");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, async mut => .write(&path, bytes))
-                .inspect(|()| debug!("wrote data"))
+            self.cache.lock().await.remove(&combined_path);
+
+            debug!("removed data from cache");
         }
+
+        Ok(size)
     }
 
     #[tracing::instrument(level = "debug", name = "rename", skip(self))]
-    fn blocking_rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+    fn blocking_rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
         let combined_from = self.settings.directory.join(from);
         let combined_into = self.settings.directory.join(into);
 
-        #[cfg(feature = "caching")]
-        {
-            system_call!(match self.settings.system, mut => .blocking_rename(&combined_from, &combined_into)).inspect(
-                |&()| {
-                    debug!("renamed data");
+        self.blocking_load_usage()?;
 
-                    let mut cache = self.cache.blocking_write();
-                    let Some(value) = cache.remove(&(*combined_from)) else { return };
+        let size = self.blocking_size(from).unwrap_or(0);
+        let previous = self.blocking_size(into).unwrap_or(0);
+        let reservation = self.usage.blocking_lock().reserve(|usage| usage.plan_rename(from, into, size, previous))?;
 
-                    cache.insert(combined_into.into_boxed_path(), value);
+        let event = Event::Renamed(from.into(), into.into());
 
-                    drop(cache);
+        self.watchers.expect(&event);
 
-                    debug!("renamed data in cache");
-                },
-            )
+        if let Err(error) =
+            system_call!(match self.settings.system, ref => .blocking_rename(&combined_from, &combined_into))
+        {
+            reservation.release(&mut self.usage.blocking_lock());
+
+            return Err(error);
         }
-        #[cfg(not(feature = "caching"))]
+
+        reservation.commit();
+
+        debug!("renamed data");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, mut => .blocking_rename(&from, &into))
-                .inspect(|()| debug!("renamed data"))
+            self.cache.blocking_lock().rename(&combined_from, combined_into.into_boxed_path());
+
+            debug!("renamed data in cache");
         }
+
+        Ok(())
     }
 
     #[tracing::instrument(level = "debug", name = "rename", skip(self))]
-    async fn rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+    async fn rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
         let combined_from = self.settings.directory.join(from);
         let combined_into = self.settings.directory.join(into);
 
-        #[cfg(feature = "caching")]
-        {
-            let result = system_call!(match self.settings.system, async mut => .rename(&combined_from, &combined_into));
+        self.load_usage().await?;
 
-            if result.is_ok() {
-                debug!("renamed data");
+        let size = self.size(from).await.unwrap_or(0);
+        let previous = self.size(into).await.unwrap_or(0);
+        let reservation = self.usage.lock().await.reserve(|usage| usage.plan_rename(from, into, size, previous))?;
 
-                let mut cache = self.cache.write().await;
-                let Some(value) = cache.remove(&(*combined_from)) else { return result };
+        let event = Event::Renamed(from.into(), into.into());
 
-                cache.insert(combined_into.into_boxed_path(), value);
+        self.watchers.expect(&event);
 
-                drop(cache);
-
-                debug!("renamed data in cache");
-            }
+        if let Err(error) =
+            system_call!(match self.settings.system, async ref => .rename(&combined_from, &combined_into))
+        {
+            reservation.release(&mut *self.usage.lock().await);
 
-            result
+            return Err(error);
         }
-        #[cfg(not(feature = "caching"))]
+
+        reservation.commit();
+
+        debug!("renamed data");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, async mut => .rename(&from, &into))
-                .inspect(|()| debug!("renamed data"))
+            self.cache.lock().await.rename(&combined_from, combined_into.into_boxed_path());
+
+            debug!("renamed data in cache");
         }
+
+        Ok(())
     }
 
     #[tracing::instrument(level = "debug", name = "delete", skip(self))]
-    fn blocking_delete(&mut self, path: &Path) -> Result<(), Self::Error> {
+    fn blocking_delete(&self, path: &Path) -> Result<(), Self::Error> {
         let combined_path = self.settings.directory.join(path);
 
+        self.blocking_load_usage()?;
+
+        let size = self.blocking_size(path).unwrap_or(0);
+
+        let event = Event::Deleted(path.into());
+
+        self.watchers.expect(&event);
+
+        system_call!(match self.settings.system, ref => .blocking_delete(&combined_path))?;
+
+        let mut usage = self.usage.blocking_lock();
+        let changes = usage.plan_delete(path, size);
+
+        usage.apply(&changes);
+
+        drop(usage);
+
+        debug!("removed data");
+
+        self.watchers.notify(&event);
+
         #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, mut => .blocking_delete(&combined_path)).inspect(|&()| {
-                debug!("removed data");
-
-                self.cache.blocking_write().remove(&(*combined_path));
+            self.cache.blocking_lock().remove(&combined_path);
 
-                debug!("removed data from cache");
-            })
+            debug!("removed data from cache");
         }
-        #[cfg(not(feature = "caching"))]
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", name = "delete", skip(self))]
+    async fn delete(&self, path: &Path) -> Result<(), Self::Error> {
+        let combined_path = self.settings.directory.join(path);
+
+        self.load_usage().await?;
+
+        let size = self.size(path).await.unwrap_or(0);
+
+        let event = Event::Deleted(path.into());
+
+        self.watchers.expect(&event);
+
+        system_call!(match self.settings.system, async ref => .delete(&combined_path))?;
+
+        let mut usage = self.usage.lock().await;
+        let changes = usage.plan_delete(path, size);
+
+        usage.apply(&changes);
+
+        drop(usage);
+
+        debug!("removed data");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, mut => .blocking_delete(&path))
-                .inspect(|()| debug!("removed data"))
+            self.cache.lock().await.remove(&combined_path);
+
+            debug!("removed data from cache");
         }
+
+        Ok(())
     }
+}
 
-    #[tracing::instrument(level = "debug", name = "delete", skip(self))]
-    async fn delete(&mut self, path: &Path) -> Result<(), Self::Error> {
+impl Storage {
+    /// Writes the given bytes into the given path, only checking the hard quota of its group if `enforce_quota` is set.
+    ///
+    /// Writes that restore data that was previously stored, such as those reverting a failed batch, should not enforce
+    /// the quota, as the restored data already counted against it.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be written, or would exceed the group's enforced hard
+    /// quota.
+    pub(crate) fn blocking_write_with(&self, path: &Path, bytes: &[u8], enforce_quota: bool) -> anyhow::Result<()> {
         let combined_path = self.settings.directory.join(path);
 
+        let stored =
+            if self.settings.checksums { Cow::Owned(crate::checksum::append(bytes)) } else { Cow::Borrowed(bytes) };
+
+        self.blocking_load_usage()?;
+
+        let previous = self.blocking_size(path).unwrap_or(0);
+        let reservation = self.usage.blocking_lock().reserve(|usage| {
+            if enforce_quota {
+                usage.plan_write(path, previous, stored.len() as u64)
+            } else {
+                Ok(usage.plan_unchecked_write(path, previous, stored.len() as u64))
+            }
+        })?;
+
+        let event = Event::Written(path.into());
+
+        self.watchers.expect(&event);
+
+        if let Err(error) = system_call!(match self.settings.system, ref => .blocking_write(&combined_path, &stored)) {
+            reservation.release(&mut self.usage.blocking_lock());
+
+            return Err(error);
+        }
+
+        reservation.commit();
+
+        debug!("wrote data");
+
+        self.watchers.notify(&event);
+
         #[cfg(feature = "caching")]
         {
-            let result = system_call!(match self.settings.system, async mut => .delete(&combined_path));
+            let modified = self.blocking_modified(path).ok().flatten();
+
+            self.cache.blocking_lock().insert(combined_path.into_boxed_path(), Arc::from(bytes), modified);
+
+            debug!("wrote data to cache");
+        }
+
+        Ok(())
+    }
 
-            if result.is_ok() {
-                debug!("removed data");
+    /// Writes the given bytes into the given path, only checking the hard quota of its group if `enforce_quota` is set.
+    ///
+    /// Writes that restore data that was previously stored, such as those reverting a failed batch, should not enforce
+    /// the quota, as the restored data already counted against it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be written, or would exceed the group's enforced hard
+    /// quota.
+    pub(crate) async fn write_with(&self, path: &Path, bytes: &[u8], enforce_quota: bool) -> anyhow::Result<()> {
+        let combined_path = self.settings.directory.join(path);
 
-                self.cache.write().await.remove(&(*combined_path));
+        let stored =
+            if self.settings.checksums { Cow::Owned(crate::checksum::append(bytes)) } else { Cow::Borrowed(bytes) };
 
-                debug!("removed data from cache");
+        self.load_usage().await?;
+
+        let previous = self.size(path).await.unwrap_or(0);
+        let reservation = self.usage.lock().await.reserve(|usage| {
+            if enforce_quota {
+                usage.plan_write(path, previous, stored.len() as u64)
+            } else {
+                Ok(usage.plan_unchecked_write(path, previous, stored.len() as u64))
             }
+        })?;
+
+        let event = Event::Written(path.into());
+
+        self.watchers.expect(&event);
+
+        if let Err(error) = system_call!(match self.settings.system, async ref => .write(&combined_path, &stored)) {
+            reservation.release(&mut *self.usage.lock().await);
 
-            result
+            return Err(error);
         }
-        #[cfg(not(feature = "caching"))]
+
+        reservation.commit();
+
+        debug!("wrote data");
+
+        self.watchers.notify(&event);
+
+        #[cfg(feature = "caching")]
         {
-            system_call!(match self.settings.system, async mut => .delete(&path)).inspect(|()| debug!("removed data"))
+            let modified = self.modified(path).await.ok().flatten();
+
+            self.cache.lock().await.insert(combined_path.into_boxed_path(), Arc::from(bytes), modified);
+
+            debug!("wrote data to cache");
         }
+
+        Ok(())
+    }
+
+    /// Returns the bytes at the start of the data at the given path, up to the given length.
+    ///
+    /// The bytes are read from the storage system directly, so they are neither taken from nor inserted into the cache,
+    /// and are not verified against the data's checksum.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be read.
+    pub(crate) fn blocking_read_prefix(&self, path: &Path, length: usize) -> anyhow::Result<Vec<u8>> {
+        let combined_path = self.settings.directory.join(path);
+        let stream = system_call!(match self.settings.system, ref => .blocking_read_stream(&combined_path))?;
+        let mut bytes = Vec::with_capacity(length);
+
+        stream.take(length as u64).read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Returns the bytes at the start of the data at the given path, up to the given length.
+    ///
+    /// The bytes are read from the storage system directly, so they are neither taken from nor inserted into the cache,
+    /// and are not verified against the data's checksum.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data could not be read.
+    pub(crate) async fn read_prefix(&self, path: &Path, length: usize) -> anyhow::Result<Vec<u8>> {
+        let combined_path = self.settings.directory.join(path);
+        let stream = system_call!(match self.settings.system, async ref => .read_stream(&combined_path))?;
+        let mut bytes = Vec::with_capacity(length);
+
+        stream.take(length as u64).read_to_end(&mut bytes).await?;
+
+        Ok(bytes)
     }
 }
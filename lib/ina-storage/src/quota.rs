@@ -0,0 +1,494 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::num::NonZero;
+use std::path::{Component, Path};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::{AsyncRead, ReadBuf};
+use tracing::{debug, warn};
+
+use crate::Storage;
+use crate::settings::Settings;
+use crate::system::DataReader;
+
+/// The directory that contains the usage record path of each group.
+const USAGE_DIRECTORY: &str = ".usage";
+
+/// The storage usage of a single group of data.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct GroupUsage {
+    /// The number of bytes used by the group.
+    pub bytes: u64,
+    /// The number of bytes after which writes log a warning, if any.
+    pub soft_quota: Option<u64>,
+    /// The number of bytes that writes may not exceed, if any.
+    pub hard_quota: Option<u64>,
+}
+
+/// A change to the number of bytes used by a group of stored data.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct UsageChange {
+    /// The affected group.
+    group: Box<str>,
+    /// The number of bytes added to the group.
+    added: u64,
+    /// The number of bytes removed from the group.
+    removed: u64,
+}
+
+/// Usage changes that were applied before the change of the data that they describe, so that concurrent changes of a
+/// group cannot exceed its hard quota together.
+///
+/// A reservation must be committed once the change of the data succeeds, or released if it fails.
+#[must_use = "reservations must be committed or released"]
+#[derive(Debug)]
+pub(crate) struct Reservation {
+    /// The reserved changes.
+    changes: Vec<UsageChange>,
+}
+
+impl Reservation {
+    /// Keeps the reserved changes, as the change of the data that they describe succeeded.
+    pub fn commit(self) {
+        drop(self.changes);
+    }
+
+    /// Reverts the reserved changes within the given usage, as the change of the data that they describe failed.
+    pub fn release(self, usage: &mut Usage) {
+        usage.revert(&self.changes);
+    }
+}
+
+/// A reader that fails once its inner reader yields more bytes than the hard quota of a group allows.
+#[derive(Debug)]
+pub(crate) struct Limited<R> {
+    /// The inner reader.
+    inner: R,
+    /// The group that the bytes are written into, or [`None`] if the bytes are not limited.
+    group: Option<Box<str>>,
+    /// The number of bytes used by the group, excluding the data that is being replaced.
+    base: u64,
+    /// The group's hard quota in bytes.
+    limit: u64,
+    /// The number of bytes that may be read before the hard quota is exceeded.
+    allowed: u64,
+    /// The number of bytes that have been read.
+    read: u64,
+}
+
+impl<R> Limited<R> {
+    /// Counts the given number of bytes read from the inner reader.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the bytes exceed the group's hard quota.
+    fn count(&mut self, length: usize) -> std::io::Result<()> {
+        self.read = self.read.saturating_add(length as u64);
+
+        match &self.group {
+            Some(group) if self.read > self.allowed => Err(std::io::Error::other(crate::Error::QuotaExceeded {
+                group: group.clone(),
+                bytes: self.base.saturating_add(self.read),
+                limit: self.limit,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = self.inner.read(buf)?;
+
+        self.count(length).map(|()| length)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Limited<R> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+
+        Poll::Ready(this.count(buf.filled().len() - start))
+    }
+}
+
+/// Tracks the number of bytes used by each group of stored data.
+///
+/// Data is grouped by the value of the path segment at the configured index, which is usually the guild identifier.
+#[derive(Debug)]
+pub struct Usage {
+    /// The index of the path segment that groups data.
+    segment: usize,
+    /// The number of bytes after which writes log a warning, if any.
+    soft_quota: Option<u64>,
+    /// The number of bytes that writes may not exceed, if any.
+    hard_quota: Option<u64>,
+    /// The number of bytes used by each group, or [`None`] if it has not yet been calculated.
+    totals: Option<HashMap<Box<str>, u64>>,
+}
+
+impl Usage {
+    /// Creates a new [`Usage`] using the given settings.
+    #[must_use]
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            segment: settings.quota_segment,
+            soft_quota: settings.soft_quota.map(NonZero::get),
+            hard_quota: settings.hard_quota.map(NonZero::get),
+            totals: None,
+        }
+    }
+
+    /// Returns the group of the data at the given path, or [`None`] if the data is not grouped.
+    #[must_use]
+    pub fn group_of(&self, path: &Path) -> Option<Box<str>> {
+        let components = path
+            .components()
+            .filter_map(|component| if let Component::Normal(name) = component { Some(name) } else { None })
+            .collect::<Vec<_>>();
+
+        // The final component names the data itself, so it never forms a group.
+        if components.len() <= self.segment + 1 {
+            return None;
+        }
+
+        components.get(self.segment).map(|name| name.to_string_lossy().into())
+    }
+
+    /// Returns the path that names the usage record of the given group, which orders access to the group's usage.
+    ///
+    /// No data is stored at this path.
+    #[must_use]
+    pub fn record_path(group: &str) -> Box<Path> {
+        Path::new(USAGE_DIRECTORY).join(group).into()
+    }
+
+    /// Returns the usage of the given group.
+    #[must_use]
+    pub fn get(&self, group: &str) -> GroupUsage {
+        let bytes = self.totals.as_ref().and_then(|totals| totals.get(group).copied()).unwrap_or(0);
+
+        GroupUsage { bytes, soft_quota: self.soft_quota, hard_quota: self.hard_quota }
+    }
+
+    /// Returns the usage changes caused by replacing data of the given previous size at the given path with data of the
+    /// given size.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the change would exceed the group's hard quota.
+    pub(crate) fn plan_write(&self, path: &Path, previous: u64, size: u64) -> crate::Result<Vec<UsageChange>> {
+        let changes = self.plan_unchecked_write(path, previous, size);
+
+        changes.iter().try_for_each(|change| self.check(change))?;
+
+        Ok(changes)
+    }
+
+    /// Returns the usage changes caused by replacing data of the given previous size at the given path with data of the
+    /// given size, without checking the group's quotas.
+    pub(crate) fn plan_unchecked_write(&self, path: &Path, previous: u64, size: u64) -> Vec<UsageChange> {
+        self.group_of(path).map(|group| UsageChange { group, added: size, removed: previous }).into_iter().collect()
+    }
+
+    /// Returns the usage changes caused by renaming data of the given size, replacing data of the given previous size.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the change would exceed the destination group's hard quota.
+    pub(crate) fn plan_rename(
+        &self,
+        from: &Path,
+        into: &Path,
+        size: u64,
+        previous: u64,
+    ) -> crate::Result<Vec<UsageChange>> {
+        let from = self.group_of(from).map(|group| UsageChange { group, added: 0, removed: size });
+        let into = self.group_of(into).map(|group| UsageChange { group, added: size, removed: previous });
+
+        // Data renamed within a single group does not grow it, regardless of its size.
+        if let Some(into) = &into
+            && from.as_ref().is_none_or(|from| from.group != into.group)
+        {
+            self.check(into)?;
+        }
+
+        Ok(from.into_iter().chain(into).collect())
+    }
+
+    /// Returns the usage changes caused by deleting data of the given size from the given path.
+    pub(crate) fn plan_delete(&self, path: &Path, size: u64) -> Vec<UsageChange> {
+        self.group_of(path).map(|group| UsageChange { group, added: 0, removed: size }).into_iter().collect()
+    }
+
+    /// Returns a reader that fails once the given reader yields more bytes than may replace data of the given previous
+    /// size at the given path without exceeding the group's hard quota, alongside a reservation of those bytes.
+    ///
+    /// The allowed bytes are reserved up front, as the size of the data is only known once it has been written. The
+    /// reservation should be released once the data is written, and replaced by the data's actual size.
+    pub(crate) fn limit<R>(&mut self, path: &Path, previous: u64, inner: R) -> (Limited<R>, Reservation) {
+        let group = self.hard_quota.and_then(|_| self.group_of(path));
+        let base = group.as_deref().map_or(0, |group| self.get(group).bytes.saturating_sub(previous));
+        let limit = self.hard_quota.unwrap_or(u64::MAX);
+        // Data that does not grow its group is always allowed, matching the checks of regular writes.
+        let allowed = limit.saturating_sub(base).max(previous);
+        let reserved = if group.is_some() { allowed } else { previous };
+
+        let changes = self.plan_unchecked_write(path, previous, reserved);
+
+        self.apply(&changes);
+
+        (Limited { inner, group, base, limit, allowed, read: 0 }, Reservation { changes })
+    }
+
+    /// Applies the given usage changes.
+    pub(crate) fn apply(&mut self, changes: &[UsageChange]) {
+        let Some(totals) = self.totals.as_mut() else { return };
+
+        for UsageChange { group, added, removed } in changes {
+            let bytes = totals.entry(group.clone()).or_default();
+
+            *bytes = bytes.saturating_sub(*removed).saturating_add(*added);
+        }
+
+        totals.retain(|_, bytes| *bytes > 0);
+    }
+
+    /// Reserves the usage changes returned by the given function.
+    ///
+    /// Changes are planned and applied at once, so that concurrent writes of a group cannot exceed its hard quota
+    /// together.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the changes could not be planned.
+    pub(crate) fn reserve<F>(&mut self, plan: F) -> crate::Result<Reservation>
+    where
+        F: FnOnce(&Self) -> crate::Result<Vec<UsageChange>>,
+    {
+        let changes = plan(self)?;
+
+        self.apply(&changes);
+
+        Ok(Reservation { changes })
+    }
+
+    /// Reverts the given usage changes, which were applied before the change of the data that they describe failed.
+    fn revert(&mut self, changes: &[UsageChange]) {
+        let changes = changes
+            .iter()
+            .map(|UsageChange { group, added, removed }| UsageChange {
+                group: group.clone(),
+                added: *removed,
+                removed: *added,
+            })
+            .collect::<Vec<_>>();
+
+        self.apply(&changes);
+    }
+
+    /// Checks whether the given change is allowed, logging a warning if it exceeds the group's soft quota.
+    ///
+    /// Changes that do not grow a group are always allowed, so that groups over their quota can still be cleaned up.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the change would exceed the group's hard quota.
+    fn check(&self, UsageChange { group, added, removed }: &UsageChange) -> crate::Result<()> {
+        if added <= removed {
+            return Ok(());
+        }
+
+        let bytes = self.get(group).bytes.saturating_sub(*removed).saturating_add(*added);
+
+        if let Some(limit) = self.hard_quota
+            && bytes > limit
+        {
+            return Err(crate::Error::QuotaExceeded { group: group.clone(), bytes, limit });
+        }
+        if let Some(limit) = self.soft_quota
+            && bytes > limit
+        {
+            warn!(group, bytes, limit, "storage group exceeds soft quota");
+        }
+
+        Ok(())
+    }
+
+    /// Sets the number of bytes used by each group from the given paths and their sizes.
+    fn load(&mut self, sizes: impl IntoIterator<Item = (Box<Path>, u64)>) {
+        let mut totals = HashMap::<Box<str>, u64>::new();
+
+        for (path, size) in sizes {
+            if let Some(group) = self.group_of(&path) {
+                *totals.entry(group).or_default() += size;
+            }
+        }
+
+        debug!(groups = totals.len(), "calculated storage usage");
+
+        self.totals = Some(totals);
+    }
+}
+
+impl Storage {
+    /// Calculates the number of bytes used by each group of stored data, if it has not been calculated yet.
+    ///
+    /// The usage is held while it is calculated, so writes made meanwhile are only counted once it has been calculated.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stored data could not be listed or measured.
+    pub fn blocking_load_usage(&self) -> anyhow::Result<()> {
+        let mut usage = self.usage.blocking_lock();
+
+        if usage.totals.is_some() {
+            return Ok(());
+        }
+
+        let paths = self.blocking_list(Path::new(""))?;
+        let mut sizes = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let size = self.blocking_size(&path)?;
+
+            sizes.push((path, size));
+        }
+
+        usage.load(sizes);
+
+        drop(usage);
+
+        Ok(())
+    }
+
+    /// Calculates the number of bytes used by each group of stored data, if it has not been calculated yet.
+    ///
+    /// The usage is held while it is calculated, so writes made meanwhile are only counted once it has been calculated.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stored data could not be listed or measured.
+    pub async fn load_usage(&self) -> anyhow::Result<()> {
+        let mut usage = self.usage.lock().await;
+
+        if usage.totals.is_some() {
+            return Ok(());
+        }
+
+        let paths = self.list(Path::new("")).await?;
+        let mut sizes = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let size = self.size(&path).await?;
+
+            sizes.push((path, size));
+        }
+
+        usage.load(sizes);
+
+        drop(usage);
+
+        Ok(())
+    }
+
+    /// Returns the usage of the given group of stored data.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the usage could not be calculated.
+    pub fn blocking_usage(&self, group: &str) -> anyhow::Result<GroupUsage> {
+        self.blocking_load_usage()?;
+
+        Ok(self.usage.blocking_lock().get(group))
+    }
+
+    /// Returns the usage of the given group of stored data.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the usage could not be calculated.
+    pub async fn usage(&self, group: &str) -> anyhow::Result<GroupUsage> {
+        self.load_usage().await?;
+
+        Ok(self.usage.lock().await.get(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+    use std::path::Path;
+
+    use super::Usage;
+
+    #[test]
+    fn enforce_hard_quota() -> anyhow::Result<()> {
+        let mut settings = crate::settings::test_settings("quota");
+
+        settings.hard_quota = NonZero::new(10);
+
+        let mut usage = Usage::new(&settings);
+
+        assert_eq!(usage.group_of(Path::new("role/1/2.pack")), Some("1".into()));
+        assert_eq!(usage.group_of(Path::new("role/2.pack")), None);
+
+        usage.load([(Path::new("role/1/2.pack").into(), 6), (Path::new("poll/1/2.pack").into(), 2)]);
+
+        assert_eq!(usage.get("1").bytes, 8);
+        assert!(usage.plan_write(Path::new("role/1/3.pack"), 0, 3).is_err());
+        assert!(usage.plan_write(Path::new("role/2/3.pack"), 0, 3).is_ok());
+        assert!(usage.plan_rename(Path::new("role/1/2.pack"), Path::new("role/1/3.pack"), 6, 0).is_ok());
+        assert!(usage.plan_rename(Path::new("role/3/4.pack"), Path::new("role/1/4.pack"), 3, 0).is_err());
+
+        usage.apply(&usage.plan_write(Path::new("role/1/2.pack"), 6, 4)?);
+        usage.apply(&usage.plan_delete(Path::new("poll/1/2.pack"), 2));
+
+        assert_eq!(usage.get("1").bytes, 4);
+
+        let (_, reservation) = usage.limit(Path::new("role/1/5.pack"), 0, std::io::empty());
+
+        // The bytes that a stream may write are reserved, so concurrent writes of the group cannot claim them.
+        assert_eq!(usage.get("1").bytes, 10);
+        assert!(usage.plan_write(Path::new("role/1/6.pack"), 0, 1).is_err());
+
+        reservation.release(&mut usage);
+
+        assert_eq!(usage.get("1").bytes, 4);
+
+        Ok(())
+    }
+}
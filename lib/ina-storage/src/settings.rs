@@ -45,6 +45,48 @@ pub struct Settings {
     #[arg(id = "DATA_DIRECTORY", long = "data-directory")]
     #[option(default = self::default_directory())]
     pub directory: PathBuf,
+    /// The directory within which to keep snapshots of all stored data.
+    ///
+    /// This is ignored if snapshots are disabled.
+    ///
+    /// Default: `./res/snapshots`
+    #[arg(id = "DATA_SNAPSHOT_DIRECTORY", long = "data-snapshot-directory")]
+    #[option(default = self::default_snapshot_directory())]
+    pub snapshot_directory: PathBuf,
+    /// The number of seconds between scheduled snapshots. If unset, snapshots will only be taken when requested.
+    ///
+    /// This is ignored if snapshots are disabled.
+    ///
+    /// Default: unset
+    #[arg(id = "DATA_SNAPSHOT_INTERVAL", long = "data-snapshot-interval")]
+    #[option(default)]
+    pub snapshot_interval: Option<NonZero<u64>>,
+    /// The number of snapshots to keep, after which the oldest snapshots are deleted.
+    ///
+    /// This is ignored if snapshots are disabled.
+    ///
+    /// Default: `7`
+    #[arg(id = "DATA_SNAPSHOT_ROTATIONS", long = "data-snapshot-rotations")]
+    #[option(default = self::default_snapshot_rotations())]
+    pub snapshot_rotations: NonZero<usize>,
+
+    /// Whether to append a checksum to written data, which is verified when the data is read.
+    ///
+    /// Data written with a checksum is always verified, even if this is disabled. While this is enabled, data without
+    /// a checksum fails to be read, unless legacy data is accepted.
+    ///
+    /// Default: `false`
+    #[arg(id = "DATA_CHECKSUMS", long = "data-checksums")]
+    #[option(default)]
+    pub checksums: bool,
+
+    /// Whether to accept legacy data without a checksum while checksums are enabled, such as data written before they
+    /// were enabled.
+    ///
+    /// Default: `false`
+    #[arg(id = "DATA_CHECKSUMS_LEGACY", long = "data-checksums-legacy")]
+    #[option(default)]
+    pub checksums_legacy: bool,
 
     /// The storage thread's output queue capacity. If set to `1`, no buffering will be done.
     ///
@@ -52,6 +94,66 @@ pub struct Settings {
     #[arg(id = "DATA_QUEUE_CAPACITY", long = "data-queue-capacity")]
     #[option(default = self::default_queue_capacity())]
     pub queue_capacity: NonZero<usize>,
+    /// The number of seconds to wait for the storage thread to respond before a request fails.
+    ///
+    /// Default: `60`
+    #[arg(id = "DATA_CALL_TIMEOUT", long = "data-call-timeout")]
+    #[option(default = self::default_call_timeout())]
+    pub call_timeout: NonZero<u64>,
+
+    /// The maximum number of entries held within the storage cache. If set to `0`, no data will be cached.
+    ///
+    /// This is ignored if caching is disabled.
+    ///
+    /// Default: `1024`
+    #[arg(id = "DATA_CACHE_ENTRIES", long = "data-cache-entries")]
+    #[option(default = self::default_cache_entries())]
+    pub cache_entries: usize,
+    /// The maximum number of bytes held within the storage cache.
+    ///
+    /// This is ignored if caching is disabled.
+    ///
+    /// Default: `67108864` (64 MiB)
+    #[arg(id = "DATA_CACHE_BYTES", long = "data-cache-bytes")]
+    #[option(default = self::default_cache_bytes())]
+    pub cache_bytes: u64,
+    /// The number of seconds after which cached data expires. If unset, cached data will not expire.
+    ///
+    /// This is ignored if caching is disabled.
+    ///
+    /// Default: unset
+    #[arg(id = "DATA_CACHE_TTL", long = "data-cache-ttl")]
+    #[option(default)]
+    pub cache_ttl: Option<NonZero<u64>>,
+
+    /// The number of seconds between sweeps that delete expired data.
+    ///
+    /// Default: `3600` (1 hour)
+    #[arg(id = "DATA_SWEEP_INTERVAL", long = "data-sweep-interval")]
+    #[option(default = self::default_sweep_interval())]
+    pub sweep_interval: NonZero<u64>,
+
+    /// The index of the path segment that groups stored data for usage tracking and quotas.
+    ///
+    /// Data is stored at paths like `role/{guild}/{user}`, so the default groups data by guild.
+    ///
+    /// Default: `1`
+    #[arg(id = "DATA_QUOTA_SEGMENT", long = "data-quota-segment")]
+    #[option(default = self::default_quota_segment())]
+    pub quota_segment: usize,
+    /// The number of bytes that a group of stored data may use before writes log a warning. If unset, no warnings will
+    /// be logged.
+    ///
+    /// Default: unset
+    #[arg(id = "DATA_SOFT_QUOTA", long = "data-soft-quota")]
+    #[option(default)]
+    pub soft_quota: Option<NonZero<u64>>,
+    /// The number of bytes that a group of stored data may not exceed. If unset, writes will not be limited.
+    ///
+    /// Default: unset
+    #[arg(id = "DATA_HARD_QUOTA", long = "data-hard-quota")]
+    #[option(default)]
+    pub hard_quota: Option<NonZero<u64>>,
 }
 
 /// Returns the default queue capacity.
@@ -61,7 +163,62 @@ fn default_queue_capacity() -> NonZero<usize> {
     capacity
 }
 
+/// Returns the default number of seconds to wait for a response.
+fn default_call_timeout() -> NonZero<u64> {
+    let Some(timeout) = NonZero::new(60) else { unreachable!("the default timeout must be non-zero") };
+
+    timeout
+}
+
+/// Returns the default maximum number of cache entries.
+const fn default_cache_entries() -> usize {
+    1024
+}
+
+/// Returns the default maximum number of cached bytes.
+const fn default_cache_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+/// Returns the default interval between sweeps of expired data.
+fn default_sweep_interval() -> NonZero<u64> {
+    let Some(interval) = NonZero::new(60 * 60) else { unreachable!("the default interval must be non-zero") };
+
+    interval
+}
+
+/// Returns the default index of the path segment that groups stored data.
+const fn default_quota_segment() -> usize {
+    1
+}
+
+/// Returns the default number of kept snapshots.
+fn default_snapshot_rotations() -> NonZero<usize> {
+    let Some(rotations) = NonZero::new(7) else { unreachable!("the default rotations must be non-zero") };
+
+    rotations
+}
+
 /// Returns the default data directory.
 fn default_directory() -> PathBuf {
     std::env::current_dir().map_or_else(|_| PathBuf::from("./res/data/"), |v| v.join("res/data"))
 }
+
+/// Returns the default snapshot directory.
+fn default_snapshot_directory() -> PathBuf {
+    std::env::current_dir().map_or_else(|_| PathBuf::from("./res/snapshots/"), |v| v.join("res/snapshots"))
+}
+
+/// Returns the default settings, storing data within the given directory of the memory system if it is enabled.
+#[cfg(test)]
+pub(crate) fn test_settings(directory: &str) -> Settings {
+    let mut settings = OptionalSettings::default().fill_defaults();
+
+    #[cfg(feature = "system-memory")]
+    {
+        settings.system = System::Memory;
+    }
+    settings.directory = PathBuf::from(directory);
+
+    settings
+}
@@ -0,0 +1,340 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::collections::HashSet;
+use std::num::NonZero;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::{debug, warn};
+
+use crate::Storage;
+use crate::archive::Manifest;
+use crate::batch::Operation;
+use crate::system::DataReader;
+
+/// The prefix of the file name of every snapshot.
+const PREFIX: &str = "snapshot-";
+/// The extension of every snapshot.
+const EXTENSION: &str = ".tar.gz";
+
+impl Storage {
+    /// Writes a snapshot of all stored data into the snapshot directory, returning the snapshot's path.
+    ///
+    /// Once written, the oldest snapshots are deleted until only the configured number of rotations remain.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stored data could not be read or the snapshot could not be written.
+    pub fn blocking_snapshot(&self) -> anyhow::Result<Box<Path>> {
+        self.blocking_snapshot_at(SystemTime::now())
+    }
+
+    /// Writes a snapshot of all stored data, named after the given time, into the snapshot directory, returning the
+    /// snapshot's path.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stored data could not be read or the snapshot could not be written.
+    fn blocking_snapshot_at(&self, time: SystemTime) -> anyhow::Result<Box<Path>> {
+        let paths = self.blocking_list(Path::new(""))?;
+        let mut entries = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let bytes = self.blocking_read(&path)?;
+
+            entries.push(crate::archive::export_entry(path, bytes, false)?);
+        }
+
+        let directory = &self.settings.snapshot_directory;
+        let destination = self::path_for(directory, time);
+
+        crate::archive::write_archive(&destination, &entries)?;
+        self::rotate(directory, self.settings.snapshot_rotations)?;
+
+        Ok(destination)
+    }
+
+    /// Writes a snapshot of all stored data into the snapshot directory, returning the snapshot's path.
+    ///
+    /// Once written, the oldest snapshots are deleted until only the configured number of rotations remain.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stored data could not be read or the snapshot could not be written.
+    pub async fn snapshot(&self) -> anyhow::Result<Box<Path>> {
+        let paths = self.list(Path::new("")).await?;
+        let mut entries = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let bytes = self.read(&path).await?;
+
+            entries.push(crate::archive::export_entry(path, bytes, false)?);
+        }
+
+        let directory = self.settings.snapshot_directory.clone();
+        let rotations = self.settings.snapshot_rotations;
+        let destination = self::path_for(&directory, SystemTime::now());
+
+        tokio::task::spawn_blocking(move || {
+            crate::archive::write_archive(&destination, &entries)?;
+            self::rotate(&directory, rotations)?;
+
+            Ok(destination)
+        })
+        .await?
+    }
+
+    /// Replaces all stored data with the contents of the given snapshot, returning the snapshot's manifest.
+    ///
+    /// Relative paths are resolved within the snapshot directory. If no path is given, the most recent snapshot is
+    /// restored. Data that is not contained within the snapshot is deleted, and the restore is all-or-nothing.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the snapshot could not be found, read, or verified, or if the data could
+    /// not be written.
+    pub fn blocking_restore(&mut self, snapshot: Option<&Path>) -> anyhow::Result<Manifest> {
+        let source = self.resolve_snapshot(snapshot)?;
+        let (manifest, entries) = crate::archive::read_archive(&source)?;
+        let kept = manifest.entries.iter().map(|entry| &*entry.path).collect::<HashSet<_>>();
+        let mut operations = Vec::with_capacity(manifest.entries.len());
+
+        for path in self.blocking_list(Path::new(""))? {
+            if !kept.contains(&*path) {
+                operations.push(Operation::Delete(path));
+            }
+        }
+
+        operations.extend(crate::archive::import_operations(&manifest, entries, false)?);
+
+        self.blocking_apply(&operations)?;
+
+        debug!(path = ?source, count = manifest.entries.len(), "restored snapshot");
+
+        Ok(manifest)
+    }
+
+    /// Replaces all stored data with the contents of the given snapshot, returning the snapshot's manifest.
+    ///
+    /// Relative paths are resolved within the snapshot directory. If no path is given, the most recent snapshot is
+    /// restored. Data that is not contained within the snapshot is deleted, and the restore is all-or-nothing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the snapshot could not be found, read, or verified, or if the data could
+    /// not be written.
+    pub async fn restore(&mut self, snapshot: Option<&Path>) -> anyhow::Result<Manifest> {
+        let source = self.resolve_snapshot(snapshot)?;
+        let (manifest, entries) = {
+            let source = source.clone();
+
+            tokio::task::spawn_blocking(move || crate::archive::read_archive(&source)).await??
+        };
+        let kept = manifest.entries.iter().map(|entry| &*entry.path).collect::<HashSet<_>>();
+        let mut operations = Vec::with_capacity(manifest.entries.len());
+
+        for path in self.list(Path::new("")).await? {
+            if !kept.contains(&*path) {
+                operations.push(Operation::Delete(path));
+            }
+        }
+
+        operations.extend(crate::archive::import_operations(&manifest, entries, false)?);
+
+        self.apply(&operations).await?;
+
+        debug!(path = ?source, count = manifest.entries.len(), "restored snapshot");
+
+        Ok(manifest)
+    }
+
+    /// Returns the path of the given snapshot, or of the most recent snapshot if no path is given.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the snapshot directory could not be read or contains no snapshots.
+    fn resolve_snapshot(&self, snapshot: Option<&Path>) -> anyhow::Result<PathBuf> {
+        let directory = &self.settings.snapshot_directory;
+
+        if let Some(snapshot) = snapshot {
+            return Ok(directory.join(snapshot));
+        }
+
+        self::list(directory)?.pop().ok_or_else(|| anyhow::anyhow!("no snapshots found within {}", directory.display()))
+    }
+}
+
+/// Returns the path of a snapshot taken at the given time within the given directory.
+fn path_for(directory: &Path, time: SystemTime) -> Box<Path> {
+    let millis = time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis());
+
+    directory.join(format!("{PREFIX}{millis}{EXTENSION}")).into_boxed_path()
+}
+
+/// Returns the paths of every snapshot within the given directory, ordered from the oldest to the most recent.
+///
+/// # Errors
+///
+/// This function will return an error if the directory could not be read.
+pub fn list(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !directory.try_exists()? {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+        let Some(millis) = name.strip_prefix(PREFIX).and_then(|name| name.strip_suffix(EXTENSION)) else { continue };
+        let Ok(millis) = millis.parse::<u128>() else { continue };
+
+        snapshots.push((millis, path));
+    }
+
+    snapshots.sort_unstable();
+
+    Ok(snapshots.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Deletes the oldest snapshots within the given directory until only the given number of rotations remain, returning
+/// the number of deleted snapshots.
+///
+/// # Errors
+///
+/// This function will return an error if the directory could not be read or a snapshot could not be deleted.
+fn rotate(directory: &Path, rotations: NonZero<usize>) -> std::io::Result<usize> {
+    let mut snapshots = self::list(directory)?;
+    let excess = snapshots.len().saturating_sub(rotations.get());
+
+    for path in snapshots.drain(.. excess) {
+        std::fs::remove_file(&path)?;
+
+        debug!(?path, "deleted old snapshot");
+    }
+
+    Ok(excess)
+}
+
+/// A thread that periodically asks the storage thread to take a snapshot.
+#[derive(Debug)]
+pub(crate) struct Snapshotter {
+    /// The sender that stops the snapshotter when dropped.
+    sender: Sender<()>,
+    /// The snapshotter's thread handle.
+    handle: JoinHandle<()>,
+}
+
+impl Snapshotter {
+    /// Spawns a new [`Snapshotter`] that takes snapshots at the given interval.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub(crate) fn spawn(interval: Duration) -> std::io::Result<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = std::thread::Builder::new().name("storage-snapshotter".to_owned()).spawn(move || {
+            while receiver.recv_timeout(interval) == Err(RecvTimeoutError::Timeout) {
+                match crate::thread::blocking_snapshot() {
+                    Ok(path) => debug!(?path, "took scheduled snapshot"),
+                    Err(error) => warn!(%error, "failed to take scheduled snapshot"),
+                }
+            }
+        })?;
+
+        Ok(Self { sender, handle })
+    }
+
+    /// Stops the snapshotter, blocking the current thread until any ongoing snapshot has been written.
+    pub(crate) fn stop(self) {
+        drop(self.sender);
+
+        if self.handle.join().is_err() {
+            warn!("snapshotter thread panicked");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "system-memory"))]
+mod tests {
+    use std::num::NonZero;
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::Storage;
+    use crate::system::{DataReader, DataWriter};
+
+    #[test]
+    fn restore_rotated_snapshots() -> anyhow::Result<()> {
+        let directory = std::env::temp_dir().join(format!("ina-snapshots-{}", std::process::id()));
+        let Some(snapshot_rotations) = NonZero::new(2) else { unreachable!() };
+        let mut settings = crate::settings::test_settings("snapshot");
+
+        settings.snapshot_directory = directory.clone();
+        settings.snapshot_rotations = snapshot_rotations;
+
+        let mut storage = Storage::new(settings);
+
+        storage.blocking_write(Path::new("a"), b"old")?;
+
+        let first = storage.blocking_snapshot_at(UNIX_EPOCH + Duration::from_millis(1))?;
+
+        storage.blocking_write(Path::new("a"), b"new")?;
+        storage.blocking_write(Path::new("b"), b"new")?;
+
+        let second = storage.blocking_snapshot_at(UNIX_EPOCH + Duration::from_millis(2))?;
+        let third = storage.blocking_snapshot_at(UNIX_EPOCH + Duration::from_millis(3))?;
+
+        assert_eq!(super::list(&directory)?, [second.to_path_buf(), third.to_path_buf()]);
+        assert!(!first.exists());
+
+        storage.blocking_delete(Path::new("b"))?;
+        storage.blocking_restore(None)?;
+
+        assert_eq!(&*storage.blocking_read(Path::new("b"))?, b"new");
+
+        storage.blocking_write(Path::new("a"), b"old")?;
+        storage.blocking_write(Path::new("c"), b"new")?;
+        storage.blocking_restore(second.file_name().map(Path::new))?;
+
+        assert_eq!(&*storage.blocking_read(Path::new("a"))?, b"new");
+        assert!(!storage.blocking_exists(Path::new("c"))?);
+
+        std::fs::remove_dir_all(directory)?;
+
+        Ok(())
+    }
+}
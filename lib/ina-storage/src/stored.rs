@@ -14,16 +14,48 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
+use std::ffi::OsStr;
+use std::fmt::Display;
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 
-use crate::format::DataFormat;
+use crate::batch::Batch;
+use crate::format::{DataDecode, DataEncode, DataFormat};
+
+/// The magic byte sequence that begins the version header of a stored value.
+const VERSION_MAGIC: [u8; 4] = *b"1N4V";
+/// The magic byte sequence that begins the version header of a stored value that expires.
+///
+/// This header is followed by the data version and the expiry time, in seconds since the Unix epoch.
+const EXPIRY_MAGIC: [u8; 4] = *b"1N4X";
+/// The length of the longest version header of a stored value.
+pub(crate) const HEADER_LENGTH: usize = EXPIRY_MAGIC.len() + size_of::<u32>() + size_of::<u64>();
 
 /// A value that can be stored within the storage system.
 pub trait Stored: Send + Sync + Serialize + for<'de> Deserialize<'de> {
+    /// The current version of this type's stored data.
+    ///
+    /// This is written alongside every stored value, and data written before versioning was introduced is considered
+    /// to be version `1`.
+    const DATA_VERSION: u32 = 1;
+
+    /// The default lifetime of this type's stored values.
+    ///
+    /// Values written without an explicit lifetime expire once this much time has passed since they were last written.
+    /// If this is [`None`], values never expire by default.
+    const DATA_EXPIRY: Option<Duration> = None;
+
+    /// The names of this type's secondary indexes.
+    ///
+    /// Each index maps keys to the values that contain them, and is updated whenever a value is written, renamed, or
+    /// deleted through this type's API.
+    const DATA_INDICES: &'static [&'static str] = &[];
+
     /// The arguments required to construct a new path for this type.
     type PathArguments: Send;
 
@@ -36,6 +68,25 @@ pub trait Stored: Send + Sync + Serialize + for<'de> Deserialize<'de> {
     /// Returns the expected storage path for this value.
     fn data_path(&self) -> impl AsRef<Path> + Send;
 
+    /// Decodes a value of this type that was stored using the given data version, migrating it into the current
+    /// version if necessary.
+    ///
+    /// Returns [`None`] if the given version cannot be migrated into the current version.
+    fn data_decode<F: DataDecode>(format: &F, version: u32, bytes: &[u8]) -> Option<Result<Self, F::Error>> {
+        (version == Self::DATA_VERSION).then(|| format.decode(bytes))
+    }
+
+    /// Returns the path of the given secondary index of this type.
+    #[must_use]
+    fn data_index_path(index: &str) -> Box<Path> {
+        crate::index::path_for(&std::any::type_name::<Self>().replace(|c: char| !c.is_alphanumeric(), "_"), index)
+    }
+
+    /// Returns the keys of this value within the given secondary index.
+    fn data_index_keys(&self, _index: &str) -> Box<[Box<str>]> {
+        Box::default()
+    }
+
     /// Returns an asynchronous API for this stored value type.
     fn async_api() -> AsyncApi<Self> {
         AsyncApi(PhantomData)
@@ -117,6 +168,33 @@ impl<T: Stored> AsyncApi<T> {
         crate::thread::write(path.into_boxed_path(), value).await
     }
 
+    /// Writes the given value into the storage system at the path represented by the given path arguments, expiring
+    /// after the given lifetime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be written to.
+    pub async fn write_for(self, arguments: T::PathArguments, value: &T, lifetime: Duration) -> Result<()> {
+        let format = T::data_format();
+        let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
+
+        crate::thread::write_for(path.into_boxed_path(), value, lifetime).await
+    }
+
+    /// Returns the remaining lifetime of the stored value represented by the given path arguments.
+    ///
+    /// Returns [`None`] if the value never expires.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    pub async fn lifetime(self, arguments: T::PathArguments) -> Result<Option<Duration>> {
+        let format = T::data_format();
+        let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
+
+        crate::thread::lifetime(path.into_boxed_path()).await
+    }
+
     /// Renames the value represented by the given path arguments.
     ///
     /// # Errors
@@ -127,7 +205,7 @@ impl<T: Stored> AsyncApi<T> {
         let from = T::data_path_for(from).as_ref().with_extension(format.extension());
         let into = T::data_path_for(into).as_ref().with_extension(format.extension());
 
-        crate::thread::rename(from.into_boxed_path(), into.into_boxed_path()).await
+        self::rename::<T>(from.into_boxed_path(), into.into_boxed_path()).await
     }
 
     /// Deletes the value represented by the given path arguments.
@@ -139,7 +217,70 @@ impl<T: Stored> AsyncApi<T> {
         let format = T::data_format();
         let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
 
-        crate::thread::delete(path.into_boxed_path()).await
+        self::delete::<T>(path.into_boxed_path()).await
+    }
+
+    /// Returns the paths of all values of this type stored under the given path prefix.
+    ///
+    /// Paths without this type's full format extension are skipped, and the extension is removed from the returned
+    /// paths.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix cannot be read.
+    pub async fn list(self, prefix: impl AsRef<Path> + Send) -> Result<Box<[Box<Path>]>> {
+        let format = T::data_format();
+        let paths = crate::thread::list(prefix.as_ref().into()).await?;
+
+        Ok(paths.iter().filter_map(|path| self::strip_extension(path, format.extension().as_ref())).collect())
+    }
+
+    /// Returns all values of this type stored under the given path prefix.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix or any of the contained values cannot be read.
+    pub async fn scan(self, prefix: impl AsRef<Path> + Send) -> Result<Vec<T>> {
+        let extension = T::data_format().extension().as_ref().to_os_string();
+        let paths = crate::thread::list(prefix.as_ref().into()).await?;
+        let mut values = Vec::with_capacity(paths.len());
+
+        for path in paths.into_iter().filter(|path| self::strip_extension(path, &extension).is_some()) {
+            values.push(crate::thread::read(path).await?);
+        }
+
+        Ok(values)
+    }
+
+    /// Returns all values of this type with the given key within the given secondary index.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index does not exist, or the index or any of the found values cannot
+    /// be read.
+    pub async fn find_by_index(self, index: &str, key: impl Display + Send) -> Result<Vec<T>> {
+        let key = key.to_string().into_boxed_str();
+
+        if !T::DATA_INDICES.contains(&index) {
+            bail!("unknown data index '{index}'");
+        }
+
+        let paths = crate::thread::find_in_index(T::data_index_path(index), key.clone()).await?;
+        let mut values = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            // Data deleted or expired outside of this type's API keeps its index entries until the next sweep prunes
+            // them.
+            if !crate::thread::exists(path.clone()).await? {
+                continue;
+            }
+
+            if let Some(value) = self::filter_indexed(crate::thread::read(path).await, index, &key)? {
+                values.push(value);
+            }
+        }
+
+        Ok(values)
     }
 }
 
@@ -198,6 +339,32 @@ impl<T: Stored> AsyncHolderApi<'_, T> {
         crate::thread::write(path.into_boxed_path(), self.0).await
     }
 
+    /// Writes this value into the storage system, expiring after the given lifetime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be written to.
+    pub async fn write_for(self, lifetime: Duration) -> Result<()> {
+        let format = T::data_format();
+        let path = self.0.data_path().as_ref().with_extension(format.extension());
+
+        crate::thread::write_for(path.into_boxed_path(), self.0, lifetime).await
+    }
+
+    /// Returns the remaining lifetime of this value as saved within the storage system.
+    ///
+    /// Returns [`None`] if the value never expires.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    pub async fn lifetime(self) -> Result<Option<Duration>> {
+        let format = T::data_format();
+        let path = self.0.data_path().as_ref().with_extension(format.extension());
+
+        crate::thread::lifetime(path.into_boxed_path()).await
+    }
+
     /// Renames this value.
     ///
     /// # Errors
@@ -208,7 +375,7 @@ impl<T: Stored> AsyncHolderApi<'_, T> {
         let from = self.0.data_path().as_ref().with_extension(format.extension());
         let into = T::data_path_for(into).as_ref().with_extension(format.extension());
 
-        crate::thread::rename(from.into_boxed_path(), into.into_boxed_path()).await
+        self::rename::<T>(from.into_boxed_path(), into.into_boxed_path()).await
     }
 
     /// Deletes this value.
@@ -220,7 +387,7 @@ impl<T: Stored> AsyncHolderApi<'_, T> {
         let format = T::data_format();
         let path = self.0.data_path().as_ref().with_extension(format.extension());
 
-        crate::thread::delete(path.into_boxed_path()).await
+        self::delete::<T>(path.into_boxed_path()).await
     }
 }
 
@@ -308,6 +475,45 @@ impl<T: Stored> SyncApi<T> {
         crate::thread::blocking_write(path.into_boxed_path(), value)
     }
 
+    /// Writes the given value into the storage system at the path represented by the given path arguments, expiring
+    /// after the given lifetime.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be written to.
+    pub fn write_for(self, arguments: T::PathArguments, value: &T, lifetime: Duration) -> Result<()> {
+        let format = T::data_format();
+        let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
+
+        crate::thread::blocking_write_for(path.into_boxed_path(), value, lifetime)
+    }
+
+    /// Returns the remaining lifetime of the stored value represented by the given path arguments.
+    ///
+    /// Returns [`None`] if the value never expires.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    pub fn lifetime(self, arguments: T::PathArguments) -> Result<Option<Duration>> {
+        let format = T::data_format();
+        let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
+
+        crate::thread::blocking_lifetime(path.into_boxed_path())
+    }
+
     /// Renames the value represented by the given path arguments.
     ///
     /// This blocks the current thread.
@@ -324,7 +530,7 @@ impl<T: Stored> SyncApi<T> {
         let from = T::data_path_for(from).as_ref().with_extension(format.extension());
         let into = T::data_path_for(into).as_ref().with_extension(format.extension());
 
-        crate::thread::blocking_rename(from.into_boxed_path(), into.into_boxed_path())
+        self::blocking_rename::<T>(from.into_boxed_path(), into.into_boxed_path())
     }
 
     /// Deletes the value represented by the given path arguments.
@@ -342,7 +548,87 @@ impl<T: Stored> SyncApi<T> {
         let format = T::data_format();
         let path = T::data_path_for(arguments).as_ref().with_extension(format.extension());
 
-        crate::thread::blocking_delete(path.into_boxed_path())
+        self::blocking_delete::<T>(path.into_boxed_path())
+    }
+
+    /// Returns the paths of all values of this type stored under the given path prefix.
+    ///
+    /// Paths without this type's full format extension are skipped, and the extension is removed from the returned
+    /// paths.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix cannot be read.
+    pub fn list(self, prefix: impl AsRef<Path>) -> Result<Box<[Box<Path>]>> {
+        let format = T::data_format();
+        let paths = crate::thread::blocking_list(prefix.as_ref().into())?;
+
+        Ok(paths.iter().filter_map(|path| self::strip_extension(path, format.extension().as_ref())).collect())
+    }
+
+    /// Returns all values of this type stored under the given path prefix.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix or any of the contained values cannot be read.
+    pub fn scan(self, prefix: impl AsRef<Path>) -> Result<Vec<T>> {
+        let format = T::data_format();
+        let paths = crate::thread::blocking_list(prefix.as_ref().into())?;
+
+        paths
+            .into_iter()
+            .filter(|path| self::strip_extension(path, format.extension().as_ref()).is_some())
+            .map(crate::thread::blocking_read)
+            .collect()
+    }
+
+    /// Returns all values of this type with the given key within the given secondary index.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the index does not exist, or the index or any of the found values cannot
+    /// be read.
+    pub fn find_by_index(self, index: &str, key: impl Display) -> Result<Vec<T>> {
+        let key = key.to_string().into_boxed_str();
+
+        if !T::DATA_INDICES.contains(&index) {
+            bail!("unknown data index '{index}'");
+        }
+
+        let paths = crate::thread::blocking_find_in_index(T::data_index_path(index), key.clone())?;
+        let mut values = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            // Data deleted or expired outside of this type's API keeps its index entries until the next sweep prunes
+            // them.
+            if !crate::thread::blocking_exists(path.clone())? {
+                continue;
+            }
+
+            if let Some(value) = self::filter_indexed(crate::thread::blocking_read(path), index, &key)? {
+                values.push(value);
+            }
+        }
+
+        Ok(values)
     }
 }
 
@@ -425,6 +711,44 @@ impl<T: Stored> SyncHolderApi<'_, T> {
         crate::thread::blocking_write(path.into_boxed_path(), self.0)
     }
 
+    /// Writes this value into the storage system, expiring after the given lifetime.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be written to.
+    pub fn write_for(self, lifetime: Duration) -> Result<()> {
+        let format = T::data_format();
+        let path = self.0.data_path().as_ref().with_extension(format.extension());
+
+        crate::thread::blocking_write_for(path.into_boxed_path(), self.0, lifetime)
+    }
+
+    /// Returns the remaining lifetime of this value as saved within the storage system.
+    ///
+    /// Returns [`None`] if the value never expires.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    pub fn lifetime(self) -> Result<Option<Duration>> {
+        let format = T::data_format();
+        let path = self.0.data_path().as_ref().with_extension(format.extension());
+
+        crate::thread::blocking_lifetime(path.into_boxed_path())
+    }
+
     /// Renames this value.
     ///
     /// This blocks the current thread.
@@ -441,7 +765,7 @@ impl<T: Stored> SyncHolderApi<'_, T> {
         let from = self.0.data_path().as_ref().with_extension(format.extension());
         let into = T::data_path_for(into).as_ref().with_extension(format.extension());
 
-        crate::thread::blocking_rename(from.into_boxed_path(), into.into_boxed_path())
+        self::blocking_rename::<T>(from.into_boxed_path(), into.into_boxed_path())
     }
 
     /// Deletes this value.
@@ -459,6 +783,234 @@ impl<T: Stored> SyncHolderApi<'_, T> {
         let format = T::data_format();
         let path = self.0.data_path().as_ref().with_extension(format.extension());
 
-        crate::thread::blocking_delete(path.into_boxed_path())
+        self::blocking_delete::<T>(path.into_boxed_path())
+    }
+}
+
+/// Deletes the value at the given path, removing it from its type's secondary indexes.
+///
+/// # Errors
+///
+/// This function will return an error if the path cannot be written to.
+async fn delete<T: Stored>(path: Box<Path>) -> Result<()> {
+    if T::DATA_INDICES.is_empty() {
+        crate::thread::delete(path).await
+    } else {
+        Batch::new().delete_at::<T>(&path).commit().await
+    }
+}
+
+/// Deletes the value at the given path, removing it from its type's secondary indexes.
+///
+/// # Errors
+///
+/// This function will return an error if the path cannot be written to.
+///
+/// # Panics
+///
+/// Panics if this is called in an asynchronous context.
+fn blocking_delete<T: Stored>(path: Box<Path>) -> Result<()> {
+    if T::DATA_INDICES.is_empty() {
+        crate::thread::blocking_delete(path)
+    } else {
+        Batch::new().delete_at::<T>(&path).blocking_commit()
+    }
+}
+
+/// Renames the value at the given path, moving it within its type's secondary indexes.
+///
+/// # Errors
+///
+/// This function will return an error if the path cannot be written to.
+async fn rename<T: Stored>(from: Box<Path>, into: Box<Path>) -> Result<()> {
+    if T::DATA_INDICES.is_empty() {
+        crate::thread::rename(from, into).await
+    } else {
+        Batch::new().rename_at::<T>(&from, &into).commit().await
+    }
+}
+
+/// Renames the value at the given path, moving it within its type's secondary indexes.
+///
+/// # Errors
+///
+/// This function will return an error if the path cannot be written to.
+///
+/// # Panics
+///
+/// Panics if this is called in an asynchronous context.
+fn blocking_rename<T: Stored>(from: Box<Path>, into: Box<Path>) -> Result<()> {
+    if T::DATA_INDICES.is_empty() {
+        crate::thread::blocking_rename(from, into)
+    } else {
+        Batch::new().rename_at::<T>(&from, &into).blocking_commit()
+    }
+}
+
+/// Returns the given read value if it still has the given key within the given index.
+///
+/// Values that expired since they were indexed are skipped.
+///
+/// # Errors
+///
+/// This function will return an error if the value could not be read for any other reason.
+fn filter_indexed<T: Stored>(result: Result<T>, index: &str, key: &str) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(value.data_index_keys(index).iter().any(|k| &**k == key).then_some(value)),
+        Err(error) if matches!(error.downcast_ref(), Some(crate::Error::Expired(_))) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Returns the storage path, including the format extension, of the value represented by the given path arguments.
+pub(crate) fn path_for<T: Stored>(arguments: T::PathArguments) -> Box<Path> {
+    let format = T::data_format();
+
+    T::data_path_for(arguments).as_ref().with_extension(format.extension()).into_boxed_path()
+}
+
+/// Encodes the given value, prefixed with a header containing its type's current data version and its expiry time, if
+/// any.
+///
+/// # Errors
+///
+/// This function will return an error if the value could not be encoded.
+pub(crate) fn encode<T: Stored>(value: &T, expiry: Option<SystemTime>) -> Result<Arc<[u8]>> {
+    let bytes = T::data_format().encode(value)?;
+    let mut output = Vec::with_capacity(HEADER_LENGTH + bytes.len());
+
+    if let Some(expiry) = expiry {
+        let seconds = expiry.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+
+        output.extend_from_slice(&EXPIRY_MAGIC);
+        output.extend_from_slice(&T::DATA_VERSION.to_le_bytes());
+        output.extend_from_slice(&seconds.to_le_bytes());
+    } else {
+        output.extend_from_slice(&VERSION_MAGIC);
+        output.extend_from_slice(&T::DATA_VERSION.to_le_bytes());
+    }
+
+    output.extend_from_slice(&bytes);
+
+    Ok(output.into())
+}
+
+/// Returns the time at which a value written now with the given lifetime expires.
+pub(crate) fn expiry_after(lifetime: Option<Duration>) -> Option<SystemTime> {
+    lifetime.and_then(|lifetime| SystemTime::now().checked_add(lifetime))
+}
+
+/// Returns the time at which the given stored bytes expire, or [`None`] if they never expire.
+pub(crate) fn split_expiry(bytes: &[u8]) -> Option<SystemTime> {
+    let (header, _) = self::split_header(bytes);
+    let seconds =
+        header.strip_prefix(&EXPIRY_MAGIC)?.get(size_of::<u32>() ..)?.first_chunk::<{ size_of::<u64>() }>()?;
+
+    UNIX_EPOCH.checked_add(Duration::from_secs(u64::from_le_bytes(*seconds)))
+}
+
+/// Returns the remaining lifetime of the given stored bytes, or [`None`] if they never expire.
+///
+/// Expired bytes have a remaining lifetime of zero.
+pub(crate) fn remaining_lifetime(bytes: &[u8]) -> Option<Duration> {
+    self::split_expiry(bytes).map(|expiry| expiry.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Returns whether the given stored bytes have expired.
+pub(crate) fn is_expired(bytes: &[u8]) -> bool {
+    self::split_expiry(bytes).is_some_and(|expiry| expiry <= SystemTime::now())
+}
+
+/// Decodes a value from the given bytes, returning the value and the data version that it was stored with.
+///
+/// If the bytes were written using an older data version, the value is migrated into the current version.
+///
+/// # Errors
+///
+/// This function will return an error if the value could not be decoded or migrated.
+pub(crate) fn decode<T: Stored>(bytes: &[u8]) -> Result<(T, u32)> {
+    let (version, bytes) = self::split_version(bytes);
+
+    match T::data_decode(&T::data_format(), version, bytes) {
+        Some(result) => Ok((result?, version)),
+        None => Err(crate::Error::UnsupportedVersion(version, T::DATA_VERSION).into()),
+    }
+}
+
+/// Splits the given bytes into their version header and the remaining encoded bytes.
+///
+/// Bytes without a version header are returned with an empty header.
+pub(crate) fn split_header(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let length = if bytes.starts_with(&VERSION_MAGIC) {
+        VERSION_MAGIC.len() + size_of::<u32>()
+    } else if bytes.starts_with(&EXPIRY_MAGIC) {
+        HEADER_LENGTH
+    } else {
+        return (&[], bytes);
+    };
+
+    if bytes.len() >= length { bytes.split_at(length) } else { (&[], bytes) }
+}
+
+/// Splits the given bytes into their stored data version and the remaining encoded bytes.
+///
+/// Bytes without a version header are assumed to have been written using version `1`.
+fn split_version(bytes: &[u8]) -> (u32, &[u8]) {
+    let (header, bytes) = self::split_header(bytes);
+    let version = header.get(VERSION_MAGIC.len() ..).and_then(<[u8]>::first_chunk::<{ size_of::<u32>() }>);
+
+    (version.map_or(1, |version| u32::from_le_bytes(*version)), bytes)
+}
+
+/// Returns the given path with the given format extension removed, or [`None`] if the path does not end with it.
+///
+/// The extension is compared as a whole, so stacked extensions like `pack.gz.cha` only match paths that end with every
+/// part of the stack.
+fn strip_extension(path: &Path, extension: &OsStr) -> Option<Box<Path>> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.strip_suffix(extension.to_str()?)?.strip_suffix('.')?;
+
+    (!stem.is_empty()).then(|| path.with_file_name(stem).into_boxed_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn read_expiry_header() {
+        let bytes = [&super::EXPIRY_MAGIC[..], &2_u32.to_le_bytes(), &1_000_u64.to_le_bytes(), b"data"].concat();
+
+        assert_eq!(super::split_header(&bytes).1, b"data");
+        assert_eq!(super::split_version(&bytes), (2, &b"data"[..]));
+        assert_eq!(super::split_expiry(&bytes), Some(UNIX_EPOCH + Duration::from_secs(1_000)));
+        assert_eq!(super::remaining_lifetime(&bytes), Some(Duration::ZERO));
+        assert!(super::is_expired(&bytes));
+
+        let bytes = [&super::VERSION_MAGIC[..], &2_u32.to_le_bytes(), b"data"].concat();
+
+        assert_eq!(super::split_version(&bytes), (2, &b"data"[..]));
+        assert_eq!(super::split_expiry(&bytes), None);
+        assert!(!super::is_expired(&bytes));
+    }
+
+    #[test]
+    fn strip_stacked_extensions() {
+        let extension = OsStr::new("pack.gz.cha");
+
+        assert_eq!(
+            super::strip_extension(Path::new("role/1/2.pack.gz.cha"), extension),
+            Some(Path::new("role/1/2").into())
+        );
+        assert_eq!(
+            super::strip_extension(Path::new("role/1/2.v1.pack.gz.cha"), extension),
+            Some(Path::new("role/1/2.v1").into())
+        );
+        assert_eq!(super::strip_extension(Path::new("role/1/2.pack.gz"), extension), None);
+        assert_eq!(super::strip_extension(Path::new("role/1/2.gz.cha"), extension), None);
+        assert_eq!(super::strip_extension(Path::new("role/1/2pack.gz.cha"), extension), None);
+        assert_eq!(super::strip_extension(Path::new("role/1/.pack.gz.cha"), extension), None);
     }
 }
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, DuplexStream, ReadBuf};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::format::{StreamFormat, StreamReader};
+use crate::system::DataStream;
+
+/// The number of bytes buffered between a blocking thread that encodes or decodes a stream and its consumer.
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// A reader that blocks the current thread while reading from its inner asynchronous reader.
+#[derive(Debug)]
+struct BlockingReader<R> {
+    /// The inner reader.
+    inner: R,
+    /// The handle of the runtime that drives the inner reader.
+    handle: Handle,
+}
+
+impl<R: AsyncRead + Unpin> Read for BlockingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.handle.block_on(self.inner.read(buf))
+    }
+}
+
+/// A stream of the bytes written by a blocking thread.
+///
+/// Once the thread stops writing, its result decides whether the stream ends successfully, so that consumers never
+/// mistake incomplete data for complete data.
+#[derive(Debug)]
+struct Piped {
+    /// The receiving end of the pipe.
+    receiver: DuplexStream,
+    /// The thread writing into the pipe, or [`None`] if its result has already been returned.
+    task: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl AsyncRead for Piped {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+
+        ready!(Pin::new(&mut this.receiver).poll_read(cx, buf))?;
+
+        if buf.filled().len() > start || buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let Some(task) = this.task.as_mut() else { return Poll::Ready(Ok(())) };
+        let result = ready!(Pin::new(task).poll(cx));
+
+        this.task = None;
+
+        Poll::Ready(result.unwrap_or_else(|error| Err(std::io::Error::other(error))))
+    }
+}
+
+/// Returns the path that data streamed into the given path using the given format is stored at.
+pub fn path_for<F: StreamFormat>(path: &Path, format: &F) -> Box<Path> {
+    path.with_extension(format.extension()).into_boxed_path()
+}
+
+/// Encodes the bytes of the given reader using the given format, streaming them into the given path and returning the
+/// number of bytes stored.
+///
+/// Encoding is performed on a blocking thread, and only a bounded number of bytes is held in memory at a time. The
+/// path is only replaced once the reader has been read in full.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent, or if reading, encoding, or writing fails.
+pub async fn write<F>(path: &Path, format: &F, reader: impl AsyncRead + Send + Unpin + 'static) -> anyhow::Result<u64>
+where
+    F: StreamFormat + Clone + Send + Sync + 'static,
+{
+    let path = self::path_for(path, format);
+    let format = format.clone();
+    let handle = Handle::current();
+    let stream = self::pipe(move || format.encode_stream(Box::new(BlockingReader { inner: reader, handle })));
+
+    crate::thread::write_stream(path, stream).await
+}
+
+/// Returns a stream of the decoded bytes of the data that was streamed into the given path using the given format.
+///
+/// Decoding is performed on a blocking thread, and only a bounded number of bytes is held in memory at a time.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or the data could not be opened. Decoding
+/// errors are returned by reads of the stream.
+pub async fn read<F>(path: &Path, format: &F) -> anyhow::Result<DataStream>
+where
+    F: StreamFormat + Clone + Send + Sync + 'static,
+{
+    let stored = crate::thread::read_stream(self::path_for(path, format)).await?;
+    let format = format.clone();
+    let handle = Handle::current();
+
+    Ok(self::pipe(move || format.decode_stream(Box::new(BlockingReader { inner: stored, handle }))))
+}
+
+/// Returns a stream of the bytes yielded by the reader returned by the given function, which is created and read on a
+/// blocking thread.
+fn pipe(reader: impl FnOnce() -> StreamReader<'static> + Send + 'static) -> DataStream {
+    let (mut sender, receiver) = tokio::io::duplex(PIPE_CAPACITY);
+    let handle = Handle::current();
+
+    let task = tokio::task::spawn_blocking(move || {
+        let mut reader = reader();
+        let mut buffer = vec![0; PIPE_CAPACITY];
+
+        loop {
+            let length = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            handle.block_on(sender.write_all(&buffer[.. length]))?;
+        }
+
+        handle.block_on(sender.shutdown())
+    });
+
+    DataStream::new(Piped { receiver, task: Some(task) })
+}
+
+#[cfg(all(test, feature = "system-memory"))]
+mod tests {
+    use std::io::{Cursor, Read};
+    use std::num::NonZero;
+    use std::path::Path;
+
+    use crate::Storage;
+    use crate::format::{Raw, StreamFormat};
+    use crate::system::{BlockingDataStream, DataReader, DataWriter};
+
+    #[test]
+    fn stream_into_storage() -> anyhow::Result<()> {
+        let mut settings = crate::settings::test_settings("stream");
+
+        settings.checksums = true;
+        settings.hard_quota = NonZero::new(64 * 1024);
+
+        let storage = Storage::new(settings);
+
+        let path = super::path_for(Path::new("blob/1/data"), &Raw);
+        let data = (0 .. 48 * 1024_u32).map(|n| n.to_le_bytes()[1]).collect::<Vec<_>>();
+        let encoded = Raw.encode_stream(Box::new(Cursor::new(data.clone())));
+
+        storage.blocking_write_stream(&path, BlockingDataStream::new(encoded))?;
+
+        let mut read = Vec::new();
+
+        storage.blocking_read_stream(&path)?.read_to_end(&mut read)?;
+
+        assert_eq!(read, data);
+        assert_eq!(&*storage.blocking_read(&path)?, data);
+
+        let other = super::path_for(Path::new("blob/1/other"), &Raw);
+        let result = storage.blocking_write_stream(&other, BlockingDataStream::new(Cursor::new(data)));
+
+        assert!(result.is_err());
+        assert!(!storage.blocking_exists(&other)?);
+
+        Ok(())
+    }
+}
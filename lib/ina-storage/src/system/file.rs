@@ -14,22 +14,80 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
+use std::ffi::{OsStr, OsString};
 use std::ops::{Deref, DerefMut};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
-use super::{DataReader, DataSystem, DataWriter};
+use super::{BlockingDataStream, DataReader, DataStream, DataSystem, DataWriter};
 
 /// The global instance of the file system.
 static INSTANCE: RwLock<FileSystem> = RwLock::const_new(FileSystem);
 
+/// The extension given to the temporary files that are created while writing.
+const TEMPORARY_EXTENSION: &str = "tmp";
+
 /// A file-based data storage system.
+///
+/// Writes are performed atomically by first writing into a temporary sibling file, which is then renamed over the
+/// destination file. This ensures that an interrupted write never leaves behind partially written data.
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FileSystem;
 
+impl FileSystem {
+    /// Removes any temporary files left behind by interrupted writes within the given directory, returning the number
+    /// of removed files.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory could not be read or a file could not be removed.
+    pub fn blocking_clean(&mut self, directory: &Path) -> Result<usize, std::io::Error> {
+        if !self.blocking_exists(directory)? || !std::fs::metadata(directory)?.is_dir() {
+            return Ok(0);
+        }
+
+        let paths = self::blocking_walk(directory)?;
+        let mut count = 0;
+
+        for path in paths.iter().filter(|path| self::is_temporary(path)) {
+            std::fs::remove_file(path)?;
+            trace!(?path, "removed temporary file");
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Removes any temporary files left behind by interrupted writes within the given directory, returning the number
+    /// of removed files.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory could not be read or a file could not be removed.
+    pub async fn clean(&mut self, directory: &Path) -> Result<usize, std::io::Error> {
+        if !self.exists(directory).await? || !tokio::fs::metadata(directory).await?.is_dir() {
+            return Ok(0);
+        }
+
+        let paths = self::walk(directory).await?;
+        let mut count = 0;
+
+        for path in paths.iter().filter(|path| self::is_temporary(path)) {
+            tokio::fs::remove_file(path).await?;
+            trace!(?path, "removed temporary file");
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
 impl DataSystem for FileSystem {
     fn blocking_get() -> impl Deref<Target = Self> {
         INSTANCE.blocking_read()
@@ -67,6 +125,16 @@ impl DataReader for FileSystem {
         Ok(tokio::fs::metadata(path).await.inspect(|_| trace!("accessed file metadata"))?.len())
     }
 
+    fn blocking_modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        // Modification times are not available on every platform, in which case they are simply not tracked.
+        Ok(std::fs::metadata(path).inspect(|_| trace!("accessed file metadata"))?.modified().ok())
+    }
+
+    async fn modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        // Modification times are not available on every platform, in which case they are simply not tracked.
+        Ok(tokio::fs::metadata(path).await.inspect(|_| trace!("accessed file metadata"))?.modified().ok())
+    }
+
     fn blocking_read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
         let mut file = std::fs::File::open(path)?;
         trace!("opened file handle");
@@ -119,81 +187,206 @@ impl DataReader for FileSystem {
 
         Ok(buffer.into())
     }
+
+    fn blocking_read_stream(&self, path: &Path) -> Result<BlockingDataStream, Self::Error> {
+        let file = std::fs::File::open(path)?;
+        trace!("opened file handle");
+
+        // The shared lock is held until the stream is dropped, which closes the file.
+        file.lock_shared()?;
+        trace!("locked file");
+
+        Ok(BlockingDataStream::new(file))
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<DataStream, Self::Error> {
+        let file = tokio::fs::File::open(path).await?;
+        trace!("opened file handle");
+
+        // Currently, `lock` is not implemented in `tokio` due to the MSRV requirement.
+        // Because of this, we need to juggle between the stdlib and tokio file types.
+        let file = file.into_std().await;
+        // The shared lock is held until the stream is dropped, which closes the file.
+        file.lock_shared()?;
+        trace!("locked file");
+
+        Ok(DataStream::new(tokio::fs::File::from_std(file)))
+    }
+
+    fn blocking_list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        if !self.blocking_exists(prefix)? {
+            return Ok(Box::default());
+        }
+        if !std::fs::metadata(prefix)?.is_dir() {
+            return Ok(Box::new([prefix.into()]));
+        }
+
+        let mut paths: Vec<_> = self::blocking_walk(prefix)?
+            .into_iter()
+            .filter(|path| !self::is_temporary(path) && !crate::journal::is_journal(path))
+            .map(PathBuf::into_boxed_path)
+            .collect();
+
+        paths.sort_unstable();
+
+        Ok(paths.into_boxed_slice())
+    }
+
+    async fn list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        if !self.exists(prefix).await? {
+            return Ok(Box::default());
+        }
+        if !tokio::fs::metadata(prefix).await?.is_dir() {
+            return Ok(Box::new([prefix.into()]));
+        }
+
+        let mut paths: Vec<_> = self::walk(prefix)
+            .await?
+            .into_iter()
+            .filter(|path| !self::is_temporary(path) && !crate::journal::is_journal(path))
+            .map(PathBuf::into_boxed_path)
+            .collect();
+
+        paths.sort_unstable();
+
+        Ok(paths.into_boxed_slice())
+    }
 }
 
 impl DataWriter for FileSystem {
     type Error = std::io::Error;
 
-    fn blocking_write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+    fn blocking_write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
         if let Some(path) = path.parent() {
             std::fs::create_dir_all(path)?;
 
             trace!("created parent directories");
         }
 
-        // We have to use `options` here because `File::create` will truncate before the lock is acquired.
-        let mut file = if self.blocking_exists(path)? {
-            std::fs::File::options().write(true).open(path)?
-        } else {
-            std::fs::File::options().create_new(true).write(true).open(path)?
-        };
-        trace!("opened file handle");
+        let temporary_path = self::temporary_path(path);
+        let mut file = std::fs::File::create(&temporary_path)?;
+        trace!("opened temporary file handle");
 
-        file.lock()?;
-        trace!("locked file");
+        if let Err(error) = std::io::Write::write_all(&mut file, bytes).and_then(|()| file.sync_all()) {
+            drop(file);
 
-        // Try to resize to match the length of the byte array, truncating to zero if the value is too large.
-        // Realistically, since 128-bit systems are not commonplace, this is unnecessary and will always succeed.
-        file.set_len(bytes.len().try_into().unwrap_or(0))?;
-        trace!("resized file");
+            // The temporary file is incomplete, so there's no reason to keep it around.
+            if let Err(error) = std::fs::remove_file(&temporary_path) {
+                warn!(%error, "failed to remove temporary file");
+            }
 
-        std::io::Write::write_all(&mut file, bytes)?;
-        trace!("wrote file");
+            return Err(error);
+        }
+        trace!("wrote temporary file");
 
-        file.unlock()?;
-        trace!("unlocked file");
+        drop(file);
 
-        Ok(())
+        self.blocking_rename(&temporary_path, path)
     }
 
-    async fn write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
         if let Some(path) = path.parent() {
             tokio::fs::create_dir_all(path).await?;
 
             trace!("created parent directories");
         }
 
-        // We have to use `options` here because `File::create` will truncate before the lock is acquired.
-        let file = if self.exists(path).await? {
-            tokio::fs::File::options().write(true).open(path).await?
-        } else {
-            tokio::fs::File::options().create_new(true).write(true).open(path).await?
+        let temporary_path = self::temporary_path(path);
+        let mut file = tokio::fs::File::create(&temporary_path).await?;
+        trace!("opened temporary file handle");
+
+        let result = async {
+            tokio::io::AsyncWriteExt::write_all(&mut file, bytes).await?;
+
+            file.sync_all().await
         };
-        trace!("opened file handle");
 
-        // Currently, `lock` is not implemented in `tokio` due to the MSRV requirement.
-        // Because of this, we need to juggle between the stdlib and tokio file types.
-        let file = file.into_std().await;
-        file.lock()?;
-        trace!("locked file");
+        if let Err(error) = result.await {
+            drop(file);
 
-        let mut file = tokio::fs::File::from_std(file);
+            // The temporary file is incomplete, so there's no reason to keep it around.
+            if let Err(error) = tokio::fs::remove_file(&temporary_path).await {
+                warn!(%error, "failed to remove temporary file");
+            }
 
-        // Try to resize to match the length of the byte array, truncating to zero if the value is too large.
-        // Realistically, since 128-bit systems are not commonplace, this is unnecessary and will always succeed.
-        file.set_len(bytes.len().try_into().unwrap_or(0)).await?;
-        trace!("resized file");
+            return Err(error);
+        }
+        trace!("wrote temporary file");
 
-        tokio::io::AsyncWriteExt::write_all(&mut file, bytes).await?;
-        trace!("wrote file");
+        drop(file);
 
-        file.into_std().await.unlock()?;
-        trace!("unlocked file");
+        self.rename(&temporary_path, path).await
+    }
+
+    fn blocking_write_stream(&self, path: &Path, mut stream: BlockingDataStream) -> Result<u64, Self::Error> {
+        if let Some(path) = path.parent() {
+            std::fs::create_dir_all(path)?;
+
+            trace!("created parent directories");
+        }
+
+        let temporary_path = self::temporary_path(path);
+        let mut file = std::fs::File::create(&temporary_path)?;
+        trace!("opened temporary file handle");
+
+        let size = match std::io::copy(&mut stream, &mut file).and_then(|size| file.sync_all().map(|()| size)) {
+            Ok(size) => size,
+            Err(error) => {
+                drop(file);
 
-        Ok(())
+                // The temporary file is incomplete, so there's no reason to keep it around.
+                if let Err(error) = std::fs::remove_file(&temporary_path) {
+                    warn!(%error, "failed to remove temporary file");
+                }
+
+                return Err(error);
+            }
+        };
+        trace!(size, "streamed temporary file");
+
+        drop(file);
+
+        self.blocking_rename(&temporary_path, path).map(|()| size)
     }
 
-    fn blocking_rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+    async fn write_stream(&self, path: &Path, mut stream: DataStream) -> Result<u64, Self::Error> {
+        if let Some(path) = path.parent() {
+            tokio::fs::create_dir_all(path).await?;
+
+            trace!("created parent directories");
+        }
+
+        let temporary_path = self::temporary_path(path);
+        let mut file = tokio::fs::File::create(&temporary_path).await?;
+        trace!("opened temporary file handle");
+
+        let result = async {
+            let size = tokio::io::copy(&mut stream, &mut file).await?;
+
+            file.sync_all().await.map(|()| size)
+        };
+
+        let size = match result.await {
+            Ok(size) => size,
+            Err(error) => {
+                drop(file);
+
+                // The temporary file is incomplete, so there's no reason to keep it around.
+                if let Err(error) = tokio::fs::remove_file(&temporary_path).await {
+                    warn!(%error, "failed to remove temporary file");
+                }
+
+                return Err(error);
+            }
+        };
+        trace!(size, "streamed temporary file");
+
+        drop(file);
+
+        self.rename(&temporary_path, path).await.map(|()| size)
+    }
+
+    fn blocking_rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
         if let Some(path) = into.parent() {
             std::fs::create_dir_all(path)?;
 
@@ -212,10 +405,13 @@ impl DataWriter for FileSystem {
             trace!("unlocked file");
         }
 
-        std::fs::rename(from, into).inspect(|()| trace!("renamed file"))
+        std::fs::rename(from, into)?;
+        trace!("renamed file");
+
+        self::blocking_sync_parents(from, into)
     }
 
-    async fn rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+    async fn rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
         if let Some(path) = into.parent() {
             tokio::fs::create_dir_all(path).await?;
 
@@ -236,15 +432,18 @@ impl DataWriter for FileSystem {
             trace!("unlocked file");
         }
 
-        tokio::fs::rename(from, into).await.inspect(|()| trace!("renamed file"))
+        tokio::fs::rename(from, into).await?;
+        trace!("renamed file");
+
+        self::sync_parents(from, into).await
     }
 
-    fn blocking_delete(&mut self, path: &Path) -> Result<(), Self::Error> {
+    fn blocking_delete(&self, path: &Path) -> Result<(), Self::Error> {
         if std::fs::metadata(path)?.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) }
             .inspect(|()| trace!("removed file"))
     }
 
-    async fn delete(&mut self, path: &Path) -> Result<(), Self::Error> {
+    async fn delete(&self, path: &Path) -> Result<(), Self::Error> {
         if tokio::fs::metadata(path).await?.is_dir() {
             tokio::fs::remove_dir_all(path).await
         } else {
@@ -253,3 +452,124 @@ impl DataWriter for FileSystem {
         .inspect(|()| trace!("removed file"))
     }
 }
+
+/// Returns the path of the temporary sibling file used while writing to the given path.
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(".");
+    file_name.push(TEMPORARY_EXTENSION);
+
+    path.with_file_name(file_name)
+}
+
+/// Returns whether the given path refers to a temporary file created while writing.
+pub(crate) fn is_temporary(path: &Path) -> bool {
+    path.file_name().and_then(OsStr::to_str).is_some_and(|name| {
+        name.strip_prefix('.')
+            .and_then(|name| name.strip_suffix(TEMPORARY_EXTENSION))
+            .is_some_and(|name| name.ends_with('.'))
+    })
+}
+
+/// Returns the paths of all files contained within the given directory and its sub-directories.
+///
+/// # Errors
+///
+/// This function will return an error if a directory could not be read.
+fn blocking_walk(directory: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut paths = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                directories.push(entry.path());
+            } else {
+                paths.push(entry.path());
+            }
+        }
+        trace!(?directory, "read directory");
+    }
+
+    Ok(paths)
+}
+
+/// Returns the paths of all files contained within the given directory and its sub-directories.
+///
+/// # Errors
+///
+/// This function will return an error if a directory could not be read.
+async fn walk(directory: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut paths = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let mut entries = tokio::fs::read_dir(&directory).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                directories.push(entry.path());
+            } else {
+                paths.push(entry.path());
+            }
+        }
+        trace!(?directory, "read directory");
+    }
+
+    Ok(paths)
+}
+
+/// Returns the directory containing the given path, defaulting to the current directory.
+fn parent_directory(path: &Path) -> &Path {
+    path.parent().filter(|path| !path.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."))
+}
+
+/// Flushes the parent directories of the given renamed paths to disk, ensuring that the rename itself persists.
+///
+/// # Errors
+///
+/// This function will return an error if a directory could not be opened or synchronized.
+fn blocking_sync_parents(from: &Path, into: &Path) -> Result<(), std::io::Error> {
+    // Directories cannot be opened as files on every platform, but on Unix this is required for durability.
+    if cfg!(not(unix)) {
+        return Ok(());
+    }
+
+    let (from_parent, into_parent) = (self::parent_directory(from), self::parent_directory(into));
+
+    std::fs::File::open(into_parent)?.sync_all()?;
+
+    if from_parent != into_parent {
+        std::fs::File::open(from_parent)?.sync_all()?;
+    }
+    trace!("synchronized parent directories");
+
+    Ok(())
+}
+
+/// Flushes the parent directories of the given renamed paths to disk, ensuring that the rename itself persists.
+///
+/// # Errors
+///
+/// This function will return an error if a directory could not be opened or synchronized.
+async fn sync_parents(from: &Path, into: &Path) -> Result<(), std::io::Error> {
+    // Directories cannot be opened as files on every platform, but on Unix this is required for durability.
+    if cfg!(not(unix)) {
+        return Ok(());
+    }
+
+    let (from_parent, into_parent) = (self::parent_directory(from), self::parent_directory(into));
+
+    tokio::fs::File::open(into_parent).await?.sync_all().await?;
+
+    if from_parent != into_parent {
+        tokio::fs::File::open(from_parent).await?.sync_all().await?;
+    }
+    trace!("synchronized parent directories");
+
+    Ok(())
+}
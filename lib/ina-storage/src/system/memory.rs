@@ -15,14 +15,17 @@
 // <https://www.gnu.org/licenses/>.
 
 use std::collections::HashMap;
+use std::io::Read;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
-use std::sync::{Arc, LazyLock};
+use std::sync::{Arc, LazyLock, PoisonError, RwLockReadGuard, RwLockWriteGuard};
+use std::time::SystemTime;
 
+use tokio::io::AsyncReadExt;
 use tokio::sync::RwLock;
 use tracing::trace;
 
-use super::{DataReader, DataSystem, DataWriter};
+use super::{BlockingDataStream, DataReader, DataStream, DataSystem, DataWriter};
 
 /// The global instance of the memory system.
 static INSTANCE: LazyLock<RwLock<MemorySystem>> = LazyLock::new(RwLock::default);
@@ -30,6 +33,9 @@ static INSTANCE: LazyLock<RwLock<MemorySystem>> = LazyLock::new(RwLock::default)
 /// An error that can be returned by the memory system.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     /// The path is missing from the system.
     #[error("missing path '{0}'")]
     MissingPath(Box<Path>),
@@ -38,10 +44,23 @@ pub enum Error {
 /// A memory-based data storage system.
 ///
 /// This should only ever be used for testing purposes.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default)]
 pub struct MemorySystem {
     /// The inner hash map.
-    inner: HashMap<Box<Path>, Arc<[u8]>>,
+    inner: std::sync::RwLock<HashMap<Box<Path>, Arc<[u8]>>>,
+}
+
+impl MemorySystem {
+    /// Returns a reference to the inner hash map.
+    fn inner(&self) -> RwLockReadGuard<'_, HashMap<Box<Path>, Arc<[u8]>>> {
+        // The map remains usable even if a previous holder panicked, as every change is a single insertion or removal.
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a mutable reference to the inner hash map.
+    fn inner_mut(&self) -> RwLockWriteGuard<'_, HashMap<Box<Path>, Arc<[u8]>>> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl DataSystem for MemorySystem {
@@ -66,7 +85,7 @@ impl DataReader for MemorySystem {
     type Error = Error;
 
     fn blocking_exists(&self, path: &Path) -> Result<bool, Self::Error> {
-        Ok(self.inner.contains_key(path)).inspect(|_| trace!("checked for data"))
+        Ok(self.inner().contains_key(path)).inspect(|_| trace!("checked for data"))
     }
 
     async fn exists(&self, path: &Path) -> Result<bool, Self::Error> {
@@ -74,70 +93,122 @@ impl DataReader for MemorySystem {
     }
 
     fn blocking_size(&self, path: &Path) -> Result<u64, Self::Error> {
-        let Some(value) = self.inner.get(path) else {
+        let Some(size) = self.inner().get(path).map(|value| value.len()) else {
             return Err(Error::MissingPath(path.into()));
         };
 
-        Ok(value.len() as u64).inspect(|_| trace!("fetched data size"))
+        Ok(size as u64).inspect(|_| trace!("fetched data size"))
     }
 
     async fn size(&self, path: &Path) -> Result<u64, Self::Error> {
         self.blocking_size(path)
     }
 
+    fn blocking_modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        // Data can only be modified through this system, so there's no need to track modification times.
+        if self.inner().contains_key(path) { Ok(None) } else { Err(Error::MissingPath(path.into())) }
+    }
+
+    async fn modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        self.blocking_modified(path)
+    }
+
     fn blocking_read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
-        self.inner.get(path).cloned().inspect(|_| trace!("fetched data")).ok_or_else(|| Error::MissingPath(path.into()))
+        self.inner()
+            .get(path)
+            .cloned()
+            .inspect(|_| trace!("fetched data"))
+            .ok_or_else(|| Error::MissingPath(path.into()))
     }
 
     async fn read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
         self.blocking_read(path)
     }
+
+    fn blocking_read_stream(&self, path: &Path) -> Result<BlockingDataStream, Self::Error> {
+        self.blocking_read(path).map(BlockingDataStream::from_bytes)
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<DataStream, Self::Error> {
+        self.blocking_read(path).map(DataStream::from_bytes)
+    }
+
+    fn blocking_list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        let mut paths = self.inner().keys().filter(|path| path.starts_with(prefix)).cloned().collect::<Box<[_]>>();
+
+        paths.sort_unstable();
+
+        Ok(paths).inspect(|_| trace!("listed data"))
+    }
+
+    async fn list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        self.blocking_list(prefix)
+    }
 }
 
 impl DataWriter for MemorySystem {
     type Error = Error;
 
-    fn blocking_write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
-        self.inner.insert(path.into(), bytes.into());
+    fn blocking_write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner_mut().insert(path.into(), bytes.into());
 
         trace!("wrote data");
 
         Ok(())
     }
 
-    async fn write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
         self.blocking_write(path, bytes)
     }
 
-    fn blocking_rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
-        let Some(value) = self.inner.remove(from) else {
+    fn blocking_write_stream(&self, path: &Path, mut stream: BlockingDataStream) -> Result<u64, Self::Error> {
+        let mut buffer = Vec::new();
+
+        stream.read_to_end(&mut buffer)?;
+        self.blocking_write(path, &buffer)?;
+
+        Ok(buffer.len() as u64)
+    }
+
+    async fn write_stream(&self, path: &Path, mut stream: DataStream) -> Result<u64, Self::Error> {
+        let mut buffer = Vec::new();
+
+        stream.read_to_end(&mut buffer).await?;
+        self.blocking_write(path, &buffer)?;
+
+        Ok(buffer.len() as u64)
+    }
+
+    fn blocking_rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+        let mut inner = self.inner_mut();
+        let Some(value) = inner.remove(from) else {
             return Err(Error::MissingPath(from.into()));
         };
 
-        self.inner.insert(into.into(), value);
+        inner.insert(into.into(), value);
+
+        drop(inner);
 
         trace!("renamed data");
 
         Ok(())
     }
 
-    async fn rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+    async fn rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
         self.blocking_rename(from, into)
     }
 
-    fn blocking_delete(&mut self, path: &Path) -> Result<(), Self::Error> {
-        if !self.blocking_exists(path)? {
+    fn blocking_delete(&self, path: &Path) -> Result<(), Self::Error> {
+        if self.inner_mut().remove(path).is_none() {
             return Err(Error::MissingPath(path.into()));
         }
 
-        self.inner.remove(path);
-
         trace!("removed data");
 
         Ok(())
     }
 
-    async fn delete(&mut self, path: &Path) -> Result<(), Self::Error> {
+    async fn delete(&self, path: &Path) -> Result<(), Self::Error> {
         self.blocking_delete(path)
     }
 }
@@ -14,14 +14,23 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
+use std::fmt::Debug;
+use std::io::{Cursor, Read};
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+use tokio::io::{AsyncRead, ReadBuf};
 
 #[cfg(feature = "system-file")]
 pub use self::file::FileSystem;
 #[cfg(feature = "system-memory")]
 pub use self::memory::MemorySystem;
+#[cfg(feature = "system-sqlite")]
+pub use self::sqlite::SqliteSystem;
 
 /// A file-based system.
 #[cfg(feature = "system-file")]
@@ -29,6 +38,65 @@ pub mod file;
 /// A memory-based system. This should only ever be used for testing.
 #[cfg(feature = "system-memory")]
 pub mod memory;
+/// A `SQLite`-based system.
+#[cfg(feature = "system-sqlite")]
+pub mod sqlite;
+
+/// A stream of data bytes.
+pub struct DataStream(Pin<Box<dyn AsyncRead + Send + Sync>>);
+
+impl DataStream {
+    /// Creates a new [`DataStream`] that yields the bytes of the given reader.
+    pub fn new(reader: impl AsyncRead + Send + Sync + 'static) -> Self {
+        Self(Box::pin(reader))
+    }
+
+    /// Creates a new [`DataStream`] that yields the given bytes.
+    #[must_use]
+    pub fn from_bytes(bytes: Arc<[u8]>) -> Self {
+        Self::new(Cursor::new(bytes))
+    }
+}
+
+impl AsyncRead for DataStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        self.0.as_mut().poll_read(cx, buf)
+    }
+}
+
+impl Debug for DataStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataStream").finish_non_exhaustive()
+    }
+}
+
+/// A stream of data bytes that blocks the current thread while reading.
+pub struct BlockingDataStream(Box<dyn Read + Send>);
+
+impl BlockingDataStream {
+    /// Creates a new [`BlockingDataStream`] that yields the bytes of the given reader.
+    pub fn new(reader: impl Read + Send + 'static) -> Self {
+        Self(Box::new(reader))
+    }
+
+    /// Creates a new [`BlockingDataStream`] that yields the given bytes.
+    #[must_use]
+    pub fn from_bytes(bytes: Arc<[u8]>) -> Self {
+        Self::new(Cursor::new(bytes))
+    }
+}
+
+impl Read for BlockingDataStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Debug for BlockingDataStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingDataStream").finish_non_exhaustive()
+    }
+}
 
 /// A value that reads and writes generic data.
 pub trait DataSystem: DataReader + DataWriter + 'static {
@@ -102,6 +170,28 @@ pub trait DataReader {
     /// This function will return an error if the path cannot be read.
     fn size(&self, path: &Path) -> impl Future<Output = Result<u64, Self::Error>> + Send;
 
+    /// Returns the time at which the data at the given path was last modified, or [`None`] if this reader does not
+    /// track modification times.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    fn blocking_modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error>;
+
+    /// Returns the time at which the data at the given path was last modified, or [`None`] if this reader does not
+    /// track modification times.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    fn modified(&self, path: &Path) -> impl Future<Output = Result<Option<SystemTime>, Self::Error>> + Send;
+
     /// Reads bytes from the given path.
     ///
     /// This blocks the current thread.
@@ -121,9 +211,57 @@ pub trait DataReader {
     ///
     /// This function will return an error if the path cannot be read.
     fn read(&self, path: &Path) -> impl Future<Output = Result<Arc<[u8]>, Self::Error>> + Send;
+
+    /// Opens a stream of the bytes at the given path.
+    ///
+    /// This blocks the current thread, as does reading from the returned stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    fn blocking_read_stream(&self, path: &Path) -> Result<BlockingDataStream, Self::Error>;
+
+    /// Opens a stream of the bytes at the given path.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path cannot be read.
+    fn read_stream(&self, path: &Path) -> impl Future<Output = Result<DataStream, Self::Error>> + Send;
+
+    /// Returns the paths of all data stored under the given path prefix, in sorted order.
+    ///
+    /// The prefix is matched by whole path components, so `a/b` contains `a/b/c` but not `a/bc`. If nothing is stored
+    /// under the prefix, an empty list is returned.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix cannot be read.
+    fn blocking_list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error>;
+
+    /// Returns the paths of all data stored under the given path prefix, in sorted order.
+    ///
+    /// The prefix is matched by whole path components, so `a/b` contains `a/b/c` but not `a/bc`. If nothing is stored
+    /// under the prefix, an empty list is returned.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the prefix cannot be read.
+    fn list(&self, prefix: &Path) -> impl Future<Output = Result<Box<[Box<Path>]>, Self::Error>> + Send;
 }
 
 /// A value that writes data bytes.
+///
+/// Writes only borrow the value, so that writes of different paths may be made concurrently.
 pub trait DataWriter {
     /// The error that can be returned during writing.
     type Error: Into<anyhow::Error>;
@@ -139,14 +277,40 @@ pub trait DataWriter {
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn blocking_write(&mut self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error>;
+    fn blocking_write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error>;
 
     /// Writes bytes into the given path.
     ///
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn write(&mut self, path: &Path, bytes: &[u8]) -> impl Future<Output = Result<(), Self::Error>> + Send;
+    fn write(&self, path: &Path, bytes: &[u8]) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Writes the bytes of the given stream into the given path, returning the number of bytes written.
+    ///
+    /// The path is only replaced once the stream has been read in full, so a failing stream never leaves behind
+    /// partially written data.
+    ///
+    /// This blocks the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called in an asynchronous context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stream cannot be read or the path cannot be written to.
+    fn blocking_write_stream(&self, path: &Path, stream: BlockingDataStream) -> Result<u64, Self::Error>;
+
+    /// Writes the bytes of the given stream into the given path, returning the number of bytes written.
+    ///
+    /// The path is only replaced once the stream has been read in full, so a failing stream never leaves behind
+    /// partially written data.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stream cannot be read or the path cannot be written to.
+    fn write_stream(&self, path: &Path, stream: DataStream) -> impl Future<Output = Result<u64, Self::Error>> + Send;
 
     /// Renames the bytes to be associated with a new path.
     ///
@@ -159,14 +323,14 @@ pub trait DataWriter {
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn blocking_rename(&mut self, from: &Path, into: &Path) -> Result<(), Self::Error>;
+    fn blocking_rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error>;
 
     /// Renames the bytes to be associated with a new path.
     ///
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn rename(&mut self, from: &Path, into: &Path) -> impl Future<Output = Result<(), Self::Error>> + Send;
+    fn rename(&self, from: &Path, into: &Path) -> impl Future<Output = Result<(), Self::Error>> + Send;
 
     /// Deletes bytes from the given path.
     ///
@@ -179,12 +343,12 @@ pub trait DataWriter {
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn blocking_delete(&mut self, path: &Path) -> Result<(), Self::Error>;
+    fn blocking_delete(&self, path: &Path) -> Result<(), Self::Error>;
 
     /// Deletes bytes from the given path.
     ///
     /// # Errors
     ///
     /// This function will return an error if the path cannot be written to.
-    fn delete(&mut self, path: &Path) -> impl Future<Output = Result<(), Self::Error>> + Send;
+    fn delete(&self, path: &Path) -> impl Future<Output = Result<(), Self::Error>> + Send;
 }
@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::io::Read;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use tokio::io::AsyncReadExt;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use super::{BlockingDataStream, DataReader, DataStream, DataSystem, DataWriter};
+
+/// The name of the database file, which is created within the data directory.
+pub const DATABASE_FILE: &str = "data.sqlite3";
+
+/// The global instance of the `SQLite` system.
+static INSTANCE: RwLock<SqliteSystem> = RwLock::const_new(SqliteSystem { root: PathBuf::new(), connection: None });
+
+/// An error that can be returned by the `SQLite` system.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A `SQLite` error.
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    /// The database has not been opened.
+    #[error("the database has not been opened")]
+    Closed,
+    /// The path cannot be represented as a database key.
+    #[error("invalid path '{0}'")]
+    InvalidPath(Box<Path>),
+    /// The path is missing from the system.
+    #[error("missing path '{0}'")]
+    MissingPath(Box<Path>),
+}
+
+/// A `SQLite`-based data storage system.
+///
+/// All data is stored as blobs within a single database file, keyed by their path relative to the data directory.
+#[derive(Debug, Default)]
+pub struct SqliteSystem {
+    /// The directory that stored paths are relative to.
+    root: PathBuf,
+    /// The database connection.
+    connection: Option<Mutex<Connection>>,
+}
+
+impl SqliteSystem {
+    /// Opens the database within the given data directory, creating it if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the database could not be opened or initialized.
+    pub fn open(&mut self, directory: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(directory)?;
+
+        let connection = Connection::open(directory.join(DATABASE_FILE))?;
+        trace!("opened database connection");
+
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS data (
+                path TEXT PRIMARY KEY NOT NULL,
+                bytes BLOB NOT NULL,
+                modified INTEGER NOT NULL
+            ) WITHOUT ROWID;",
+        )?;
+        trace!("initialized database");
+
+        self.root = directory.to_path_buf();
+        self.connection = Some(Mutex::new(connection));
+
+        Ok(())
+    }
+
+    /// Returns the database connection.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the database has not been opened.
+    fn connection(&self) -> Result<MutexGuard<'_, Connection>, Error> {
+        let connection = self.connection.as_ref().ok_or(Error::Closed)?;
+
+        // The connection remains usable even if a previous holder panicked, as every statement is atomic.
+        Ok(connection.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Returns the database key of the given path.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path is not valid UTF-8.
+    fn key(&self, path: &Path) -> Result<String, Error> {
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        let components = path.components().map(|component| component.as_os_str().to_str()).collect::<Option<Vec<_>>>();
+
+        components.map(|components| components.join("/")).ok_or_else(|| Error::InvalidPath(path.into()))
+    }
+
+    /// Returns the path of the given database key.
+    fn path(&self, key: &str) -> Box<Path> {
+        self.root.join(key).into_boxed_path()
+    }
+}
+
+impl DataSystem for SqliteSystem {
+    fn blocking_get() -> impl Deref<Target = Self> {
+        INSTANCE.blocking_read()
+    }
+
+    async fn get() -> impl Deref<Target = Self> {
+        INSTANCE.read().await
+    }
+
+    fn blocking_get_mut() -> impl DerefMut<Target = Self> {
+        INSTANCE.blocking_write()
+    }
+
+    async fn get_mut() -> impl DerefMut<Target = Self> {
+        INSTANCE.write().await
+    }
+}
+
+// Queries are run directly within the asynchronous methods, as they complete quickly and the storage thread already
+// runs separately from the rest of the program.
+impl DataReader for SqliteSystem {
+    type Error = Error;
+
+    fn blocking_exists(&self, path: &Path) -> Result<bool, Self::Error> {
+        let key = self.key(path)?;
+        let exists =
+            self.connection()?
+                .query_row("SELECT EXISTS (SELECT 1 FROM data WHERE path = ?1)", [key], |row| row.get::<_, bool>(0))?;
+
+        Ok(exists).inspect(|_| trace!("checked for data"))
+    }
+
+    async fn exists(&self, path: &Path) -> Result<bool, Self::Error> {
+        self.blocking_exists(path)
+    }
+
+    fn blocking_size(&self, path: &Path) -> Result<u64, Self::Error> {
+        let key = self.key(path)?;
+        let size = self
+            .connection()?
+            .query_row("SELECT length(bytes) FROM data WHERE path = ?1", [key], |row| row.get::<_, u64>(0))
+            .optional()?;
+
+        size.inspect(|_| trace!("fetched data size")).ok_or_else(|| Error::MissingPath(path.into()))
+    }
+
+    async fn size(&self, path: &Path) -> Result<u64, Self::Error> {
+        self.blocking_size(path)
+    }
+
+    fn blocking_modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        let key = self.key(path)?;
+        let modified = self
+            .connection()?
+            .query_row("SELECT modified FROM data WHERE path = ?1", [key], |row| row.get::<_, i64>(0))
+            .optional()?;
+
+        let Some(modified) = modified else { return Err(Error::MissingPath(path.into())) };
+
+        trace!("fetched data modification time");
+
+        Ok(u64::try_from(modified)
+            .ok()
+            .and_then(|nanos| SystemTime::UNIX_EPOCH.checked_add(Duration::from_nanos(nanos))))
+    }
+
+    async fn modified(&self, path: &Path) -> Result<Option<SystemTime>, Self::Error> {
+        self.blocking_modified(path)
+    }
+
+    fn blocking_read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
+        let key = self.key(path)?;
+        let bytes = self
+            .connection()?
+            .query_row("SELECT bytes FROM data WHERE path = ?1", [key], |row| row.get::<_, Vec<u8>>(0))
+            .optional()?;
+
+        bytes.map(Into::into).inspect(|_| trace!("fetched data")).ok_or_else(|| Error::MissingPath(path.into()))
+    }
+
+    async fn read(&self, path: &Path) -> Result<Arc<[u8]>, Self::Error> {
+        self.blocking_read(path)
+    }
+
+    fn blocking_read_stream(&self, path: &Path) -> Result<BlockingDataStream, Self::Error> {
+        self.blocking_read(path).map(BlockingDataStream::from_bytes)
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<DataStream, Self::Error> {
+        self.blocking_read(path).map(DataStream::from_bytes)
+    }
+
+    fn blocking_list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        let key = self.key(prefix)?;
+        let connection = self.connection()?;
+
+        let get_path = |row: &Row<'_>| row.get::<_, String>(0);
+
+        let paths = if key.is_empty() {
+            let mut statement = connection.prepare("SELECT path FROM data")?;
+
+            statement.query_map([], get_path)?.collect::<Result<Vec<_>, _>>()?
+        } else {
+            // Keys below the prefix start with `{prefix}/`, and since `0` directly follows `/`, `{prefix}0` is the
+            // first key that is no longer below the prefix.
+            let mut statement = connection
+                .prepare("SELECT path FROM data WHERE path = ?1 OR (path >= ?1 || '/' AND path < ?1 || '0')")?;
+
+            statement.query_map([key], get_path)?.collect::<Result<Vec<_>, _>>()?
+        };
+
+        drop(connection);
+
+        let mut paths = paths.iter().map(|key| self.path(key)).collect::<Box<[_]>>();
+
+        paths.sort_unstable();
+
+        Ok(paths).inspect(|_| trace!("listed data"))
+    }
+
+    async fn list(&self, prefix: &Path) -> Result<Box<[Box<Path>]>, Self::Error> {
+        self.blocking_list(prefix)
+    }
+}
+
+impl DataWriter for SqliteSystem {
+    type Error = Error;
+
+    fn blocking_write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+        let key = self.key(path)?;
+        // This will not overflow until the year 2262.
+        let modified = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |time| time.as_nanos());
+        let modified = i64::try_from(modified).unwrap_or(i64::MAX);
+
+        self.connection()?.execute(
+            "INSERT INTO data (path, bytes, modified) VALUES (?1, ?2, ?3)
+                ON CONFLICT (path) DO UPDATE SET bytes = excluded.bytes, modified = excluded.modified",
+            params![key, bytes, modified],
+        )?;
+
+        trace!("wrote data");
+
+        Ok(())
+    }
+
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.blocking_write(path, bytes)
+    }
+
+    // Blobs are written within a single statement, so the stream is buffered in full before it is stored.
+    fn blocking_write_stream(&self, path: &Path, mut stream: BlockingDataStream) -> Result<u64, Self::Error> {
+        let mut buffer = Vec::new();
+
+        stream.read_to_end(&mut buffer)?;
+        self.blocking_write(path, &buffer)?;
+
+        Ok(buffer.len() as u64)
+    }
+
+    async fn write_stream(&self, path: &Path, mut stream: DataStream) -> Result<u64, Self::Error> {
+        let mut buffer = Vec::new();
+
+        stream.read_to_end(&mut buffer).await?;
+        self.blocking_write(path, &buffer)?;
+
+        Ok(buffer.len() as u64)
+    }
+
+    fn blocking_rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+        let (from_key, into_key) = (self.key(from)?, self.key(into)?);
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction()?;
+
+        transaction.execute("DELETE FROM data WHERE path = ?2 AND ?1 != ?2", [&from_key, &into_key])?;
+
+        if transaction.execute("UPDATE data SET path = ?2 WHERE path = ?1", [&from_key, &into_key])? == 0 {
+            return Err(Error::MissingPath(from.into()));
+        }
+
+        transaction.commit()?;
+
+        drop(connection);
+
+        trace!("renamed data");
+
+        Ok(())
+    }
+
+    async fn rename(&self, from: &Path, into: &Path) -> Result<(), Self::Error> {
+        self.blocking_rename(from, into)
+    }
+
+    fn blocking_delete(&self, path: &Path) -> Result<(), Self::Error> {
+        let key = self.key(path)?;
+        // Like directories within the file system, deleting a prefix deletes everything stored under it.
+        let count = self
+            .connection()?
+            .execute("DELETE FROM data WHERE path = ?1 OR (path >= ?1 || '/' AND path < ?1 || '0')", [key])?;
+
+        if count == 0 {
+            return Err(Error::MissingPath(path.into()));
+        }
+
+        trace!(count, "removed data");
+
+        Ok(())
+    }
+
+    async fn delete(&self, path: &Path) -> Result<(), Self::Error> {
+        self.blocking_delete(path)
+    }
+}
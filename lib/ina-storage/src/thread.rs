@@ -14,29 +14,83 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
+use std::borrow::Cow;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
 
+use ina_threading::Handle;
+use ina_threading::metrics::Metrics;
 use ina_threading::statics::Static;
-use ina_threading::threads::invoker::{Stateful, StatefulInvoker};
+use ina_threading::supervisor::{Restart, Supervisor};
+use ina_threading::threads::invoker::{CallError, Reply, Reservation, Stateful, StatefulInvoker};
 use tokio::sync::RwLock;
+use tokio::sync::broadcast::Receiver;
+use tracing::{debug, warn};
 
-use crate::format::{DataDecode, DataEncode};
+use crate::access::{Access, Locks};
+#[cfg(feature = "snapshot")]
+use crate::archive::Manifest;
+use crate::batch::{Batch, Operation};
+#[cfg(feature = "blob")]
+use crate::blob::BlobId;
+#[cfg(feature = "caching")]
+use crate::cache::CacheStats;
+use crate::expiry::Sweeper;
+use crate::index::INDEX_DIRECTORY;
+use crate::journal::{Journal, Pending};
+use crate::quota::{GroupUsage, Usage};
 use crate::settings::Settings;
+#[cfg(feature = "snapshot")]
+use crate::snapshot::Snapshotter;
 use crate::stored::Stored;
-use crate::system::{DataReader, DataWriter};
-use crate::{Result, Storage};
+#[cfg(feature = "system-file")]
+use crate::system::FileSystem;
+#[cfg(feature = "system-sqlite")]
+use crate::system::SqliteSystem;
+use crate::system::{DataReader, DataStream, DataSystem, DataWriter};
+use crate::watch::{Event, Watchers};
+use crate::{Error, Result, Storage, System};
 
 /// The storage thread's handle.
 static THREAD: StorageThread = StorageThread::new();
+/// Restarts the storage thread if handling a request panics, so that other requests continue to be handled.
+const SUPERVISOR: Supervisor = Supervisor::new(Restart::OnPanic);
+/// The thread that periodically deletes expired data.
+static SWEEPER: Mutex<Option<Sweeper>> = Mutex::new(None);
+/// The duration to wait for the storage thread to respond before a request fails.
+static TIMEOUT: Mutex<Duration> = Mutex::new(Duration::MAX);
+/// The journal of requests that modify stored data, if the configured storage system persists data.
+static JOURNAL: Mutex<Option<Arc<Journal>>> = Mutex::new(None);
+/// The thread that periodically takes snapshots of all stored data.
+#[cfg(feature = "snapshot")]
+static SNAPSHOTTER: Mutex<Option<Snapshotter>> = Mutex::new(None);
 
 /// The storage thread's type.
 pub type StorageThread = Static<StorageThreadInner>;
 /// The storage thread's inner type.
-pub type StorageThreadInner = StatefulInvoker<RwLock<Storage>, Request, Response>;
+pub type StorageThreadInner = StatefulInvoker<State, Envelope, Response>;
+
+/// The state of the storage thread.
+#[derive(Debug)]
+pub struct State {
+    /// The managed storage instance.
+    storage: RwLock<Storage>,
+    /// The locks that order access to stored paths.
+    locks: Locks,
+}
+
+/// A request sent to the storage thread alongside its position within the journal.
+#[derive(Debug)]
+pub struct Envelope {
+    /// The request.
+    request: Request,
+    /// The sequence number of the request within the journal, if it was recorded.
+    sequence: Option<u64>,
+}
 
 /// A request sent to the storage thread.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Debug)]
 pub enum Request {
     /// Returns whether a path exists.
     Exists(Box<Path>),
@@ -44,12 +98,106 @@ pub enum Request {
     Size(Box<Path>),
     /// Returns the data at the given path.
     Read(Box<Path>),
+    /// Returns a stream of the data at the given path.
+    ReadStream(Box<Path>),
+    /// Returns the paths of all data stored under the given path prefix.
+    List(Box<Path>),
     /// Writes bytes into the given path.
     Write(Box<Path>, Arc<[u8]>),
+    /// Writes the bytes of a stream into the given path.
+    WriteStream(Box<Path>, DataStream),
     /// Renames the bytes to be associated with a new path.
     Rename(Box<Path>, Box<Path>),
     /// Deletes the data at the given path.
     Delete(Box<Path>),
+    /// Applies the given operations all-or-nothing.
+    Batch(Box<[Operation]>),
+    /// Deletes the data at the given path if it has expired.
+    Expire(Box<Path>),
+    /// Removes the entries of values that no longer exist from the given secondary index.
+    PruneIndex(Box<Path>),
+    /// Re-encrypts the data at the given path using the current encryption key.
+    #[cfg(feature = "format-encryption")]
+    Reencrypt(Box<Path>),
+    /// Subscribes to changes of data stored under the given path prefix.
+    Subscribe(Box<Path>),
+    /// Returns the usage of the given group of stored data.
+    Usage(Box<str>),
+    /// Returns the paths of all values with the given key within the given secondary index.
+    FindInIndex(Box<Path>, Box<str>),
+    /// Stores the given bytes as a blob.
+    #[cfg(feature = "blob")]
+    StoreBlob(Arc<[u8]>),
+    /// Returns the number of stored values that reference the given blob.
+    #[cfg(feature = "blob")]
+    BlobReferences(BlobId),
+    /// Deletes all unreferenced blobs that were not stored within the given grace period.
+    #[cfg(feature = "blob")]
+    CollectBlobs(Duration),
+    /// Writes a snapshot of all stored data.
+    #[cfg(feature = "snapshot")]
+    Snapshot,
+    /// Replaces all stored data with the contents of the given snapshot, or of the most recent snapshot.
+    #[cfg(feature = "snapshot")]
+    Restore(Option<Box<Path>>),
+    /// Returns the statistics of the storage cache.
+    #[cfg(feature = "caching")]
+    Stats,
+}
+
+impl Request {
+    /// Returns the operations that the request applies when it is replayed from the journal, or [`None`] if the
+    /// request is not journaled.
+    ///
+    /// Streamed writes are not journaled, as their bytes are not available until the storage thread reads them.
+    fn operations(&self) -> Option<Cow<'_, [Operation]>> {
+        let operation = match self {
+            Self::Write(path, bytes) => Operation::Write(path.clone(), Arc::clone(bytes)),
+            Self::Rename(from, into) => Operation::Rename(from.clone(), into.clone()),
+            Self::Delete(path) => Operation::Delete(path.clone()),
+            Self::Batch(operations) => return Some(Cow::Borrowed(operations)),
+            #[cfg(feature = "blob")]
+            Self::StoreBlob(bytes) => Operation::Write(BlobId::of(bytes).path(), Arc::clone(bytes)),
+            _ => return None,
+        };
+
+        Some(Cow::Owned(vec![operation]))
+    }
+
+    /// Returns the stored data that the request accesses while it is handled.
+    fn access(&self) -> Access {
+        match self {
+            Self::Exists(path) | Self::Size(path) | Self::Read(path) | Self::ReadStream(path) => Access::read(path),
+            Self::Write(path, _)
+            | Self::WriteStream(path, _)
+            | Self::Delete(path)
+            | Self::Expire(path)
+            | Self::PruneIndex(path) => Access::write(path),
+            Self::Rename(from, into) => Access::Write(Box::new([from.clone(), into.clone()])),
+            Self::Batch(operations) => {
+                Access::Write(operations.iter().flat_map(Operation::paths).map(Box::from).collect())
+            }
+            #[cfg(feature = "blob")]
+            Self::StoreBlob(bytes) => Access::write(&BlobId::of(bytes).path()),
+            #[cfg(feature = "format-encryption")]
+            Self::Reencrypt(path) => Access::write(path),
+            Self::FindInIndex(index, _) => Access::read(index),
+            Self::Usage(group) => Access::read(&Usage::record_path(group)),
+            Self::Subscribe(_) => Access::Nothing,
+            Self::List(_) => Access::ReadAll,
+            #[cfg(feature = "blob")]
+            Self::BlobReferences(_) => Access::ReadAll,
+            #[cfg(feature = "caching")]
+            Self::Stats => Access::Nothing,
+            // Snapshots only pause modifications, so that reads may still be handled while one is written.
+            #[cfg(feature = "snapshot")]
+            Self::Snapshot => Access::ReadAll,
+            #[cfg(feature = "blob")]
+            Self::CollectBlobs(_) => Access::WriteAll,
+            #[cfg(feature = "snapshot")]
+            Self::Restore(_) => Access::WriteAll,
+        }
+    }
 }
 
 /// A response sent from the storage thread.
@@ -65,48 +213,254 @@ pub enum Response {
     Size(u64),
     /// The bytes of some data.
     Read(Arc<[u8]>),
+    /// A stream of the bytes of some data.
+    Stream(DataStream),
+    /// The paths of some stored data.
+    List(Box<[Box<Path>]>),
+    /// Whether expired data was deleted.
+    Expired(bool),
+    /// The number of stale index entries that were removed.
+    Pruned(usize),
+    /// Whether data was re-encrypted.
+    #[cfg(feature = "format-encryption")]
+    Reencrypted(bool),
+    /// A receiver of changes to some stored data.
+    Subscribe(Receiver<Event>),
+    /// The usage of a group of stored data.
+    Usage(GroupUsage),
+    /// The identifier of a stored blob.
+    #[cfg(feature = "blob")]
+    Blob(BlobId),
+    /// The number of stored values that reference a blob.
+    #[cfg(feature = "blob")]
+    References(usize),
+    /// The number of unreferenced blobs that were deleted.
+    #[cfg(feature = "blob")]
+    Collected(usize),
+    /// The path of a written snapshot.
+    #[cfg(feature = "snapshot")]
+    Snapshot(Box<Path>),
+    /// The manifest of a restored snapshot.
+    #[cfg(feature = "snapshot")]
+    Manifest(Manifest),
+    /// The statistics of the storage cache.
+    #[cfg(feature = "caching")]
+    Stats(CacheStats),
 }
 
-/// Creates a new localization thread.
+/// Creates a new storage thread that manages the given storage instance.
+///
+/// Requests are admitted in the order that they are received and handled concurrently, each waiting until every earlier
+/// request that accesses the same stored data has been handled.
+///
+/// If handling a request panics, the thread is restarted and the request's caller receives an error. The restarted
+/// thread manages a new storage instance, so that no cached data or usage that the panic left inconsistent is kept,
+/// although existing subscriptions to changes of stored data remain open.
 ///
 /// # Errors
 ///
 /// This function will return an error if the thread fails to spawn.
-fn create(settings: Settings) -> Result<StorageThreadInner> {
-    let capacity = settings.queue_capacity;
-    let storage = RwLock::new(Storage::new(settings));
+fn create(storage: Storage) -> Result<StorageThreadInner> {
+    let capacity = storage.settings.queue_capacity;
+    let settings = storage.settings.clone();
+    let watchers = Arc::clone(&storage.watchers);
+    #[cfg_attr(not(feature = "watch-external"), expect(unused_mut, reason = "only mutated when watching edits"))]
+    let mut storage = storage;
+
+    #[cfg(feature = "watch-external")]
+    storage.watch_external()?;
+
+    let initial = Mutex::new(Some(storage));
+    let state = move || {
+        let storage = initial.lock().unwrap_or_else(PoisonError::into_inner).take();
+        let storage = storage.unwrap_or_else(|| self::recreate(settings.clone(), Arc::clone(&watchers)));
+
+        State { storage: RwLock::new(storage), locks: Locks::default() }
+    };
+
+    Ok(StatefulInvoker::spawn_concurrent_supervised_with_runtime("storage", capacity, state, SUPERVISOR, self::run)?)
+}
+
+/// Creates a new storage instance for a restarted storage thread, which notifies the given subscribers of changes.
+fn recreate(settings: Settings, watchers: Arc<Watchers>) -> Storage {
+    let mut storage = Storage::new(settings);
+
+    storage.watchers = watchers;
+
+    #[cfg(feature = "watch-external")]
+    if let Err(error) = storage.watch_external() {
+        warn!(%error, "failed to watch storage directory for external edits");
+    }
+
+    debug!("recreated storage instance for restarted thread");
 
-    Ok(StatefulInvoker::spawn_with_runtime("storage", capacity, storage, self::run)?)
+    storage
+}
+
+/// Opens the journal of the configured storage system, returning it alongside the requests that must be replayed.
+///
+/// Returns [`None`] if the storage system does not persist data between runs, as there is nothing to replay.
+///
+/// # Errors
+///
+/// This function will return an error if the journal could not be opened.
+fn open_journal(settings: &Settings) -> Result<Option<(Journal, Pending)>> {
+    #[cfg(feature = "system-memory")]
+    if settings.system == System::Memory {
+        return Ok(None);
+    }
+
+    Ok(Some(Journal::open(&settings.directory)?))
+}
+
+/// Prepares the configured storage system for use.
+///
+/// For the file system, this removes any temporary files left behind by writes that were interrupted during a previous
+/// run. For the `SQLite` system, this opens the database.
+///
+/// # Errors
+///
+/// This function will return an error if the storage system could not be prepared.
+fn blocking_prepare(settings: &Settings) -> Result<()> {
+    match settings.system {
+        #[cfg(feature = "system-file")]
+        System::File => {
+            let count = FileSystem::blocking_get_mut().blocking_clean(&settings.directory)?;
+
+            debug!(count, "removed leftover temporary files");
+        }
+        #[cfg(feature = "system-memory")]
+        System::Memory => {}
+        #[cfg(feature = "system-sqlite")]
+        System::Sqlite => {
+            SqliteSystem::blocking_get_mut().open(&settings.directory)?;
+
+            debug!("opened database");
+        }
+    }
+
+    Ok(())
+}
+
+/// Prepares the configured storage system for use.
+///
+/// For the file system, this removes any temporary files left behind by writes that were interrupted during a previous
+/// run. For the `SQLite` system, this opens the database.
+///
+/// # Errors
+///
+/// This function will return an error if the storage system could not be prepared.
+async fn prepare(settings: &Settings) -> Result<()> {
+    match settings.system {
+        #[cfg(feature = "system-file")]
+        System::File => {
+            let count = FileSystem::get_mut().await.clean(&settings.directory).await?;
+
+            debug!(count, "removed leftover temporary files");
+        }
+        #[cfg(feature = "system-memory")]
+        System::Memory => {}
+        #[cfg(feature = "system-sqlite")]
+        System::Sqlite => {
+            SqliteSystem::get_mut().await.open(&settings.directory)?;
+
+            debug!("opened database");
+        }
+    }
+
+    Ok(())
 }
 
 /// Starts the storage thread.
 ///
+/// The configured storage system is prepared for use beforehand, and any journaled requests that were not applied
+/// during the previous run are replayed. Expired data is swept at the configured interval, and if enabled, snapshots
+/// are also taken at the configured interval.
+///
 /// # Panics
 ///
 /// Panics if the thread has already been initialized.
 ///
 /// # Errors
 ///
-/// This function will return an error if the thread fails to spawn.
+/// This function will return an error if the thread fails to spawn, the storage system could not be prepared, or the
+/// journal could not be opened.
 #[tracing::instrument(level = "trace", name = "new_thread", skip_all)]
 pub async fn start(settings: Settings) -> Result<()> {
-    THREAD.async_api().initialize(self::create(settings)?).await;
+    let interval = Duration::from_secs(settings.sweep_interval.get());
+    let timeout = Duration::from_secs(settings.call_timeout.get());
+    #[cfg(feature = "snapshot")]
+    let snapshot_interval = settings.snapshot_interval.map(|interval| Duration::from_secs(interval.get()));
+
+    self::prepare(&settings).await?;
+
+    let journal = self::open_journal(&settings)?;
+    let mut storage = Storage::new(settings);
+
+    if let Some((journal, pending)) = journal {
+        storage.replay(&pending).await;
+        journal.compact()?;
+
+        *JOURNAL.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(journal));
+    }
+
+    THREAD.async_api().initialize(self::create(storage)?).await;
+
+    *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner) = timeout;
+
+    *SWEEPER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Sweeper::spawn(interval)?);
+
+    #[cfg(feature = "snapshot")]
+    if let Some(interval) = snapshot_interval {
+        *SNAPSHOTTER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Snapshotter::spawn(interval)?);
+    }
 
     Ok(())
 }
 
 /// Starts the storage thread, blocking the current thread until successful.
 ///
+/// The configured storage system is prepared for use beforehand, and any journaled requests that were not applied
+/// during the previous run are replayed. Expired data is swept at the configured interval, and if enabled, snapshots
+/// are also taken at the configured interval.
+///
 /// # Panics
 ///
 /// Panics if the thread has already been initialized or if this is called from within an asynchronous context.
 ///
 /// # Errors
 ///
-/// This function will return an error if the thread fails to spawn.
+/// This function will return an error if the thread fails to spawn, the storage system could not be prepared, or the
+/// journal could not be opened.
 #[tracing::instrument(level = "trace", name = "new_thread", skip_all)]
 pub fn blocking_start(settings: Settings) -> Result<()> {
-    THREAD.sync_api().initialize(self::create(settings)?);
+    let interval = Duration::from_secs(settings.sweep_interval.get());
+    let timeout = Duration::from_secs(settings.call_timeout.get());
+    #[cfg(feature = "snapshot")]
+    let snapshot_interval = settings.snapshot_interval.map(|interval| Duration::from_secs(interval.get()));
+
+    self::blocking_prepare(&settings)?;
+
+    let journal = self::open_journal(&settings)?;
+    let mut storage = Storage::new(settings);
+
+    if let Some((journal, pending)) = journal {
+        storage.blocking_replay(&pending);
+        journal.compact()?;
+
+        *JOURNAL.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(journal));
+    }
+
+    THREAD.sync_api().initialize(self::create(storage)?);
+
+    *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner) = timeout;
+
+    *SWEEPER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Sweeper::spawn(interval)?);
+
+    #[cfg(feature = "snapshot")]
+    if let Some(interval) = snapshot_interval {
+        *SNAPSHOTTER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Snapshotter::spawn(interval)?);
+    }
 
     Ok(())
 }
@@ -117,7 +471,27 @@ pub fn blocking_start(settings: Settings) -> Result<()> {
 ///
 /// Panics if the storage thread is not initialized.
 pub async fn close() {
+    let sweeper = SWEEPER.lock().unwrap_or_else(PoisonError::into_inner).take();
+
+    if let Some(sweeper) = sweeper
+        && let Err(error) = tokio::task::spawn_blocking(|| sweeper.stop()).await
+    {
+        warn!(%error, "failed to stop sweeper thread");
+    }
+
+    #[cfg(feature = "snapshot")]
+    let snapshotter = SNAPSHOTTER.lock().unwrap_or_else(PoisonError::into_inner).take();
+
+    #[cfg(feature = "snapshot")]
+    if let Some(snapshotter) = snapshotter
+        && let Err(error) = tokio::task::spawn_blocking(|| snapshotter.stop()).await
+    {
+        warn!(%error, "failed to stop snapshotter thread");
+    }
+
     THREAD.async_api().close().await;
+
+    self::close_journal();
 }
 
 /// Closes the storage thread.
@@ -128,24 +502,285 @@ pub async fn close() {
 ///
 /// Panics if the storage thread is not initialized or if this is called in an asynchronous context.
 pub fn blocking_close() {
+    let sweeper = SWEEPER.lock().unwrap_or_else(PoisonError::into_inner).take();
+
+    if let Some(sweeper) = sweeper {
+        sweeper.stop();
+    }
+
+    #[cfg(feature = "snapshot")]
+    let snapshotter = SNAPSHOTTER.lock().unwrap_or_else(PoisonError::into_inner).take();
+
+    #[cfg(feature = "snapshot")]
+    if let Some(snapshotter) = snapshotter {
+        snapshotter.stop();
+    }
+
     THREAD.sync_api().close();
+
+    self::close_journal();
 }
 
-/// Runs the thread's process.
-async fn run(Stateful { state, value }: Stateful<RwLock<Storage>, Request>) -> Response {
-    match &value {
-        Request::Exists(path) => state.read().await.exists(path).await.map_or_else(Response::Error, Response::Exists),
-        Request::Size(path) => state.read().await.size(path).await.map_or_else(Response::Error, Response::Size),
-        Request::Read(path) => state.read().await.read(path).await.map_or_else(Response::Error, Response::Read),
+/// Returns the current runtime metrics of the storage thread.
+///
+/// # Panics
+///
+/// Panics if the storage thread is not initialized.
+pub async fn metrics() -> Option<Metrics> {
+    THREAD.async_api().get().await.metrics()
+}
+
+/// Returns the current runtime metrics of the storage thread.
+///
+/// This blocks the current thread.
+///
+/// # Panics
+///
+/// Panics if the storage thread is not initialized or if this is called in an asynchronous context.
+pub fn blocking_metrics() -> Option<Metrics> {
+    THREAD.sync_api().get().metrics()
+}
+
+/// Compacts and closes the journal, writing any requests that were recorded but not applied.
+fn close_journal() {
+    let journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner).take();
+
+    if let Some(journal) = journal
+        && let Err(error) = journal.compact().and_then(|compacted| if compacted { Ok(()) } else { journal.flush() })
+    {
+        warn!(%error, "failed to close journal");
+    }
+}
+
+/// Returns the journal, if the configured storage system persists data.
+fn journal() -> Option<Arc<Journal>> {
+    JOURNAL.lock().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Sends the given request to the storage thread, returning its response.
+///
+/// Requests that modify stored data are recorded within the journal as they are sent, so that they are replayed when
+/// the storage thread is next started if the process stops before they are applied. A request is only recorded once a
+/// slot within the storage thread's channel has been reserved for it, and is then sent without waiting, so that every
+/// recorded request is sent even if the call is cancelled.
+///
+/// If the storage thread does not respond within the configured timeout once the request is sent, the request fails,
+/// although it may still be applied.
+///
+/// # Errors
+///
+/// This function will return an error if the request could not be recorded or sent, or if the storage thread did not
+/// respond in time.
+async fn call(request: Request) -> anyhow::Result<Response> {
+    let timeout = *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner);
+    let api = THREAD.async_api();
+    let thread = api.get().await;
+    let reply = self::submit(thread.reserve().await?, request)?;
+
+    // The thread is released before waiting, so that it may be closed while this request is handled.
+    drop(thread);
+
+    self::response(reply.recv_with_timeout(timeout).await, timeout)
+}
+
+/// Sends the given request to the storage thread, returning its response.
+///
+/// Requests that modify stored data are recorded within the journal as they are sent, so that they are replayed when
+/// the storage thread is next started if the process stops before they are applied. A request is only recorded once a
+/// slot within the storage thread's channel has been reserved for it, and is then sent without waiting, so that every
+/// recorded request is sent even if the call is cancelled.
+///
+/// If the storage thread does not respond within the configured timeout once the request is sent, the request fails,
+/// although it may still be applied.
+///
+/// # Errors
+///
+/// This function will return an error if the request could not be recorded or sent, or if the storage thread did not
+/// respond in time.
+///
+/// # Panics
+///
+/// Panics if this is called from within an asynchronous context.
+fn blocking_call(request: Request) -> anyhow::Result<Response> {
+    let timeout = *TIMEOUT.lock().unwrap_or_else(PoisonError::into_inner);
+    let api = THREAD.sync_api();
+    let thread = api.get();
+    let reply = self::submit(thread.blocking_reserve()?, request)?;
+
+    // The thread is released before waiting, so that it may be closed while this request is handled.
+    drop(thread);
+
+    self::response(reply.blocking_recv_with_timeout(timeout), timeout)
+}
+
+/// Returns the response of a request that was sent to the storage thread, which fails with [`Error::TimedOut`] if the
+/// storage thread did not respond within the given timeout.
+///
+/// # Errors
+///
+/// This function will return an error if the storage thread did not respond.
+fn response(result: Result<Response, CallError<Envelope, Response>>, timeout: Duration) -> anyhow::Result<Response> {
+    match result {
+        Ok(response) => Ok(response),
+        Err(CallError::TimedOut) => Err(Error::TimedOut(timeout).into()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Sends the given request through the given reservation, returning the pending response.
+///
+/// If the request modifies stored data, it is first recorded within the journal. The journal is held until the request
+/// is sent, so that requests are journaled in the order that they are sent, while requests that are not journaled are
+/// sent without waiting on one another. Recording only buffers the request in memory, as the storage thread writes it
+/// to the disk before the request is applied.
+///
+/// # Errors
+///
+/// This function will return an error if the request could not be recorded.
+fn submit(reservation: Reservation<'_, Envelope, Response>, request: Request) -> Result<Reply<Envelope, Response>> {
+    let Some(operations) = request.operations() else {
+        return Ok(reservation.submit(Envelope { request, sequence: None }));
+    };
+
+    let journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+    let sequence = journal.as_ref().map(|journal| journal.record(&operations)).transpose()?;
+
+    drop(operations);
+
+    let reply = reservation.submit(Envelope { request, sequence });
+
+    drop(journal);
+
+    Ok(reply)
+}
+
+/// Synchronizes the journal with the disk, so that the journaled request with the given sequence number is replayed if
+/// the process stops before it is applied.
+///
+/// Every request that was recorded before the journal is written is synchronized at once, so concurrent requests share
+/// a single write. The journal is written on a blocking thread, so that the storage thread's runtime is not blocked.
+///
+/// # Errors
+///
+/// This function will return an error if the journal could not be written.
+async fn sync(sequence: u64) -> anyhow::Result<()> {
+    let Some(journal) = self::journal() else { return Ok(()) };
+
+    tokio::task::spawn_blocking(move || journal.sync(sequence)).await??;
+
+    Ok(())
+}
+
+/// Marks the journaled request with the given sequence number as applied, compacting the journal if it has grown too
+/// large once every recorded request has been applied.
+async fn commit(sequence: u64) {
+    let Some(journal) = self::journal() else { return };
+
+    if let Err(error) = journal.commit(sequence) {
+        warn!(%error, "failed to mark journaled request as applied");
+    }
+
+    if !journal.is_compactable() {
+        return;
+    }
+
+    match tokio::task::spawn_blocking(move || journal.compact()).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(error)) => warn!(%error, "failed to compact journal"),
+        Err(error) => warn!(%error, "failed to compact journal"),
+    }
+}
+
+/// Admits the given request, returning a future that handles it once the stored data that it accesses is available.
+///
+/// The request's access is registered during admission, so that conflicting requests are handled in the order that they
+/// were received, while the wait happens within the returned future, so that admission is never held up by it.
+async fn run(Stateful { state, value }: Stateful<State, Envelope>) -> impl Future<Output = Response> + Send + 'static {
+    let Envelope { request, sequence } = value;
+    let access = state.locks.register(request.access());
+
+    async move {
+        // A journaled request is only applied once it has been written to the disk.
+        let synced = if let Some(sequence) = sequence { self::sync(sequence).await } else { Ok(()) };
+
+        access.granted().await;
+
+        let response = match synced {
+            Ok(()) => self::handle(&state.storage, request).await,
+            Err(error) => Response::Error(error),
+        };
+
+        drop(access);
+
+        // Failed requests are also marked as applied, as they were reported to their caller.
+        if let Some(sequence) = sequence {
+            self::commit(sequence).await;
+        }
+
+        response
+    }
+}
+
+/// Handles the given request.
+async fn handle(state: &RwLock<Storage>, request: Request) -> Response {
+    match request {
+        Request::Exists(path) => state.read().await.exists(&path).await.map_or_else(Response::Error, Response::Exists),
+        Request::Size(path) => state.read().await.size(&path).await.map_or_else(Response::Error, Response::Size),
+        Request::Read(path) => state.read().await.read(&path).await.map_or_else(Response::Error, Response::Read),
+        Request::ReadStream(path) => {
+            state.read().await.read_stream(&path).await.map_or_else(Response::Error, Response::Stream)
+        }
+        Request::List(prefix) => state.read().await.list(&prefix).await.map_or_else(Response::Error, Response::List),
         Request::Write(path, bytes) => {
-            state.write().await.write(path, bytes).await.map_or_else(Response::Error, |()| Response::Acknowledge)
+            state.read().await.write(&path, &bytes).await.map_or_else(Response::Error, |()| Response::Acknowledge)
+        }
+        Request::WriteStream(path, stream) => {
+            state.read().await.write_stream(&path, stream).await.map_or_else(Response::Error, Response::Size)
         }
         Request::Rename(from, into) => {
-            state.write().await.rename(from, into).await.map_or_else(Response::Error, |()| Response::Acknowledge)
+            state.read().await.rename(&from, &into).await.map_or_else(Response::Error, |()| Response::Acknowledge)
         }
         Request::Delete(path) => {
-            state.write().await.delete(path).await.map_or_else(Response::Error, |()| Response::Acknowledge)
+            state.read().await.delete(&path).await.map_or_else(Response::Error, |()| Response::Acknowledge)
+        }
+        Request::Batch(operations) => {
+            state.read().await.apply(&operations).await.map_or_else(Response::Error, |()| Response::Acknowledge)
         }
+        Request::Expire(path) => {
+            state.read().await.remove_if_expired(&path).await.map_or_else(Response::Error, Response::Expired)
+        }
+        Request::PruneIndex(index) => {
+            state.read().await.prune_index(&index).await.map_or_else(Response::Error, Response::Pruned)
+        }
+        #[cfg(feature = "format-encryption")]
+        Request::Reencrypt(path) => self::reencrypt_stored(&*state.read().await, &path)
+            .await
+            .map_or_else(Response::Error, Response::Reencrypted),
+        Request::Subscribe(prefix) => Response::Subscribe(state.read().await.subscribe(&prefix)),
+        Request::FindInIndex(index, key) => {
+            state.read().await.find_in_index(&index, &key).await.map_or_else(Response::Error, Response::List)
+        }
+        Request::Usage(group) => state.read().await.usage(&group).await.map_or_else(Response::Error, Response::Usage),
+        #[cfg(feature = "blob")]
+        Request::StoreBlob(bytes) => {
+            state.write().await.store_blob(&bytes).await.map_or_else(Response::Error, Response::Blob)
+        }
+        #[cfg(feature = "blob")]
+        Request::BlobReferences(id) => {
+            state.read().await.blob_references(id).await.map_or_else(Response::Error, Response::References)
+        }
+        #[cfg(feature = "blob")]
+        Request::CollectBlobs(grace) => {
+            state.write().await.collect_blobs(grace).await.map_or_else(Response::Error, Response::Collected)
+        }
+        #[cfg(feature = "snapshot")]
+        Request::Snapshot => state.read().await.snapshot().await.map_or_else(Response::Error, Response::Snapshot),
+        #[cfg(feature = "snapshot")]
+        Request::Restore(path) => {
+            state.write().await.restore(path.as_deref()).await.map_or_else(Response::Error, Response::Manifest)
+        }
+        #[cfg(feature = "caching")]
+        Request::Stats => Response::Stats(state.read().await.cache_stats().await),
     }
 }
 
@@ -161,7 +796,7 @@ macro_rules! invoke {
     )*) => {$(
         $(#[$attribute])*
         pub async fn $name($($($input: $type),*)?) -> anyhow::Result<$return> {
-            let response = THREAD.async_api().get_mut().await.call($($request)*).await?;
+            let response = self::call($($request)*).await?;
 
             match response {
                 $($response)*
@@ -176,7 +811,7 @@ macro_rules! invoke {
         ///
         /// Panics if this is called from within a synchronous context.
         pub fn $blocking_name($($($input: $type),*)?) -> anyhow::Result<$return> {
-            let response = THREAD.sync_api().get_mut().blocking_call($($request)*)?;
+            let response = self::blocking_call($($request)*)?;
 
             match response {
                 $($response)*
@@ -210,6 +845,50 @@ invoke! {
         Response::Size(size) => Ok(size),
     };
 
+    /// Returns the raw bytes of the data at the given path, without decoding them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    read_bytes, blocking_read_bytes (path: Box<Path>) {
+        Request::Read(path)
+    } -> Arc<[u8]> {
+        Response::Read(bytes) => Ok(bytes),
+    };
+
+    /// Returns a stream of the raw bytes of the data at the given path, without decoding them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    read_stream, blocking_read_stream (path: Box<Path>) {
+        Request::ReadStream(path)
+    } -> DataStream {
+        Response::Stream(stream) => Ok(stream),
+    };
+
+    /// Writes the raw bytes of the given stream into the given path, returning the number of bytes stored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    write_stream, blocking_write_stream (path: Box<Path>, stream: DataStream) {
+        Request::WriteStream(path, stream)
+    } -> u64 {
+        Response::Size(size) => Ok(size),
+    };
+
+    /// Returns the paths of all data stored under the given path prefix.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    list, blocking_list (prefix: Box<Path>) {
+        Request::List(prefix)
+    } -> Box<[Box<Path>]> {
+        Response::List(paths) => Ok(paths),
+    };
+
     /// Returns whether data exists at the given path.
     ///
     /// # Errors
@@ -231,50 +910,351 @@ invoke! {
     } -> () {
         Response::Acknowledge => Ok(()),
     };
+
+    /// Applies the given operations all-or-nothing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or any operation failed, in which case no
+    /// changes are kept.
+    batch, blocking_batch (operations: Box<[Operation]>) {
+        Request::Batch(operations)
+    } -> () {
+        Response::Acknowledge => Ok(()),
+    };
+
+    /// Deletes the data at the given path if it has expired, returning whether it was deleted.
+    ///
+    /// Only the data's header is read, bypassing the cache.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the data could not be read or deleted.
+    remove_if_expired, blocking_remove_if_expired (path: Box<Path>) {
+        Request::Expire(path)
+    } -> bool {
+        Response::Expired(removed) => Ok(removed),
+    };
+
+    /// Removes the entries of values that no longer exist from the given secondary index, returning the number of
+    /// removed entries.
+    ///
+    /// Values that are deleted without updating their indexes, such as expired values, leave their entries behind.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent, the index or its values could not be read,
+    /// or the index could not be written.
+    prune_index, blocking_prune_index (index: Box<Path>) {
+        Request::PruneIndex(index)
+    } -> usize {
+        Response::Pruned(count) => Ok(count),
+    };
+
+    /// Returns a receiver of every change made through the storage thread to data stored under the given path prefix.
+    ///
+    /// If the `watch-external` feature is enabled, edits made to the storage directory outside of the storage thread
+    /// are also received.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    subscribe, blocking_subscribe (prefix: Box<Path>) {
+        Request::Subscribe(prefix)
+    } -> Receiver<Event> {
+        Response::Subscribe(receiver) => Ok(receiver),
+    };
+
+    /// Returns the paths of all values with the given key within the given secondary index.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the index could not be read.
+    find_in_index, blocking_find_in_index (index: Box<Path>, key: Box<str>) {
+        Request::FindInIndex(index, key)
+    } -> Box<[Box<Path>]> {
+        Response::List(paths) => Ok(paths),
+    };
+
+    /// Returns the usage of the given group of stored data, which is usually a guild identifier.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the usage could not be calculated.
+    usage, blocking_usage (group: Box<str>) {
+        Request::Usage(group)
+    } -> GroupUsage {
+        Response::Usage(usage) => Ok(usage),
+    };
+
+    /// Stores the given bytes as a blob, returning its identifier.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the blob could not be written.
+    #[cfg(feature = "blob")]
+    store_blob, blocking_store_blob (bytes: Arc<[u8]>) {
+        Request::StoreBlob(bytes)
+    } -> BlobId {
+        Response::Blob(id) => Ok(id),
+    };
+
+    /// Returns the number of stored values that reference the given blob.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the blob indexes could not be read.
+    #[cfg(feature = "blob")]
+    blob_references, blocking_blob_references (id: BlobId) {
+        Request::BlobReferences(id)
+    } -> usize {
+        Response::References(count) => Ok(count),
+    };
+
+    /// Deletes every unreferenced blob that was not stored within the given grace period, returning the number of
+    /// blobs that were deleted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the blobs could not be collected.
+    #[cfg(feature = "blob")]
+    collect_blobs, blocking_collect_blobs (grace: Duration) {
+        Request::CollectBlobs(grace)
+    } -> usize {
+        Response::Collected(count) => Ok(count),
+    };
+
+    /// Writes a snapshot of all stored data into the snapshot directory, returning the snapshot's path.
+    ///
+    /// Writes are paused until the snapshot has been written. Once written, the oldest snapshots are deleted until
+    /// only the configured number of rotations remain.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent or the snapshot could not be written.
+    #[cfg(feature = "snapshot")]
+    snapshot, blocking_snapshot {
+        Request::Snapshot
+    } -> Box<Path> {
+        Response::Snapshot(path) => Ok(path),
+    };
+
+    /// Replaces all stored data with the contents of the given snapshot, returning the snapshot's manifest.
+    ///
+    /// Relative paths are resolved within the snapshot directory. If no path is given, the most recent snapshot is
+    /// restored. The restore is all-or-nothing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent, the snapshot could not be read, or the
+    /// data could not be written.
+    #[cfg(feature = "snapshot")]
+    restore, blocking_restore (path: Option<Box<Path>>) {
+        Request::Restore(path)
+    } -> Manifest {
+        Response::Manifest(manifest) => Ok(manifest),
+    };
+
+    /// Returns the statistics of the storage cache.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be sent.
+    #[cfg(feature = "caching")]
+    stats, blocking_stats {
+        Request::Stats
+    } -> CacheStats {
+        Response::Stats(stats) => Ok(stats),
+    };
 }
 
 /// Returns the data at the given path.
 ///
+/// If the data was stored using an older data version, it is migrated and rewritten using the current version. Expired
+/// data is deleted instead of being returned.
+///
 /// # Errors
 ///
-/// This function will return an error if the message could not be sent.
+/// This function will return an error if the message could not be sent, the data has expired, or the data could not be
+/// decoded.
 pub async fn read<T: Stored>(path: Box<Path>) -> anyhow::Result<T> {
-    let response = THREAD.async_api().get_mut().await.call(Request::Read(path)).await?;
+    let (bytes, source) = match self::read_bytes(path.clone()).await {
+        Ok(bytes) => (bytes, None),
+        Err(error) => match self::find_alternate(&path).await {
+            Some(source) => (self::read_bytes(source.clone()).await?, Some(source)),
+            None => return Err(error),
+        },
+    };
 
-    match response {
-        Response::Read(bytes) => T::data_format().decode(&bytes).map_err(Into::into),
-        Response::Error(error) => Err(error),
-        _ => unreachable!("unexpected response: '{response:?}'"),
+    if crate::stored::is_expired(&bytes) {
+        // The value would be deleted during the next sweep anyway, so failing to delete it here is not fatal.
+        if let Err(error) = self::delete(source.unwrap_or_else(|| path.clone())).await {
+            warn!(%error, "failed to remove expired data");
+        }
+
+        return Err(crate::Error::Expired(path).into());
+    }
+
+    let (value, version) = crate::stored::decode::<T>(&bytes)?;
+
+    if version < T::DATA_VERSION {
+        debug!(?path, from = version, into = T::DATA_VERSION, "migrated data");
+    }
+    if let Some(source) = &source {
+        debug!(?path, ?source, "migrated data format");
+    }
+
+    if version < T::DATA_VERSION || source.is_some() {
+        // The value has already been read successfully, so failing to upgrade the stored data is not fatal.
+        if let Err(error) = self::write_until(path, &value, crate::stored::split_expiry(&bytes)).await {
+            warn!(%error, "failed to rewrite migrated data");
+        } else if let Some(source) = source
+            && let Err(error) = self::delete(source).await
+        {
+            warn!(%error, "failed to remove migrated data");
+        }
     }
+
+    Ok(value)
 }
 
 /// Returns the data at the given path.
 ///
+/// If the data was stored using an older data version, it is migrated and rewritten using the current version. Expired
+/// data is deleted instead of being returned.
+///
 /// # Errors
 ///
-/// This function will return an error if the message could not be sent.
+/// This function will return an error if the message could not be sent, the data has expired, or the data could not be
+/// decoded.
 ///
 /// # Panics
 ///
 /// Panics if this is called from within a synchronous context.
 pub fn blocking_read<T: Stored>(path: Box<Path>) -> anyhow::Result<T> {
-    let response = THREAD.sync_api().get_mut().blocking_call(Request::Read(path))?;
+    let (bytes, source) = match self::blocking_read_bytes(path.clone()) {
+        Ok(bytes) => (bytes, None),
+        Err(error) => match self::blocking_find_alternate(&path) {
+            Some(source) => (self::blocking_read_bytes(source.clone())?, Some(source)),
+            None => return Err(error),
+        },
+    };
 
-    match response {
-        Response::Read(bytes) => T::data_format().decode(&bytes).map_err(Into::into),
-        Response::Error(error) => Err(error),
-        _ => unreachable!("unexpected response: '{response:?}'"),
+    if crate::stored::is_expired(&bytes) {
+        // The value would be deleted during the next sweep anyway, so failing to delete it here is not fatal.
+        if let Err(error) = self::blocking_delete(source.unwrap_or_else(|| path.clone())) {
+            warn!(%error, "failed to remove expired data");
+        }
+
+        return Err(crate::Error::Expired(path).into());
+    }
+
+    let (value, version) = crate::stored::decode::<T>(&bytes)?;
+
+    if version < T::DATA_VERSION {
+        debug!(?path, from = version, into = T::DATA_VERSION, "migrated data");
     }
+    if let Some(source) = &source {
+        debug!(?path, ?source, "migrated data format");
+    }
+
+    if version < T::DATA_VERSION || source.is_some() {
+        // The value has already been read successfully, so failing to upgrade the stored data is not fatal.
+        if let Err(error) = self::blocking_write_until(path, &value, crate::stored::split_expiry(&bytes)) {
+            warn!(%error, "failed to rewrite migrated data");
+        } else if let Some(source) = source
+            && let Err(error) = self::blocking_delete(source)
+        {
+            warn!(%error, "failed to remove migrated data");
+        }
+    }
+
+    Ok(value)
+}
+
+/// Returns the path at which the data for the given path is stored using a different compression format, if any.
+async fn find_alternate(path: &Path) -> Option<Box<Path>> {
+    for alternate in crate::format::alternate_paths(path) {
+        if self::exists(alternate.clone()).await.unwrap_or(false) {
+            return Some(alternate);
+        }
+    }
+
+    None
 }
 
-/// Writes bytes into the given path.
+/// Returns the path at which the data for the given path is stored using a different compression format, if any.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+fn blocking_find_alternate(path: &Path) -> Option<Box<Path>> {
+    crate::format::alternate_paths(path)
+        .into_iter()
+        .find(|alternate| self::blocking_exists(alternate.clone()).unwrap_or(false))
+}
+
+/// Writes a value into the given path.
+///
+/// The value expires after its type's default lifetime, if any.
 ///
 /// # Errors
 ///
 /// This function will return an error if the message could not be sent.
 pub async fn write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<()> {
-    let bytes = T::data_format().encode(value)?;
-    let response = THREAD.async_api().get_mut().await.call(Request::Write(path, bytes)).await?;
+    self::write_until(path, value, crate::stored::expiry_after(T::DATA_EXPIRY)).await
+}
+
+/// Writes a value into the given path.
+///
+/// The value expires after its type's default lifetime, if any.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+pub fn blocking_write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<()> {
+    self::blocking_write_until(path, value, crate::stored::expiry_after(T::DATA_EXPIRY))
+}
+
+/// Writes a value into the given path that expires after the given lifetime.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+pub async fn write_for<T: Stored>(path: Box<Path>, value: &T, lifetime: Duration) -> anyhow::Result<()> {
+    self::write_until(path, value, crate::stored::expiry_after(Some(lifetime))).await
+}
+
+/// Writes a value into the given path that expires after the given lifetime.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+pub fn blocking_write_for<T: Stored>(path: Box<Path>, value: &T, lifetime: Duration) -> anyhow::Result<()> {
+    self::blocking_write_until(path, value, crate::stored::expiry_after(Some(lifetime)))
+}
+
+/// Writes a value into the given path that expires at the given time, if any.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+async fn write_until<T: Stored>(path: Box<Path>, value: &T, expiry: Option<SystemTime>) -> anyhow::Result<()> {
+    let bytes = crate::stored::encode(value, expiry)?;
+
+    if !T::DATA_INDICES.is_empty() {
+        return Batch::new().write_at(&path, bytes, value).commit().await;
+    }
+
+    let response = self::call(Request::Write(path, bytes)).await?;
 
     match response {
         Response::Acknowledge => Ok(()),
@@ -283,7 +1263,7 @@ pub async fn write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<()>
     }
 }
 
-/// Writes bytes into the given path.
+/// Writes a value into the given path that expires at the given time, if any.
 ///
 /// # Errors
 ///
@@ -292,9 +1272,14 @@ pub async fn write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<()>
 /// # Panics
 ///
 /// Panics if this is called from within a synchronous context.
-pub fn blocking_write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<()> {
-    let bytes = T::data_format().encode(value)?;
-    let response = THREAD.sync_api().get_mut().blocking_call(Request::Write(path, bytes))?;
+fn blocking_write_until<T: Stored>(path: Box<Path>, value: &T, expiry: Option<SystemTime>) -> anyhow::Result<()> {
+    let bytes = crate::stored::encode(value, expiry)?;
+
+    if !T::DATA_INDICES.is_empty() {
+        return Batch::new().write_at(&path, bytes, value).blocking_commit();
+    }
+
+    let response = self::blocking_call(Request::Write(path, bytes))?;
 
     match response {
         Response::Acknowledge => Ok(()),
@@ -302,3 +1287,184 @@ pub fn blocking_write<T: Stored>(path: Box<Path>, value: &T) -> anyhow::Result<(
         _ => unreachable!("unexpected response: '{response:?}'"),
     }
 }
+
+/// Returns the remaining lifetime of the data at the given path, or [`None`] if it never expires.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+pub async fn lifetime(path: Box<Path>) -> anyhow::Result<Option<Duration>> {
+    Ok(crate::stored::remaining_lifetime(&self::read_bytes(path).await?))
+}
+
+/// Returns the remaining lifetime of the data at the given path, or [`None`] if it never expires.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+pub fn blocking_lifetime(path: Box<Path>) -> anyhow::Result<Option<Duration>> {
+    Ok(crate::stored::remaining_lifetime(&self::blocking_read_bytes(path)?))
+}
+
+/// Deletes all expired data, returning the number of entries that were removed.
+///
+/// Each entry is checked by its own request, so that a sweep only holds up requests that access the entry that is being
+/// checked. Entries that could not be checked or deleted are logged and skipped, so that they do not stop the sweep.
+/// Once expired data has been deleted, the entries of deleted values are pruned from every secondary index.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or the stored data could not be listed.
+pub async fn sweep() -> anyhow::Result<usize> {
+    let mut count = 0;
+
+    let paths = self::list(Path::new("").into()).await?;
+
+    for path in paths.iter().filter(|path| crate::expiry::is_swept(path)) {
+        match self::remove_if_expired(path.clone()).await {
+            Ok(removed) => count += usize::from(removed),
+            Err(error) => warn!(?path, %error, "failed to sweep stored data"),
+        }
+    }
+
+    for index in paths.iter().filter(|path| path.starts_with(INDEX_DIRECTORY)) {
+        if let Err(error) = self::prune_index(index.clone()).await {
+            warn!(?index, %error, "failed to prune index");
+        }
+    }
+
+    Ok(count)
+}
+
+/// Deletes all expired data, returning the number of entries that were removed.
+///
+/// Each entry is checked by its own request, so that a sweep only holds up requests that access the entry that is being
+/// checked. Entries that could not be checked or deleted are logged and skipped, so that they do not stop the sweep.
+/// Once expired data has been deleted, the entries of deleted values are pruned from every secondary index.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or the stored data could not be listed.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+pub fn blocking_sweep() -> anyhow::Result<usize> {
+    let mut count = 0;
+
+    let paths = self::blocking_list(Path::new("").into())?;
+
+    for path in paths.iter().filter(|path| crate::expiry::is_swept(path)) {
+        match self::blocking_remove_if_expired(path.clone()) {
+            Ok(removed) => count += usize::from(removed),
+            Err(error) => warn!(?path, %error, "failed to sweep stored data"),
+        }
+    }
+
+    for index in paths.iter().filter(|path| path.starts_with(INDEX_DIRECTORY)) {
+        if let Err(error) = self::blocking_prune_index(index.clone()) {
+            warn!(?index, %error, "failed to prune index");
+        }
+    }
+
+    Ok(count)
+}
+
+/// Re-encrypts all encrypted data stored under the given path prefix using the current encryption key, returning the
+/// number of entries that were rewritten.
+///
+/// Only data whose outermost format is encryption is re-encrypted, and data that already uses the current key is left
+/// untouched. Each entry is read and rewritten by a single request, so that no write to the entry can land in between.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or any data could not be re-encrypted.
+#[cfg(feature = "format-encryption")]
+pub async fn reencrypt(prefix: Box<Path>) -> anyhow::Result<usize> {
+    let mut count = 0;
+
+    for path in self::list(prefix).await?.into_iter().filter(|path| crate::format::encryption::is_encrypted(path)) {
+        let response = self::call(Request::Reencrypt(path.clone())).await?;
+
+        match response {
+            Response::Reencrypted(false) => {}
+            Response::Reencrypted(true) => {
+                debug!(?path, "re-encrypted data");
+
+                count += 1;
+            }
+            Response::Error(error) => return Err(error),
+            _ => unreachable!("unexpected response: '{response:?}'"),
+        }
+    }
+
+    Ok(count)
+}
+
+/// Re-encrypts all encrypted data stored under the given path prefix using the current encryption key, returning the
+/// number of entries that were rewritten.
+///
+/// Only data whose outermost format is encryption is re-encrypted, and data that already uses the current key is left
+/// untouched. Each entry is read and rewritten by a single request, so that no write to the entry can land in between.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be sent or any data could not be re-encrypted.
+///
+/// # Panics
+///
+/// Panics if this is called from within a synchronous context.
+#[cfg(feature = "format-encryption")]
+pub fn blocking_reencrypt(prefix: Box<Path>) -> anyhow::Result<usize> {
+    let mut count = 0;
+
+    for path in self::blocking_list(prefix)?.into_iter().filter(|path| crate::format::encryption::is_encrypted(path)) {
+        let response = self::blocking_call(Request::Reencrypt(path.clone()))?;
+
+        match response {
+            Response::Reencrypted(false) => {}
+            Response::Reencrypted(true) => {
+                debug!(?path, "re-encrypted data");
+
+                count += 1;
+            }
+            Response::Error(error) => return Err(error),
+            _ => unreachable!("unexpected response: '{response:?}'"),
+        }
+    }
+
+    Ok(count)
+}
+
+/// Re-encrypts the data at the given path using the current encryption key, returning whether it was rewritten.
+///
+/// # Errors
+///
+/// This function will return an error if the data could not be read, re-encrypted, or written.
+#[cfg(feature = "format-encryption")]
+async fn reencrypt_stored(storage: &Storage, path: &Path) -> anyhow::Result<bool> {
+    let Some(bytes) = self::reencrypt_bytes(&storage.read(path).await?)? else { return Ok(false) };
+
+    storage.write(path, &bytes).await?;
+
+    Ok(true)
+}
+
+/// Re-encrypts the given stored bytes using the current encryption key, retaining their version header.
+///
+/// Returns [`None`] if the bytes already use the current key.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes could not be re-encrypted.
+#[cfg(feature = "format-encryption")]
+fn reencrypt_bytes(bytes: &[u8]) -> anyhow::Result<Option<Arc<[u8]>>> {
+    let (header, bytes) = crate::stored::split_header(bytes);
+    let bytes = crate::format::encryption::reencrypt(bytes)?;
+
+    Ok(bytes.map(|bytes| [header, &bytes].concat().into()))
+}
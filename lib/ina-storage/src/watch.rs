@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+#[cfg(feature = "watch-external")]
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tracing::trace;
+
+/// The number of events that each subscription buffers before its receivers begin to lag.
+const CHANNEL_CAPACITY: usize = 64;
+/// The amount of time after a change made through the storage thread during which external edits of the same path are
+/// ignored, preventing the change from being reported twice.
+#[cfg(feature = "watch-external")]
+const EXTERNAL_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// A change to stored data.
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Event {
+    /// Data was written into the given path.
+    Written(Box<Path>),
+    /// Data was renamed from the first path into the second path.
+    Renamed(Box<Path>, Box<Path>),
+    /// Data was deleted from the given path.
+    Deleted(Box<Path>),
+}
+
+impl Event {
+    /// Returns the paths affected by this event.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        let (first, second) = match self {
+            Self::Written(path) | Self::Deleted(path) => (&**path, None),
+            Self::Renamed(from, into) => (&**from, Some(&**into)),
+        };
+
+        std::iter::once(first).chain(second)
+    }
+}
+
+/// The subscribers to changes of stored data, grouped by path prefix.
+#[derive(Debug, Default)]
+pub struct Watchers {
+    /// The event sender of each watched path prefix.
+    senders: Mutex<HashMap<Box<Path>, Sender<Event>>>,
+    /// The paths recently changed through the storage thread, and when they were changed.
+    #[cfg(feature = "watch-external")]
+    recent: Mutex<HashMap<Box<Path>, Instant>>,
+}
+
+impl Watchers {
+    /// Returns a receiver of every event that affects a path under the given prefix.
+    ///
+    /// Receivers that fall too far behind skip the oldest events, and are notified of this when receiving.
+    pub fn subscribe(&self, prefix: &Path) -> Receiver<Event> {
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+
+        senders.entry(prefix.into()).or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0).subscribe()
+    }
+
+    /// Records that the given event is about to be caused through the storage thread, so that the external edits that
+    /// it causes are ignored.
+    ///
+    /// This must be called before the change is made, as the external watcher may otherwise observe it first.
+    #[cfg_attr(
+        not(feature = "watch-external"),
+        expect(unused_variables, clippy::missing_const_for_fn, reason = "only external edits are ignored")
+    )]
+    pub fn expect(&self, event: &Event) {
+        #[cfg(feature = "watch-external")]
+        {
+            let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+
+            recent.retain(|_, changed| now.duration_since(*changed) < EXTERNAL_GRACE_PERIOD);
+            recent.extend(event.paths().map(|path| (path.into(), now)));
+        }
+    }
+
+    /// Sends the given event to every subscriber of a prefix of any of its paths.
+    ///
+    /// Prefixes without any remaining subscribers are removed.
+    pub fn notify(&self, event: &Event) {
+        self.send(event);
+    }
+
+    /// Sends the given event, which was caused by an edit made outside of the storage thread, to every subscriber of a
+    /// prefix of any of its paths.
+    ///
+    /// Events for paths that were recently changed through the storage thread are ignored.
+    #[cfg(feature = "watch-external")]
+    pub fn notify_external(&self, event: &Event) {
+        let recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+
+        if event.paths().any(|path| recent.get(path).is_some_and(|c| now.duration_since(*c) < EXTERNAL_GRACE_PERIOD)) {
+            return;
+        }
+
+        drop(recent);
+
+        self.send(event);
+    }
+
+    /// Sends the given event to every subscriber of a prefix of any of its paths.
+    fn send(&self, event: &Event) {
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+
+        senders.retain(|_, sender| sender.receiver_count() > 0);
+
+        for (prefix, sender) in senders.iter() {
+            if event.paths().any(|path| path.starts_with(prefix)) {
+                // This can only fail if every receiver was dropped, in which case it will be removed on the next call.
+                let _ = sender.send(event.clone());
+
+                trace!(?prefix, ?event, "sent change notification");
+            }
+        }
+    }
+}
+
+/// Watches the storage directory for edits made outside of the storage thread.
+#[cfg(feature = "watch-external")]
+pub mod external {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use notify::event::{EventKind, ModifyKind, RenameMode};
+    use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+    use tracing::warn;
+
+    use super::{Event, Watchers};
+
+    /// Starts watching the given directory, forwarding any edits to the given watchers.
+    ///
+    /// The returned watcher stops watching once dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory could not be watched.
+    pub fn watch(directory: &Path, watchers: Arc<Watchers>) -> notify::Result<RecommendedWatcher> {
+        std::fs::create_dir_all(directory)?;
+
+        let root = directory.canonicalize()?;
+        let mut watcher = notify::recommended_watcher({
+            let root = root.clone();
+
+            move |result: notify::Result<notify::Event>| match result {
+                Ok(event) => {
+                    if let Some(event) = self::convert(&root, &event) {
+                        watchers.notify_external(&event);
+                    }
+                }
+                Err(error) => warn!(%error, "failed to watch storage directory"),
+            }
+        })?;
+
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+
+        Ok(watcher)
+    }
+
+    /// Converts the given file system event into a storage event, if it affects stored data.
+    pub(super) fn convert(root: &Path, event: &notify::Event) -> Option<Event> {
+        let mut paths = event.paths.iter().filter_map(|path| self::relative(root, path));
+
+        match event.kind {
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any) => {
+                paths.next().map(Event::Written)
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match (paths.next(), paths.next()) {
+                (Some(from), Some(into)) => Some(Event::Renamed(from, into)),
+                (Some(path), None) => Some(Event::Written(path)),
+                (None, _) => None,
+            },
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => paths.next().map(Event::Written),
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+                paths.next().map(Event::Deleted)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the given path relative to the given root, or [`None`] if it does not refer to stored data.
+    ///
+    /// Temporary files created while writing and the journal are not considered to be stored data.
+    fn relative(root: &Path, path: &Path) -> Option<Box<Path>> {
+        let path = path.strip_prefix(root).ok()?;
+
+        let stored = !crate::system::file::is_temporary(path) && !crate::journal::is_journal(path);
+
+        (!path.as_os_str().is_empty() && stored).then(|| path.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use super::{Event, Watchers};
+
+    #[test]
+    fn notify_matching_prefixes() -> anyhow::Result<()> {
+        let watchers = Watchers::default();
+        let mut role = watchers.subscribe(Path::new("role"));
+        let mut guild = watchers.subscribe(Path::new("role/1"));
+        let mut poll = watchers.subscribe(Path::new("poll"));
+
+        watchers.notify(&Event::Written(Path::new("role/1/2").into()));
+        watchers.notify(&Event::Renamed(Path::new("role/3/4").into(), Path::new("poll/3/4").into()));
+
+        assert_eq!(role.try_recv()?, Event::Written(Path::new("role/1/2").into()));
+        assert_eq!(guild.try_recv()?, Event::Written(Path::new("role/1/2").into()));
+        assert!(matches!(role.try_recv()?, Event::Renamed(..)));
+        assert!(matches!(poll.try_recv()?, Event::Renamed(..)));
+        assert_eq!(guild.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(poll.try_recv(), Err(TryRecvError::Empty));
+
+        drop(guild);
+        watchers.notify(&Event::Deleted(Path::new("role/1/2").into()));
+
+        assert_eq!(watchers.senders.lock().map_or(0, |senders| senders.len()), 2);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "watch-external")]
+    #[test]
+    fn convert_external_edits() {
+        use notify::event::{EventKind, ModifyKind, RemoveKind, RenameMode};
+
+        let root = Path::new("/data");
+        let event = |kind, paths: &[&str]| {
+            let event = paths.iter().fold(notify::Event::new(kind), |event, path| event.add_path(root.join(path)));
+
+            super::external::convert(root, &event)
+        };
+
+        assert_eq!(
+            event(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), &["role/1/.2.pack.tmp", "role/1/2.pack"]),
+            Some(Event::Written(Path::new("role/1/2.pack").into()))
+        );
+        assert_eq!(
+            event(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), &["role/1/2.pack", "role/1/3.pack"]),
+            Some(Event::Renamed(Path::new("role/1/2.pack").into(), Path::new("role/1/3.pack").into()))
+        );
+        assert_eq!(
+            event(EventKind::Remove(RemoveKind::File), &["role/1/2.pack"]),
+            Some(Event::Deleted(Path::new("role/1/2.pack").into()))
+        );
+        assert_eq!(event(EventKind::Modify(ModifyKind::Any), &["role/1/.2.pack.tmp"]), None);
+    }
+
+    #[cfg(feature = "watch-external")]
+    #[test]
+    fn ignore_expected_external_edits() -> anyhow::Result<()> {
+        let watchers = Watchers::default();
+        let mut role = watchers.subscribe(Path::new("role"));
+        let event = Event::Written(Path::new("role/1/2").into());
+
+        watchers.expect(&event);
+        watchers.notify_external(&event);
+        watchers.notify(&event);
+
+        assert_eq!(role.try_recv()?, event);
+        assert_eq!(role.try_recv(), Err(TryRecvError::Empty));
+
+        watchers.notify_external(&Event::Written(Path::new("role/1/3").into()));
+
+        assert_eq!(role.try_recv()?, Event::Written(Path::new("role/1/3").into()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+//! Benchmarks invoking a thread from many simultaneous tasks, comparing exclusive calls against shared calls.
+
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::num::NonZero;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_main};
+use ina_threading::statics::Static;
+use ina_threading::threads::exchanger::Exchanger;
+use ina_threading::threads::invoker::Invoker;
+use ina_threading::{Handle, ReceiverHandle, SenderHandle};
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+/// The number of calls sent during each iteration.
+const CALLS: usize = 256;
+
+/// The exclusive invoker's handle.
+static EXCLUSIVE: Static<ExclusiveInvoker> = Static::new();
+/// The shared invoker's handle.
+static SHARED: Static<Invoker<u64, u64>> = Static::new();
+
+/// An invoker that matches responses to their calls through a map of completed responses keyed by nonce.
+///
+/// Calls require exclusive access, so callers must take the [`Static`] wrapper's write lock for every call.
+#[derive(Debug)]
+struct ExclusiveInvoker {
+    /// The inner thread handle.
+    exchanger: Exchanger<(usize, u64), (usize, u64), ()>,
+    /// The nonce of the next call.
+    nonce: usize,
+    /// Responses that were received before they were requested.
+    completed: BTreeMap<usize, u64>,
+}
+
+impl ExclusiveInvoker {
+    /// Spawns a new [`ExclusiveInvoker`] that increments each value that it receives.
+    fn spawn(capacity: NonZero<usize>) -> ina_threading::Result<Self> {
+        let exchanger = Exchanger::spawn_with_runtime("exclusive", capacity, |sender, mut receiver| async move {
+            while let Some((nonce, value)) = receiver.recv().await {
+                if sender.send((nonce, self::handle(value))).await.is_err() {
+                    break;
+                }
+            }
+        })?;
+
+        Ok(Self { exchanger, nonce: 0, completed: BTreeMap::new() })
+    }
+
+    /// Calls the thread, waiting for its response.
+    async fn call(&mut self, value: u64) -> Option<u64> {
+        let nonce = self.nonce;
+
+        self.nonce = self.nonce.wrapping_add(1);
+        self.exchanger.as_sender().send((nonce, value)).await.ok()?;
+
+        loop {
+            if let Some(response) = self.completed.remove(&nonce) {
+                return Some(response);
+            }
+
+            let (nonce, response) = self.exchanger.as_receiver_mut().recv().await?;
+
+            self.completed.insert(nonce, response);
+        }
+    }
+}
+
+impl Handle for ExclusiveInvoker {
+    type Output = ();
+
+    fn as_join_handle(&self) -> &std::thread::JoinHandle<Self::Output> {
+        self.exchanger.as_join_handle()
+    }
+
+    fn as_join_handle_mut(&mut self) -> &mut std::thread::JoinHandle<Self::Output> {
+        self.exchanger.as_join_handle_mut()
+    }
+
+    fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
+        self.exchanger.into_join_handle()
+    }
+}
+
+/// Handles a single call.
+const fn handle(value: u64) -> u64 {
+    value.wrapping_add(1)
+}
+
+/// Benchmarks calls sent simultaneously through the exclusive invoker against the shared invoker.
+fn compare(c: &mut Criterion, runtime: &Runtime) {
+    let mut group = c.benchmark_group("invoker");
+
+    group.throughput(Throughput::Elements(CALLS as u64));
+    group.bench_function(BenchmarkId::new("exclusive", CALLS), |b| {
+        b.to_async(runtime).iter(|| async {
+            // Each call is spawned as its own task, much like simultaneous interactions.
+            let mut tasks = (0 .. CALLS as u64)
+                .map(|value| async move { EXCLUSIVE.async_api().get_mut().await.call(value).await })
+                .collect::<JoinSet<_>>();
+
+            while let Some(result) = tasks.join_next().await {
+                black_box(result.ok().flatten());
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("shared", CALLS), |b| {
+        b.to_async(runtime).iter(|| async {
+            let mut tasks = (0 .. CALLS as u64)
+                .map(|value| async move { SHARED.async_api().get().await.call(value).await.ok() })
+                .collect::<JoinSet<_>>();
+
+            while let Some(result) = tasks.join_next().await {
+                black_box(result.ok().flatten());
+            }
+        });
+    });
+    group.finish();
+}
+
+/// Benchmarks calls sent simultaneously through each invoker.
+#[expect(clippy::panic, reason = "nothing can be benchmarked without the invoked threads")]
+fn throughput(c: &mut Criterion) {
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(error) => panic!("failed to build runtime: {error}"),
+    };
+    let Some(capacity) = NonZero::new(CALLS) else { unreachable!() };
+
+    match ExclusiveInvoker::spawn(capacity) {
+        Ok(invoker) => EXCLUSIVE.sync_api().initialize(invoker),
+        Err(error) => panic!("failed to spawn exclusive invoker: {error}"),
+    }
+    match Invoker::spawn_with_runtime("shared", capacity, async |value| self::handle(value)) {
+        Ok(invoker) => SHARED.sync_api().initialize(invoker),
+        Err(error) => panic!("failed to spawn shared invoker: {error}"),
+    }
+
+    self::compare(c, &runtime);
+
+    EXCLUSIVE.sync_api().close();
+    SHARED.sync_api().close();
+}
+
+/// Contains the benchmark group, which is generated by criterion.
+mod group {
+    criterion::criterion_group!(benches, super::throughput);
+}
+
+criterion_main!(group::benches);
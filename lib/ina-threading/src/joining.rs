@@ -19,6 +19,7 @@ use std::ops::{Deref, DerefMut};
 use tracing::debug;
 
 use crate::Handle;
+use crate::metrics::Metrics;
 
 /// A thread that is automatically joined when dropped.
 #[must_use = "this thread will drop and attempt to join immediately if left unused"]
@@ -130,6 +131,10 @@ where
     fn into_join_handle(mut self) -> std::thread::JoinHandle<Self::Output> {
         self.handle.take().expect("attempted to access a dropped thread").into_join_handle()
     }
+
+    fn metrics(&self) -> Option<Metrics> {
+        self.handle.as_ref().and_then(Handle::metrics)
+    }
 }
 
 impl<H> Deref for Joining<H>
@@ -16,17 +16,27 @@
 
 //! Provides concurrency solutions for 1N4.
 
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
 use std::thread::{Builder, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tokio::sync::RwLock;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tracing::{Instrument, debug, trace_span};
 
+use crate::metrics::Metrics;
+use crate::supervisor::Supervisor;
+
 /// Defines wrappers for join-on-drop threads.
 pub mod joining;
+/// Defines runtime metrics that are recorded by threads.
+pub mod metrics;
 /// Defines wrappers for threads that are stored statically.
 pub mod statics;
+/// Defines supervision of threads that are restarted after exiting.
+pub mod supervisor;
 
 /// Defines specialized thread implementations.
 pub mod threads {
@@ -36,6 +46,8 @@ pub mod threads {
     pub mod exchanger;
     /// Defines threads that can be called like functions.
     pub mod invoker;
+    /// Defines pools of threads that can be called like functions.
+    pub mod pool;
     /// Defines threads that can send values.
     pub mod producer;
 }
@@ -73,6 +85,11 @@ pub trait Handle {
     fn thread_name(&self) -> &str {
         self.as_join_handle().thread().name().unwrap_or("<anonymous>")
     }
+
+    /// Returns the thread's current runtime metrics, if it records any.
+    fn metrics(&self) -> Option<Metrics> {
+        None
+    }
 }
 
 /// A [`Handle`] type where the running thread may receive values through a [`Sender<T>`].
@@ -171,29 +188,90 @@ where
     /// # Errors
     ///
     /// This function will return an error if the thread fails to spawn.
-    #[expect(clippy::expect_used, reason = "if the runtime fails to spawn, we can't run the thread body")]
-    #[expect(clippy::missing_panics_doc, reason = "the panic does not cause a crash, only stops the thread")]
     pub fn spawn_with_runtime<N, F, O>(name: N, f: F) -> Result<Self>
     where
         N: AsRef<str>,
         F: FnOnce() -> O + Send + 'static,
         O: Future<Output = T>,
     {
-        Self::spawn(name, || {
-            use tokio::runtime::Builder;
-
-            let runtime = Builder::new_current_thread().enable_all().build().expect("failed to spawn runtime");
-            let id = runtime.handle().id();
-            debug!(%id, "initialized single-thread asynchronous runtime");
+        Self::spawn(name, || self::block_on_runtime(f()))
+    }
 
-            let result = runtime.block_on(f().instrument(trace_span!("rt_s")));
-            debug!(%id, "exiting asynchronous runtime");
+    /// Spawns a new [`Thread`] with the given name and task, which is restarted by the given supervisor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use ina_threading::{Handle, Thread};
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let mut attempts = 0;
+    /// let thread = Thread::spawn_supervised("worker", supervisor, move || {
+    ///     attempts += 1;
+    ///
+    ///     assert!(attempts > 2, "the first two attempts panic");
+    ///
+    ///     attempts
+    /// })?;
+    ///
+    /// assert_eq!(thread.into_join_handle().join().unwrap(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised<N, F>(name: N, supervisor: Supervisor, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let thread_name = name.as_ref().to_owned();
 
-            runtime.shutdown_timeout(*self::RUNTIME_TIMEOUT.blocking_read());
-            debug!(%id, "shut down asynchronous runtime");
+        Self::spawn(name, move || supervisor.supervise(&thread_name, f))
+    }
 
-            result
-        })
+    /// Spawns a new [`Thread`] with the given name and asynchronous task, which is restarted by the given supervisor.
+    ///
+    /// A new runtime is created for every run of the task. Each runtime has both IO and time drivers enabled, and is
+    /// configured to only run on the spawned thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use ina_threading::{Handle, Thread};
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let supervisor = Supervisor::new(Restart::Always)
+    ///     .with_backoff(Duration::ZERO, Duration::ZERO)
+    ///     .with_max_restarts(2);
+    /// let mut attempts = 0;
+    /// let thread = Thread::spawn_supervised_with_runtime("worker", supervisor, move || {
+    ///     attempts += 1;
+    ///
+    ///     async move { attempts }
+    /// })?;
+    ///
+    /// // The task is run once, and is then restarted twice.
+    /// assert_eq!(thread.into_join_handle().join().unwrap(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised_with_runtime<N, F, O>(name: N, supervisor: Supervisor, mut f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: FnMut() -> O + Send + 'static,
+        O: Future<Output = T>,
+    {
+        Self::spawn_supervised(name, supervisor, move || self::block_on_runtime(f()))
     }
 }
 
@@ -216,6 +294,102 @@ where
     }
 }
 
+/// Creates a new runtime on the current thread and runs the given future to completion, returning its output.
+///
+/// The created runtime has both IO and time drivers enabled.
+#[expect(clippy::expect_used, reason = "if the runtime fails to spawn, we can't run the thread body")]
+pub(crate) fn block_on_runtime<O: Future>(future: O) -> O::Output {
+    use tokio::runtime::Builder;
+
+    let runtime = Builder::new_current_thread().enable_all().build().expect("failed to spawn runtime");
+    let id = runtime.handle().id();
+    debug!(%id, "initialized single-thread asynchronous runtime");
+
+    let result = runtime.block_on(future.instrument(trace_span!("rt_s")));
+    debug!(%id, "exiting asynchronous runtime");
+
+    runtime.shutdown_timeout(*self::RUNTIME_TIMEOUT.blocking_read());
+    debug!(%id, "shut down asynchronous runtime");
+
+    result
+}
+
+/// Wakes a thread that is blocked on a future by unparking it.
+#[repr(transparent)]
+#[derive(Debug)]
+struct Unparker {
+    /// The blocked thread.
+    thread: std::thread::Thread,
+}
+
+impl Wake for Unparker {
+    fn wake(self: Arc<Self>) {
+        self.thread.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.thread.unpark();
+    }
+}
+
+/// Blocks the current thread until the given future completes, returning its output.
+///
+/// Unlike [`block_on_runtime`], no runtime is created, so the future may not rely on a runtime's IO or time drivers.
+/// This is intended for waiting on synchronization primitives, such as channels, from outside of a runtime.
+///
+/// # Examples
+///
+/// ```
+/// let (sender, receiver) = tokio::sync::oneshot::channel();
+///
+/// std::thread::spawn(move || sender.send(2 + 2));
+///
+/// assert_eq!(ina_threading::block_on(receiver), Ok(4));
+/// ```
+pub fn block_on<O: Future>(future: O) -> O::Output {
+    self::block_on_until(future, None).unwrap_or_else(|| unreachable!("the future is awaited without a deadline"))
+}
+
+/// Blocks the current thread until the given future completes or the given timeout elapses, returning its output if it
+/// completed in time.
+///
+/// See [`block_on`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+///
+/// assert_eq!(ina_threading::block_on_timeout(receiver, Duration::from_millis(10)), None);
+/// # drop(sender);
+/// ```
+pub fn block_on_timeout<O: Future>(future: O, timeout: Duration) -> Option<O::Output> {
+    // A timeout that cannot be represented as an instant is far enough away that it will never elapse.
+    self::block_on_until(future, Instant::now().checked_add(timeout))
+}
+
+/// Blocks the current thread until the given future completes or the given deadline passes, returning its output if it
+/// completed in time.
+fn block_on_until<O: Future>(future: O, deadline: Option<Instant>) -> Option<O::Output> {
+    let waker = Waker::from(Arc::new(Unparker { thread: std::thread::current() }));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return Some(output);
+        }
+
+        // Parking may end spuriously, in which case the future is simply polled again.
+        match deadline.map(|deadline| deadline.saturating_duration_since(Instant::now())) {
+            None => std::thread::park(),
+            Some(remaining) if remaining.is_zero() => return None,
+            Some(remaining) => std::thread::park_timeout(remaining),
+        }
+    }
+}
+
 /// Sets the asynchronous runtime timeout for any spawned threads to the given value.
 pub async fn set_runtime_timeout(duration: Duration) {
     *self::RUNTIME_TIMEOUT.write().await = duration;
@@ -0,0 +1,376 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+/// The number of bits used to divide each power of two within a [`Histogram`].
+const SUB_BUCKET_BITS: u32 = 3;
+/// The number of buckets that each power of two is divided into within a [`Histogram`].
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+/// The total number of buckets within a [`Histogram`], which covers every possible number of nanoseconds.
+const BUCKETS: usize = ((u64::BITS - SUB_BUCKET_BITS + 1) << SUB_BUCKET_BITS) as usize;
+
+/// The runtime metrics of a thread at a point in time.
+///
+/// Every thread that receives values through a channel reports the state of its queue. Invokers count a call as each
+/// value is sent, and the duration spent handling it once the thread responds. Consumers and exchangers count a call as
+/// each value is received by their task through a [`MeteredReceiver<T>`], and the duration spent handling it once the
+/// task next receives a value, while producers only report the state of their queue.
+///
+/// # Examples
+///
+/// ```
+/// # use std::num::NonZero;
+/// # use ina_threading::Handle;
+/// # use ina_threading::threads::invoker::Invoker;
+/// # fn main() -> ina_threading::Result<()> {
+/// let capacity = NonZero::<usize>::new(4).unwrap();
+/// let thread = Invoker::spawn("worker", capacity, |n: u32| n * 2)?;
+///
+/// thread.blocking_call(123).expect("the channel should not be closed");
+///
+/// let metrics = thread.metrics().expect("invokers record metrics");
+///
+/// assert_eq!(metrics.calls, 1);
+/// assert_eq!(metrics.errors, 0);
+/// assert_eq!(metrics.capacity, 4);
+/// assert!(metrics.p50.is_some());
+/// # Ok(())
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// The number of values that were sent into the thread.
+    pub calls: u64,
+    /// The number of values that the thread finished handling.
+    pub handled: u64,
+    /// The number of calls that failed, including those that timed out.
+    pub errors: u64,
+    /// The number of values that are waiting within the thread's queue.
+    pub queued: usize,
+    /// The maximum number of values that may wait within the thread's queue.
+    pub capacity: usize,
+    /// The median duration spent handling a value, if any have been handled.
+    pub p50: Option<Duration>,
+    /// The 99th percentile duration spent handling a value, if any have been handled.
+    pub p99: Option<Duration>,
+}
+
+impl Metrics {
+    /// Creates a new [`Metrics`] that only describes the queue of the given sending channel.
+    pub(crate) fn from_sender<T>(sender: &Sender<T>) -> Self {
+        let capacity = sender.max_capacity();
+
+        Self { queued: capacity - sender.capacity(), capacity, ..Self::default() }
+    }
+
+    /// Creates a new [`Metrics`] that only describes the queue of the given receiving channel.
+    pub(crate) fn from_receiver<T>(receiver: &Receiver<T>) -> Self {
+        Self { queued: receiver.len(), capacity: receiver.max_capacity(), ..Self::default() }
+    }
+
+    /// Returns the fraction of the thread's queue that is currently in use, between `0.0` and `1.0`.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss, reason = "queue capacities are far smaller than the lossless range")]
+    pub fn queue_usage(&self) -> f64 {
+        if self.capacity == 0 { 0.0 } else { self.queued as f64 / self.capacity as f64 }
+    }
+}
+
+/// Records the runtime metrics of a thread.
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    /// The number of values that were sent into the thread.
+    calls: AtomicU64,
+    /// The number of values that the thread finished handling.
+    handled: AtomicU64,
+    /// The number of calls that failed.
+    errors: AtomicU64,
+    /// The durations spent handling values.
+    durations: Histogram,
+}
+
+impl Recorder {
+    /// Records that a value was sent into the thread.
+    pub(crate) fn record_call(&self) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that a call failed.
+    pub(crate) fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that the thread finished handling a value after the given duration.
+    pub(crate) fn record_handled(&self, elapsed: Duration) {
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        self.durations.record(elapsed);
+    }
+
+    /// Returns the recorded metrics, alongside the state of the queue of the given sending channel.
+    pub(crate) fn metrics<T>(&self, sender: &Sender<T>) -> Metrics {
+        Metrics {
+            calls: self.calls.load(Ordering::Relaxed),
+            handled: self.handled.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            p50: self.durations.quantile(0.5),
+            p99: self.durations.quantile(0.99),
+            ..Metrics::from_sender(sender)
+        }
+    }
+}
+
+/// A receiving channel that records the runtime metrics of the thread that receives from it.
+///
+/// A value is counted as a call once it is received, and is counted as handled once the thread next receives from the
+/// channel, or once the channel is dropped. If the thread panics before then, the value is counted as an error instead.
+/// Methods of the inner [`Receiver<T>`] that are not provided by this type do not record any metrics.
+///
+/// # Examples
+///
+/// ```
+/// # use std::num::NonZero;
+/// # use ina_threading::{Handle, SenderHandle};
+/// # use ina_threading::threads::consumer::Consumer;
+/// # fn main() -> ina_threading::Result<()> {
+/// let capacity = NonZero::<usize>::new(2).unwrap();
+/// let thread = Consumer::spawn("worker", capacity, |mut r| {
+///     while let Some(n) = r.blocking_recv() {
+///         assert_ne!(n, 0, "zero is not accepted");
+///     }
+/// })?;
+///
+/// thread.as_sender().blocking_send(123).expect("the channel should not be closed");
+/// thread.as_sender().blocking_send(0).expect("the channel should not be closed");
+///
+/// while !thread.as_join_handle().is_finished() {
+///     std::thread::yield_now();
+/// }
+///
+/// let metrics = thread.metrics().expect("consumers record metrics");
+///
+/// assert_eq!(metrics.calls, 2);
+/// assert_eq!(metrics.handled, 1);
+/// assert_eq!(metrics.errors, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MeteredReceiver<T> {
+    /// The inner receiving channel.
+    inner: Receiver<T>,
+    /// The thread's runtime metrics.
+    recorder: Arc<Recorder>,
+    /// When the value that is being handled was received, if any.
+    received: Option<Instant>,
+}
+
+impl<T> MeteredReceiver<T> {
+    /// Creates a new [`MeteredReceiver<T>`] that records metrics of the given channel within the given recorder.
+    pub(crate) const fn new(inner: Receiver<T>, recorder: Arc<Recorder>) -> Self {
+        Self { inner, recorder, received: None }
+    }
+
+    /// Receives the next value from the channel, counting the previously received value as handled.
+    ///
+    /// See [`Receiver::recv`] for more details.
+    pub async fn recv(&mut self) -> Option<T> {
+        self.finish();
+
+        let value = self.inner.recv().await;
+
+        self.start(value.is_some());
+
+        value
+    }
+
+    /// Receives the next value from the channel, blocking the current thread until one is available and counting the
+    /// previously received value as handled.
+    ///
+    /// See [`Receiver::blocking_recv`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        self.finish();
+
+        let value = self.inner.blocking_recv();
+
+        self.start(value.is_some());
+
+        value
+    }
+
+    /// Tries to receive the next value from the channel without waiting, counting the previously received value as
+    /// handled.
+    ///
+    /// See [`Receiver::try_recv`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the channel is empty or closed.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.finish();
+
+        let value = self.inner.try_recv();
+
+        self.start(value.is_ok());
+
+        value
+    }
+
+    /// Runs the given task, which receives from this channel.
+    ///
+    /// A value that was received by an earlier task that panicked before receiving again is counted as an error, and a
+    /// value that the given task received before returning is counted as handled.
+    pub(crate) fn run<O>(&mut self, f: impl FnOnce(&mut Self) -> O) -> O {
+        if self.received.take().is_some() {
+            self.recorder.record_error();
+        }
+
+        let output = f(self);
+
+        self.finish();
+
+        output
+    }
+
+    /// Counts the value that is being handled, if any, as handled.
+    fn finish(&mut self) {
+        if let Some(received) = self.received.take() {
+            self.recorder.record_handled(received.elapsed());
+        }
+    }
+
+    /// Counts a value as received, if one was received.
+    fn start(&mut self, received: bool) {
+        if received {
+            self.recorder.record_call();
+            self.received = Some(Instant::now());
+        }
+    }
+}
+
+impl<T> Deref for MeteredReceiver<T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for MeteredReceiver<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> Drop for MeteredReceiver<T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() && self.received.take().is_some() {
+            self.recorder.record_error();
+        } else {
+            self.finish();
+        }
+    }
+}
+
+/// A histogram of durations with logarithmically sized buckets.
+///
+/// Each power of two of nanoseconds is divided into [`SUB_BUCKETS`] buckets of equal size, so a reported quantile is
+/// within 12.5% of the recorded duration.
+#[derive(Debug)]
+struct Histogram {
+    /// The number of durations recorded within each bucket.
+    buckets: Box<[AtomicU64]>,
+}
+
+impl Histogram {
+    /// Records the given duration.
+    fn record(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+
+        self.buckets[Self::index(nanos)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the duration below which the given fraction of recorded durations fall, if any have been recorded.
+    ///
+    /// The returned duration is the upper bound of the bucket that contains the quantile.
+    fn quantile(&self, quantile: f64) -> Option<Duration> {
+        let counts = self.buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect::<Box<[_]>>();
+        let total = counts.iter().sum::<u64>();
+
+        if total == 0 {
+            return None;
+        }
+
+        #[expect(clippy::cast_precision_loss, reason = "the rank is an approximation regardless")]
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "the rank is within `1 ..= total`")]
+        let rank = ((quantile * total as f64).ceil() as u64).clamp(1, total);
+        let mut seen = 0;
+
+        counts
+            .iter()
+            .position(|count| {
+                seen += count;
+
+                seen >= rank
+            })
+            .map(|index| Duration::from_nanos(Self::upper_bound(index)))
+    }
+
+    /// Returns the index of the bucket that contains the given number of nanoseconds.
+    #[expect(clippy::cast_possible_truncation, reason = "indices are always less than `BUCKETS`")]
+    const fn index(nanos: u64) -> usize {
+        if nanos < SUB_BUCKETS {
+            return nanos as usize;
+        }
+
+        let magnitude = u64::BITS - 1 - nanos.leading_zeros();
+        let shift = magnitude - SUB_BUCKET_BITS;
+        let offset = (nanos >> shift) & (SUB_BUCKETS - 1);
+
+        ((shift + 1) as usize) * (SUB_BUCKETS as usize) + offset as usize
+    }
+
+    /// Returns the largest number of nanoseconds that is contained within the bucket at the given index.
+    const fn upper_bound(index: usize) -> u64 {
+        let group = (index as u64) / SUB_BUCKETS;
+        let offset = (index as u64) % SUB_BUCKETS;
+
+        if group == 0 {
+            return offset;
+        }
+
+        let width = 1 << (group - 1);
+
+        ((SUB_BUCKETS + offset) << (group - 1)).saturating_add(width - 1)
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self { buckets: std::iter::repeat_with(AtomicU64::default).take(BUCKETS).collect() }
+    }
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, error, info, warn};
+
+use crate::metrics::MeteredReceiver;
+
+/// Determines when a supervised thread is restarted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Restart {
+    /// The thread is never restarted.
+    Never,
+    /// The thread is restarted if it panics.
+    #[default]
+    OnPanic,
+    /// The thread is restarted whenever it exits.
+    Always,
+}
+
+impl Restart {
+    /// Returns `true` if a thread that exited, possibly by panicking, should be restarted.
+    #[must_use]
+    pub const fn applies(self, panicked: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnPanic => panicked,
+            Self::Always => true,
+        }
+    }
+}
+
+/// The configuration of a supervised thread, which restarts the thread's task after it exits.
+///
+/// Each restart is delayed by an exponential backoff, which starts at the initial delay and doubles after every
+/// restart, up to the maximum delay. The backoff is reset once the task has run for at least the maximum delay without
+/// exiting.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ina_threading::supervisor::{Restart, Supervisor};
+/// let supervisor = Supervisor::new(Restart::OnPanic)
+///     .with_backoff(Duration::from_millis(100), Duration::from_secs(10))
+///     .with_max_restarts(5);
+///
+/// assert_eq!(supervisor.delay(0), Duration::from_millis(100));
+/// assert_eq!(supervisor.delay(3), Duration::from_millis(800));
+/// assert_eq!(supervisor.delay(10), Duration::from_secs(10));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Supervisor {
+    /// Determines when the thread is restarted.
+    restart: Restart,
+    /// The delay before the first restart.
+    initial_delay: Duration,
+    /// The maximum delay before any restart.
+    maximum_delay: Duration,
+    /// The maximum number of restarts, if any.
+    max_restarts: Option<usize>,
+}
+
+impl Supervisor {
+    /// The default delay before the first restart.
+    pub const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(100);
+    /// The default maximum delay before any restart.
+    pub const DEFAULT_MAXIMUM_DELAY: Duration = Duration::from_secs(30);
+
+    /// Creates a new [`Supervisor`] with the given restart policy.
+    ///
+    /// By default, the thread may be restarted any number of times.
+    #[must_use]
+    pub const fn new(restart: Restart) -> Self {
+        Self {
+            restart,
+            initial_delay: Self::DEFAULT_INITIAL_DELAY,
+            maximum_delay: Self::DEFAULT_MAXIMUM_DELAY,
+            max_restarts: None,
+        }
+    }
+
+    /// Sets the initial and maximum delays of the supervisor's exponential backoff.
+    #[must_use]
+    pub const fn with_backoff(mut self, initial: Duration, maximum: Duration) -> Self {
+        self.initial_delay = initial;
+        self.maximum_delay = maximum;
+
+        self
+    }
+
+    /// Sets the maximum number of times that the thread may be restarted.
+    #[must_use]
+    pub const fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = Some(max_restarts);
+
+        self
+    }
+
+    /// Returns the supervisor's restart policy.
+    #[must_use]
+    pub const fn restart(&self) -> Restart {
+        self.restart
+    }
+
+    /// Returns the delay before the restart that follows the given number of consecutive restarts.
+    #[must_use]
+    pub fn delay(&self, consecutive: u32) -> Duration {
+        let factor = 1_u32.checked_shl(consecutive).unwrap_or(u32::MAX);
+
+        self.initial_delay.saturating_mul(factor).min(self.maximum_delay)
+    }
+
+    /// Runs the given task on the current thread, restarting it according to this supervisor's configuration.
+    ///
+    /// If the task panics and is not restarted, the panic is resumed.
+    pub(crate) fn supervise<T>(&self, name: &str, mut f: impl FnMut() -> T) -> T {
+        self.supervise_with(name, &mut (), |()| f(), |()| false)
+    }
+
+    /// Runs the given task on the current thread with the given context, restarting it according to this supervisor's
+    /// configuration until the given function reports that the task is finished.
+    ///
+    /// A finished task is never restarted, regardless of the restart policy. If the task panics and is not restarted,
+    /// the panic is resumed.
+    pub(crate) fn supervise_with<C, T>(
+        &self,
+        name: &str,
+        context: &mut C,
+        mut f: impl FnMut(&mut C) -> T,
+        finished: impl Fn(&C) -> bool,
+    ) -> T {
+        let mut restarts = 0_usize;
+        let mut consecutive = 0_u32;
+
+        loop {
+            let started = Instant::now();
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| f(context)));
+
+            if started.elapsed() >= self.maximum_delay {
+                consecutive = 0;
+            }
+
+            if finished(context) {
+                debug!(name, "thread finished and will not be restarted");
+
+                return result.unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+            }
+            if !self.restart.applies(result.is_err()) {
+                return result.unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+            }
+            if self.max_restarts.is_some_and(|max_restarts| restarts >= max_restarts) {
+                error!(name, restarts, "thread exceeded its maximum restarts");
+
+                return result.unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+            }
+
+            let delay = self.delay(consecutive);
+            let delay_ms = delay.as_secs_f64() * 1_000.0;
+
+            match &result {
+                Ok(_) => info!(name, restarts, delay_ms, "restarting exited thread"),
+                Err(payload) => {
+                    warn!(
+                        name,
+                        restarts,
+                        delay_ms,
+                        reason = self::panic_message(&**payload),
+                        "restarting panicked thread"
+                    );
+                }
+            }
+
+            drop(result);
+            std::thread::sleep(delay);
+
+            restarts += 1;
+            consecutive = consecutive.saturating_add(1);
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new(Restart::default())
+    }
+}
+
+/// Returns `true` if the given receiver's channel is closed and has no values left to receive, meaning that a task that
+/// receives from it has nothing left to do.
+pub(crate) fn is_drained<T>(receiver: &MeteredReceiver<T>) -> bool {
+    receiver.is_closed() && receiver.is_empty()
+}
+
+/// Returns the message of the given panic payload, if it contains one.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<unknown>")
+}
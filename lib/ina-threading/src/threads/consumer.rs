@@ -15,10 +15,13 @@
 // <https://www.gnu.org/licenses/>.
 
 use std::num::NonZero;
+use std::sync::Arc;
 
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc::Sender;
 use tracing::trace;
 
+use crate::metrics::{MeteredReceiver, Metrics, Recorder};
+use crate::supervisor::Supervisor;
 use crate::{Handle, Result, SenderHandle, Thread};
 
 /// A thread that receives values through a sender channel.
@@ -28,6 +31,8 @@ pub struct Consumer<S, T> {
     thread: Thread<T>,
     /// The inner sender channel.
     sender: Sender<S>,
+    /// The thread's runtime metrics.
+    recorder: Arc<Recorder>,
 }
 
 impl<S, T> Consumer<S, T>
@@ -62,12 +67,11 @@ where
     pub fn spawn<N, F>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
     where
         N: AsRef<str>,
-        F: FnOnce(Receiver<S>) -> T + Send + 'static,
+        F: FnOnce(MeteredReceiver<S>) -> T + Send + 'static,
     {
-        let (sender, receiver) = tokio::sync::mpsc::channel(capacity.get());
-        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (client->thread)");
+        let (sender, receiver, recorder) = self::channel(name.as_ref(), capacity);
 
-        Ok(Self { thread: Thread::spawn(name, || f(receiver))?, sender })
+        Ok(Self { thread: Thread::spawn(name, || f(receiver))?, sender, recorder })
     }
 
     /// Spawns a new [`Consumer<S, T>`] with the given name and asynchronous task.
@@ -99,14 +103,150 @@ where
     pub fn spawn_with_runtime<N, F, O>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
     where
         N: AsRef<str>,
-        F: FnOnce(Receiver<S>) -> O + Send + 'static,
+        F: FnOnce(MeteredReceiver<S>) -> O + Send + 'static,
         O: Future<Output = T>,
     {
-        let (sender, receiver) = tokio::sync::mpsc::channel(capacity.get());
-        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (client->thread)");
+        let (sender, receiver, recorder) = self::channel(name.as_ref(), capacity);
 
-        Ok(Self { thread: Thread::spawn_with_runtime(name, || f(receiver))?, sender })
+        Ok(Self { thread: Thread::spawn_with_runtime(name, || f(receiver))?, sender, recorder })
     }
+
+    /// Spawns a new [`Consumer<S, T>`] with the given name and task, which is restarted by the given supervisor.
+    ///
+    /// The task borrows the thread's channel, which remains open between restarts. Values that the task had received
+    /// before it panicked are lost. Once the channel is closed and every value has been received, the task is no longer
+    /// restarted, regardless of the supervisor's restart policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::sync::mpsc;
+    /// # use std::time::Duration;
+    /// # use ina_threading::{Handle, SenderHandle};
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::consumer::Consumer;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::Always).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let (sender, receiver) = mpsc::channel();
+    /// let thread = Consumer::spawn_supervised("worker", capacity, supervisor, move |r| {
+    ///     while let Some(n) = r.blocking_recv() {
+    ///         assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///         sender.send(n * 2).expect("the channel should not be closed");
+    ///     }
+    /// })?;
+    ///
+    /// thread.as_sender().blocking_send(0).expect("the channel should not be closed");
+    /// thread.as_sender().blocking_send(123).expect("the channel should not be closed");
+    ///
+    /// // The thread is restarted after panicking, and continues to receive values.
+    /// assert_eq!(receiver.recv(), Ok(246));
+    ///
+    /// // Closing the channel stops the thread, even though it would otherwise always be restarted.
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised<N, F>(name: N, capacity: NonZero<usize>, supervisor: Supervisor, mut f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: FnMut(&mut MeteredReceiver<S>) -> T + Send + 'static,
+    {
+        let (sender, mut receiver, recorder) = self::channel(name.as_ref(), capacity);
+        let thread_name = name.as_ref().to_owned();
+        let thread = Thread::spawn(name, move || {
+            let f = |receiver: &mut MeteredReceiver<S>| receiver.run(&mut f);
+
+            supervisor.supervise_with(&thread_name, &mut receiver, f, crate::supervisor::is_drained)
+        })?;
+
+        Ok(Self { thread, sender, recorder })
+    }
+
+    /// Spawns a new [`Consumer<S, T>`] with the given name and asynchronous task, which is restarted by the given
+    /// supervisor.
+    ///
+    /// The task borrows the thread's channel, which remains open between restarts. Values that the task had received
+    /// before it panicked are lost. Once the channel is closed and every value has been received, the task is no longer
+    /// restarted, regardless of the supervisor's restart policy. A new runtime is created for every run of the task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::sync::mpsc;
+    /// # use std::time::Duration;
+    /// # use ina_threading::SenderHandle;
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::consumer::Consumer;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let (sender, receiver) = mpsc::channel();
+    /// let thread =
+    ///     Consumer::spawn_supervised_with_runtime("worker", capacity, supervisor, async move |r| {
+    ///         while let Some(n) = r.recv().await {
+    ///             assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///             sender.send(n * 2).expect("the channel should not be closed");
+    ///         }
+    ///     })?;
+    ///
+    /// thread.as_sender().blocking_send(0).expect("the channel should not be closed");
+    /// thread.as_sender().blocking_send(123).expect("the channel should not be closed");
+    ///
+    /// // The thread is restarted after panicking, and continues to receive values.
+    /// assert_eq!(receiver.recv(), Ok(246));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised_with_runtime<N, F>(
+        name: N,
+        capacity: NonZero<usize>,
+        supervisor: Supervisor,
+        mut f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: AsyncFnMut(&mut MeteredReceiver<S>) -> T + Send + 'static,
+    {
+        let (sender, mut receiver, recorder) = self::channel(name.as_ref(), capacity);
+        let thread_name = name.as_ref().to_owned();
+        let thread = Thread::spawn(name, move || {
+            let f = |receiver: &mut MeteredReceiver<S>| receiver.run(|r| crate::block_on_runtime(f(r)));
+
+            supervisor.supervise_with(&thread_name, &mut receiver, f, crate::supervisor::is_drained)
+        })?;
+
+        Ok(Self { thread, sender, recorder })
+    }
+
+    /// Creates a new [`Consumer<S, T>`] from the given thread and the sender of the channel that it receives from.
+    ///
+    /// The thread is expected to record its own metrics, so the returned consumer does not record any.
+    pub(crate) fn from_parts(thread: Thread<T>, sender: Sender<S>) -> Self {
+        Self { thread, sender, recorder: Arc::default() }
+    }
+}
+
+/// Opens the channel of a thread with the given name and capacity, returning its sender, its metered receiver, and the
+/// recorder of the thread's metrics.
+pub(super) fn channel<S>(name: &str, capacity: NonZero<usize>) -> (Sender<S>, MeteredReceiver<S>, Arc<Recorder>) {
+    let (sender, receiver) = tokio::sync::mpsc::channel(capacity.get());
+    trace!(name, capacity, "opened mpsc channel (client->thread)");
+    let recorder = Arc::new(Recorder::default());
+
+    (sender, MeteredReceiver::new(receiver, Arc::clone(&recorder)), recorder)
 }
 
 impl<S, T> Handle for Consumer<S, T>
@@ -126,6 +266,10 @@ where
     fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
         self.thread.into_join_handle()
     }
+
+    fn metrics(&self) -> Option<Metrics> {
+        Some(self.recorder.metrics(&self.sender))
+    }
 }
 
 impl<S, T> SenderHandle<S> for Consumer<S, T>
@@ -15,10 +15,13 @@
 // <https://www.gnu.org/licenses/>.
 
 use std::num::NonZero;
+use std::sync::Arc;
 
 use tokio::sync::mpsc::{Receiver, Sender};
 use tracing::trace;
 
+use crate::metrics::{MeteredReceiver, Metrics, Recorder};
+use crate::supervisor::Supervisor;
 use crate::{Handle, ReceiverHandle, Result, SenderHandle, Thread};
 
 /// A thread that both consumes and produces values through channels.
@@ -30,6 +33,8 @@ pub struct Exchanger<S, R, T> {
     sender: Sender<S>,
     /// The inner receiving channel.
     receiver: Receiver<R>,
+    /// The thread's runtime metrics.
+    recorder: Arc<Recorder>,
 }
 
 impl<S, R, T> Exchanger<S, R, T>
@@ -68,15 +73,14 @@ where
     pub fn spawn<N, F>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
     where
         N: AsRef<str>,
-        F: FnOnce(Sender<R>, Receiver<S>) -> T + Send + 'static,
+        F: FnOnce(Sender<R>, MeteredReceiver<S>) -> T + Send + 'static,
     {
-        let (local_sender, thread_receiver) = tokio::sync::mpsc::channel(capacity.get());
-        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (client->thread)");
+        let (local_sender, thread_receiver, recorder) = super::consumer::channel(name.as_ref(), capacity);
         let (thread_sender, local_receiver) = tokio::sync::mpsc::channel(capacity.get());
         trace!(name = %name.as_ref(), capacity, "opened mpsc channel (thread->client)");
         let thread = Thread::spawn(name, move || f(thread_sender, thread_receiver))?;
 
-        Ok(Self { thread, sender: local_sender, receiver: local_receiver })
+        Ok(Self { thread, sender: local_sender, receiver: local_receiver, recorder })
     }
 
     /// Spawns a new [`Exchanger<S, R, T>`] with the given name and asynchronous task.
@@ -111,16 +115,133 @@ where
     pub fn spawn_with_runtime<N, F, O>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
     where
         N: AsRef<str>,
-        F: FnOnce(Sender<R>, Receiver<S>) -> O + Send + 'static,
+        F: FnOnce(Sender<R>, MeteredReceiver<S>) -> O + Send + 'static,
         O: Future<Output = T> + Send,
     {
-        let (local_sender, thread_receiver) = tokio::sync::mpsc::channel(capacity.get());
-        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (client->thread)");
+        let (local_sender, thread_receiver, recorder) = super::consumer::channel(name.as_ref(), capacity);
         let (thread_sender, local_receiver) = tokio::sync::mpsc::channel(capacity.get());
         trace!(name = %name.as_ref(), capacity, "opened mpsc channel (thread->client)");
         let thread = Thread::spawn_with_runtime(name, || f(thread_sender, thread_receiver))?;
 
-        Ok(Self { thread, sender: local_sender, receiver: local_receiver })
+        Ok(Self { thread, sender: local_sender, receiver: local_receiver, recorder })
+    }
+
+    /// Spawns a new [`Exchanger<S, R, T>`] with the given name and task, which is restarted by the given supervisor.
+    ///
+    /// The task borrows the thread's channels, which remain open between restarts. Values that the task had received
+    /// before it panicked are lost. Once the receiving channel is closed and every value has been received, the task is
+    /// no longer restarted, regardless of the supervisor's restart policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::{Handle, ReceiverHandle, SenderHandle};
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::exchanger::Exchanger;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let mut thread = Exchanger::spawn_supervised("worker", capacity, supervisor, |s, r| {
+    ///     while let Some(n) = r.blocking_recv() {
+    ///         assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///         s.blocking_send(n * 2).expect("the channel should not be closed");
+    ///     }
+    /// })?;
+    ///
+    /// thread.as_sender().blocking_send(0).expect("the channel should not be closed");
+    /// thread.as_sender().blocking_send(123).expect("the channel should not be closed");
+    ///
+    /// // The thread is restarted after panicking, and continues to receive values.
+    /// assert_eq!(thread.as_receiver_mut().blocking_recv(), Some(246));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised<N, F>(name: N, capacity: NonZero<usize>, supervisor: Supervisor, mut f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: FnMut(&Sender<R>, &mut MeteredReceiver<S>) -> T + Send + 'static,
+    {
+        let (local_sender, mut thread_receiver, recorder) = super::consumer::channel(name.as_ref(), capacity);
+        let (thread_sender, local_receiver) = tokio::sync::mpsc::channel(capacity.get());
+        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (thread->client)");
+        let thread_name = name.as_ref().to_owned();
+        let thread = Thread::spawn(name, move || {
+            let f = |receiver: &mut MeteredReceiver<S>| receiver.run(|r| f(&thread_sender, r));
+
+            supervisor.supervise_with(&thread_name, &mut thread_receiver, f, crate::supervisor::is_drained)
+        })?;
+
+        Ok(Self { thread, sender: local_sender, receiver: local_receiver, recorder })
+    }
+
+    /// Spawns a new [`Exchanger<S, R, T>`] with the given name and asynchronous task, which is restarted by the given
+    /// supervisor.
+    ///
+    /// The task borrows the thread's channels, which remain open between restarts. Values that the task had received
+    /// before it panicked are lost. Once the receiving channel is closed and every value has been received, the task is
+    /// no longer restarted, regardless of the supervisor's restart policy. A new runtime is created for every run of
+    /// the task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::{Handle, ReceiverHandle, SenderHandle};
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::exchanger::Exchanger;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let mut thread =
+    ///     Exchanger::spawn_supervised_with_runtime("worker", capacity, supervisor, async |s, r| {
+    ///         while let Some(n) = r.recv().await {
+    ///             assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///             s.send(n * 2).await.expect("the channel should not be closed");
+    ///         }
+    ///     })?;
+    ///
+    /// thread.as_sender().blocking_send(0).expect("the channel should not be closed");
+    /// thread.as_sender().blocking_send(123).expect("the channel should not be closed");
+    ///
+    /// // The thread is restarted after panicking, and continues to receive values.
+    /// assert_eq!(thread.as_receiver_mut().blocking_recv(), Some(246));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised_with_runtime<N, F>(
+        name: N,
+        capacity: NonZero<usize>,
+        supervisor: Supervisor,
+        mut f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: AsyncFnMut(&Sender<R>, &mut MeteredReceiver<S>) -> T + Send + 'static,
+    {
+        let (local_sender, mut thread_receiver, recorder) = super::consumer::channel(name.as_ref(), capacity);
+        let (thread_sender, local_receiver) = tokio::sync::mpsc::channel(capacity.get());
+        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (thread->client)");
+        let thread_name = name.as_ref().to_owned();
+        let thread = Thread::spawn(name, move || {
+            let f = |receiver: &mut MeteredReceiver<S>| receiver.run(|r| crate::block_on_runtime(f(&thread_sender, r)));
+
+            supervisor.supervise_with(&thread_name, &mut thread_receiver, f, crate::supervisor::is_drained)
+        })?;
+
+        Ok(Self { thread, sender: local_sender, receiver: local_receiver, recorder })
     }
 }
 
@@ -141,6 +262,10 @@ where
     fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
         self.thread.into_join_handle()
     }
+
+    fn metrics(&self) -> Option<Metrics> {
+        Some(self.recorder.metrics(&self.sender))
+    }
 }
 
 impl<S, R, T> SenderHandle<S> for Exchanger<S, R, T>
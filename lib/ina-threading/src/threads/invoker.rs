@@ -14,42 +14,52 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
-use std::collections::BTreeMap;
+use std::marker::PhantomData;
 use std::num::NonZero;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use tokio::sync::mpsc::error::SendError;
-use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::{Instrument, trace, trace_span, warn};
+use tokio::sync::mpsc::{Permit, Sender};
+use tokio::sync::oneshot;
+use tokio::task::{JoinError, JoinSet};
+use tracing::{Instrument, error, trace, trace_span, warn};
 
-use super::exchanger::Exchanger;
-use crate::{Handle, ReceiverHandle, Result, SenderHandle};
+use super::consumer::Consumer;
+use crate::metrics::{MeteredReceiver, Metrics, Recorder};
+use crate::supervisor::Supervisor;
+use crate::{Handle, Result, SenderHandle};
 
 /// The thread type that is wrapped by an [`Invoker<S, R>`].
-pub(crate) type InvokerInner<S, R> = Exchanger<Tracked<S>, Tracked<R>, Result<(), CallError<S, R>>>;
+pub(crate) type InvokerInner<S, R> = Consumer<Tracked<S, R>, ()>;
 
 /// An error that may be returned when calling invoker threads.
 #[derive(Debug, thiserror::Error)]
 pub enum CallError<S, R> {
     /// Returned if a value cannot be sent into an invoker thread.
     #[error("unable to send into invoker thread: {0}")]
-    SendInto(SendError<Tracked<S>>),
-    /// Returned if a value cannot be returned from an invoker thread.
-    #[error("unable to receive from invoker thread: {0}")]
-    SendFrom(SendError<Tracked<R>>),
-    /// Returned if the thread's receiving channel was closed.
-    #[error("the thread's receiving channel was closed")]
+    SendInto(SendError<Tracked<S, R>>),
+    /// Returned if the thread stopped handling the value before returning a response, such as when it panicked.
+    #[error("the thread stopped handling the value before responding")]
+    Dropped,
+    /// Returned if the thread did not respond before the call's timeout elapsed.
+    #[error("the thread did not respond before the call timed out")]
+    TimedOut,
+    /// Returned if a slot within the thread's channel cannot be reserved, as the channel is closed.
+    #[error("the invoker thread's channel is closed")]
     Closed,
 }
 
-/// A value with an associated nonce for response tracking.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub struct Tracked<T> {
+/// A value with an associated nonce and response channel.
+#[derive(Debug)]
+pub struct Tracked<S, R> {
     /// The numeric nonce.
-    pub nonce: Option<usize>,
+    pub nonce: usize,
     /// The inner value.
-    pub value: T,
+    pub value: S,
+    /// The channel that the response is returned through, or [`None`] if the response is ignored.
+    pub reply: Option<oneshot::Sender<R>>,
 }
 
 /// A value that is tracked as an invoker's state.
@@ -65,14 +75,17 @@ where
 }
 
 /// A thread that consumes and returns values like a function.
+///
+/// Every value is sent with its own response channel, so the thread may be called through a shared reference by any
+/// number of tasks at once.
 #[derive(Debug)]
 pub struct Invoker<S, R> {
-    /// The inner exchanger thread.
-    exchanger: InvokerInner<S, R>,
-    /// A map that contains completed results.
-    completed: BTreeMap<usize, R>,
-    /// A sequence counter that tracks results.
+    /// The inner consumer thread.
+    consumer: InvokerInner<S, R>,
+    /// A sequence counter that identifies values.
     sequence: AtomicUsize,
+    /// The thread's runtime metrics.
+    recorder: Arc<Recorder>,
 }
 
 impl<S, R> Invoker<S, R>
@@ -90,7 +103,7 @@ where
     /// # use ina_threading::threads::invoker::Invoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn("worker", capacity, |n| {
+    /// let thread = Invoker::spawn("worker", capacity, |n| {
     ///     assert_eq!(n, 123);
     ///     456
     /// })?;
@@ -111,58 +124,1253 @@ where
         N: AsRef<str>,
         F: Fn(S) -> R + Send + 'static,
     {
-        let f = move |sender: Sender<Tracked<R>>, mut receiver: Receiver<Tracked<S>>| {
-            loop {
-                let Some(received) = receiver.blocking_recv() else { return Ok(()) };
-                trace!(nonce = received.nonce, "received input value");
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = move |mut receiver: MeteredReceiver<Tracked<S, R>>| {
+            while let Some(Tracked { nonce, value, reply }) = receiver.blocking_recv() {
+                trace!(nonce, "received input value");
 
-                let span = trace_span!("fn", nonce = received.nonce).entered();
+                let span = trace_span!("fn", nonce).entered();
                 trace!("started execution");
 
                 let instant = std::time::Instant::now();
-                let response = Tracked { nonce: received.nonce, value: f(received.value) };
-                trace!(elapsed_ms = instant.elapsed().as_secs_f64() * 1_000.0, "finished execution");
+                let response = f(value);
+                let elapsed = instant.elapsed();
+                trace!(elapsed_ms = elapsed.as_secs_f64() * 1_000.0, "finished execution");
 
                 drop(span);
 
-                if received.nonce.is_some() {
-                    sender.blocking_send(response).map_err(CallError::SendFrom)?;
-                    trace!(nonce = received.nonce, "sent return value");
-                }
+                thread_recorder.record_handled(elapsed);
+                self::respond(nonce, reply, response);
             }
         };
 
-        Ok(Self {
-            exchanger: Exchanger::spawn(name, capacity, f)?,
-            completed: BTreeMap::new(),
-            sequence: AtomicUsize::new(0),
-        })
+        Ok(Self::from_consumer(Consumer::spawn(name, capacity, f)?, recorder))
+    }
+
+    /// Spawns a new [`Invoker<S, R>`] with the given name and asynchronous task.
+    ///
+    /// The created runtime has both IO and time drivers enabled, and is configured to only run on the spawned thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn_with_runtime("worker", capacity, |n| async move {
+    ///     assert_eq!(n, 123);
+    ///
+    ///     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///
+    ///     456
+    /// })?;
+    ///
+    /// let response = thread.blocking_call(123).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(response, 456);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_with_runtime<N, F, O>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> O + Send + Sync + 'static,
+        O: Future<Output = R> + Send,
+    {
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = move |mut receiver: MeteredReceiver<Tracked<S, R>>| async move {
+            self::serve(&f, &mut receiver, &thread_recorder).await;
+        };
+
+        Ok(Self::from_consumer(Consumer::spawn_with_runtime(name, capacity, f)?, recorder))
+    }
+
+    /// Spawns a new [`Invoker<S, R>`] with the given name and asynchronous task, which handles values concurrently.
+    ///
+    /// Each value is passed into the task in the order that it was received, and the returned future is awaited
+    /// before the next value is received. This allows the task to wait until a value may be handled, for example to
+    /// preserve the order of related values. The future that it resolves to is then spawned, running concurrently
+    /// with the handling of other values, and all spawned futures are awaited before the thread exits. A panic while
+    /// handling a spawned future is logged, and only calls of that value return [`CallError::Dropped`].
+    ///
+    /// The created runtime has both IO and time drivers enabled, and is configured to only run on the spawned thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::{CallError, Invoker};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn_concurrent_with_runtime("worker", capacity, |n| async move {
+    ///     // Values are admitted one at a time, and are then handled concurrently.
+    ///     async move {
+    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///
+    ///         assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///         n * 2
+    ///     }
+    /// })?;
+    ///
+    /// // The panic only drops the value that caused it, and the thread continues to handle values.
+    /// assert!(matches!(thread.blocking_call(0), Err(CallError::Dropped)));
+    ///
+    /// let response = thread.blocking_call(123).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(response, 246);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_concurrent_with_runtime<N, F, O, P>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> O + Send + Sync + 'static,
+        O: Future<Output = P> + Send,
+        P: Future<Output = R> + Send + 'static,
+    {
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = move |mut receiver: MeteredReceiver<Tracked<S, R>>| async move {
+            self::serve_concurrent(&f, &mut receiver, &thread_recorder).await;
+        };
+
+        Ok(Self::from_consumer(Consumer::spawn_with_runtime(name, capacity, f)?, recorder))
+    }
+
+    /// Spawns a new [`Invoker<S, R>`] with the given name and asynchronous task, which is restarted by the given
+    /// supervisor.
+    ///
+    /// The thread's channel remains open between restarts, so the invoker may continue to be called after the task
+    /// panics. Calls of the value that was being handled when the task panicked return [`CallError::Dropped`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::invoker::{CallError, Invoker};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let thread = Invoker::spawn_supervised_with_runtime(
+    ///     "worker",
+    ///     capacity,
+    ///     supervisor,
+    ///     |n: u8| async move {
+    ///         assert_ne!(n, 0, "zero is not accepted");
+    ///
+    ///         n * 2
+    ///     },
+    /// )?;
+    ///
+    /// // The value that caused the panic never receives a response.
+    /// assert!(matches!(thread.blocking_call(0), Err(CallError::Dropped)));
+    ///
+    /// // The thread is restarted after panicking, and continues to handle values.
+    /// assert_eq!(thread.blocking_call(123).expect("the channel should not be closed"), 246);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised_with_runtime<N, F, O>(
+        name: N,
+        capacity: NonZero<usize>,
+        supervisor: Supervisor,
+        f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> O + Send + Sync + 'static,
+        O: Future<Output = R> + Send,
+    {
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = async move |receiver: &mut MeteredReceiver<Tracked<S, R>>| {
+            self::serve(&f, receiver, &thread_recorder).await;
+        };
+
+        Ok(Self::from_consumer(Consumer::spawn_supervised_with_runtime(name, capacity, supervisor, f)?, recorder))
+    }
+
+    /// Spawns a new [`Invoker<S, R>`] with the given name and asynchronous task, which handles values concurrently
+    /// and is restarted by the given supervisor.
+    ///
+    /// A panic while admitting a value restarts the task, and calls of every value that was being handled at the time
+    /// return [`CallError::Dropped`]. A panic within a spawned future only drops the value that it was handling. See
+    /// [`Invoker::spawn_concurrent_with_runtime`] and [`Invoker::spawn_supervised_with_runtime`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic);
+    /// let thread = Invoker::spawn_concurrent_supervised_with_runtime(
+    ///     "worker",
+    ///     capacity,
+    ///     supervisor,
+    ///     |n| async move { async move { n * 2 } },
+    /// )?;
+    ///
+    /// assert_eq!(thread.blocking_call(123).expect("the channel should not be closed"), 246);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_concurrent_supervised_with_runtime<N, F, O, P>(
+        name: N,
+        capacity: NonZero<usize>,
+        supervisor: Supervisor,
+        f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> O + Send + Sync + 'static,
+        O: Future<Output = P> + Send,
+        P: Future<Output = R> + Send + 'static,
+    {
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = async move |receiver: &mut MeteredReceiver<Tracked<S, R>>| {
+            self::serve_concurrent(&f, receiver, &thread_recorder).await;
+        };
+
+        Ok(Self::from_consumer(Consumer::spawn_supervised_with_runtime(name, capacity, supervisor, f)?, recorder))
+    }
+
+    /// Creates a new [`Invoker<S, R>`] that sends values into the given consumer thread, which records its metrics
+    /// into the given recorder.
+    pub(crate) const fn from_consumer(consumer: InvokerInner<S, R>, recorder: Arc<Recorder>) -> Self {
+        Self { consumer, sequence: AtomicUsize::new(0), recorder }
+    }
+
+    /// Invokes the thread, returning the response of the inner function when available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// // Calls only borrow the thread, so they may be made concurrently.
+    /// let (first, second) = tokio::join!(thread.call((2, 2)), thread.call((3, 3)));
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(first.expect("the channel should not be closed"), 4);
+    /// assert_eq!(second.expect("the channel should not be closed"), 6);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. If it is cancelled after the value has been sent, the value is still handled, but
+    /// its response is discarded.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed, or if the thread stopped
+    /// handling the value before responding.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub async fn call(&self, value: S) -> Result<R, CallError<S, R>> {
+        let (reply, receiver) = oneshot::channel();
+
+        self.send(self.track(value, Some(reply))).await?;
+        trace!("sent input value");
+
+        receiver.instrument(trace_span!("poll")).await.map_err(|_| self.dropped())
+    }
+
+    /// Invokes the thread, blocking the current thread until the response of the inner function is available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// let response = thread.blocking_call((2, 2)).expect("the channel should not be closed");
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed, or if the thread stopped
+    /// handling the value before responding.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub fn blocking_call(&self, value: S) -> Result<R, CallError<S, R>> {
+        let (reply, receiver) = oneshot::channel();
+
+        self.blocking_send(self.track(value, Some(reply)))?;
+        trace!("sent input value");
+
+        let _span = trace_span!("poll").entered();
+
+        receiver.blocking_recv().map_err(|_| self.dropped())
+    }
+
+    /// Invokes the thread, returning the response of the inner function if it is available before the given timeout.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::{CallError, Invoker};
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn_with_runtime("worker", capacity, |duration| async move {
+    ///     tokio::time::sleep(duration).await;
+    ///
+    ///     duration
+    /// })?;
+    ///
+    /// let response =
+    ///     thread.call_with_timeout(Duration::from_secs(2), Duration::from_millis(10)).await;
+    ///
+    /// assert!(matches!(response, Err(CallError::TimedOut)));
+    ///
+    /// // The timed out value is still handled before the next value, but its response is discarded.
+    /// let response = thread.call_with_timeout(Duration::ZERO, Duration::from_secs(5)).await;
+    ///
+    /// assert!(matches!(response, Ok(Duration::ZERO)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of an asynchronous runtime with the time driver enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed, if the thread stopped handling
+    /// the value before responding, or if the timeout elapsed.
+    pub async fn call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        tokio::time::timeout(timeout, self.call(value)).await.unwrap_or_else(|_| Err(self.timed_out(timeout)))
+    }
+
+    /// Invokes the thread, blocking the current thread until the response of the inner function is available or the
+    /// given timeout elapses.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::{CallError, Invoker};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |duration| std::thread::sleep(duration))?;
+    ///
+    /// let response =
+    ///     thread.blocking_call_with_timeout(Duration::from_secs(2), Duration::from_millis(10));
+    ///
+    /// assert!(matches!(response, Err(CallError::TimedOut)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed, if the thread stopped handling
+    /// the value before responding, or if the timeout elapsed.
+    pub fn blocking_call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        crate::block_on_timeout(self.call(value), timeout).unwrap_or_else(|| Err(self.timed_out(timeout)))
+    }
+
+    /// Invokes the thread without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
+    ///
+    /// This allows a caller to stop waiting for other tasks as soon as the value has been sent, for example to release
+    /// a lock that orders values before the response is awaited. The receiver resolves to an error if the thread
+    /// stopped handling the value before responding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// let receiver = thread.submit((2, 2)).await.expect("the channel should not be closed");
+    ///
+    /// assert_eq!(receiver.await.expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub async fn submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        let (reply, receiver) = oneshot::channel();
+
+        self.send(self.track(value, Some(reply))).await?;
+        trace!("sent input value without waiting for return");
+
+        Ok(receiver)
+    }
+
+    /// Invokes the thread without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
+    ///
+    /// See [`Invoker::submit`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// let receiver = thread.blocking_submit((2, 2)).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(receiver.blocking_recv().expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub fn blocking_submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        let (reply, receiver) = oneshot::channel();
+
+        self.blocking_send(self.track(value, Some(reply)))?;
+        trace!("sent input value without waiting for return");
+
+        Ok(receiver)
+    }
+
+    /// Reserves a slot within the thread's channel, returning a reservation that sends a value into the slot.
+    ///
+    /// Sending through the reservation cannot fail and does not wait, so any work that must only be done once a value
+    /// is certain to be sent may be done between reserving the slot and sending the value. If the reservation is
+    /// dropped without sending a value, the slot is released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// let reservation = thread.reserve().await.expect("the channel should not be closed");
+    /// let reply = reservation.submit((2, 2));
+    ///
+    /// assert_eq!(reply.recv().await.expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub async fn reserve(&self) -> Result<Reservation<'_, S, R>, CallError<S, R>> {
+        let Ok(permit) = self.as_sender().reserve().await else {
+            self.recorder.record_error();
+
+            return Err(CallError::Closed);
+        };
+
+        trace_span!("invoke_thread", name = %self.thread_name()).in_scope(|| trace!("reserved channel slot"));
+
+        Ok(Reservation { invoker: self, permit })
+    }
+
+    /// Reserves a slot within the thread's channel, blocking the current thread until one is available and returning a
+    /// reservation that sends a value into the slot.
+    ///
+    /// See [`Invoker::reserve`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    ///
+    /// let reservation = thread.blocking_reserve().expect("the channel should not be closed");
+    /// let reply = reservation.submit((2, 2));
+    ///
+    /// assert_eq!(reply.blocking_recv().expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub fn blocking_reserve(&self) -> Result<Reservation<'_, S, R>, CallError<S, R>> {
+        crate::block_on(self.reserve())
+    }
+
+    /// Invokes the thread, executing the method but ignoring the return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| {
+    ///     println!("{a} + {b} = {}", a + b);
+    /// })?;
+    ///
+    /// thread.call_and_forget((2, 2)).await.expect("the channel should not be closed");
+    ///
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub async fn call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        let result = self.send(self.track(value, None)).await;
+
+        trace!("sent input value without waiting for return");
+
+        result
+    }
+
+    /// Invokes the thread, executing the method but ignoring the return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::Invoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |(a, b)| {
+    ///     println!("{a} + {b} = {}", a + b);
+    /// })?;
+    ///
+    /// thread.blocking_call_and_forget((2, 2)).expect("the channel should not be closed");
+    ///
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread's receiving channel is closed.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub fn blocking_call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        let result = self.blocking_send(self.track(value, None));
+
+        trace!("sent input value without waiting for return");
+
+        result
+    }
+
+    /// Returns the given value alongside a new nonce and the given response channel.
+    fn track(&self, value: S, reply: Option<oneshot::Sender<R>>) -> Tracked<S, R> {
+        let nonce = self.sequence.fetch_add(1, Ordering::Relaxed);
+        tracing::Span::current().record("nonce", nonce);
+        trace!("created tracked value");
+
+        Tracked { nonce, value, reply }
+    }
+
+    /// Sends the given tracked value into the thread, recording whether it was sent.
+    async fn send(&self, tracked: Tracked<S, R>) -> Result<(), CallError<S, R>> {
+        let result = self.as_sender().send(tracked).await;
+
+        self.record_sent(result.is_ok());
+
+        result.map_err(CallError::SendInto)
+    }
+
+    /// Sends the given tracked value into the thread, blocking the current thread until it is sent and recording
+    /// whether it was sent.
+    fn blocking_send(&self, tracked: Tracked<S, R>) -> Result<(), CallError<S, R>> {
+        let result = self.as_sender().blocking_send(tracked);
+
+        self.record_sent(result.is_ok());
+
+        result.map_err(CallError::SendInto)
+    }
+
+    /// Records whether a value was sent into the thread.
+    fn record_sent(&self, sent: bool) {
+        if sent { self.recorder.record_call() } else { self.recorder.record_error() }
+    }
+
+    /// Records that a call timed out after the given duration, returning the matching error.
+    fn timed_out(&self, timeout: Duration) -> CallError<S, R> {
+        warn!(name = %self.thread_name(), timeout_ms = timeout.as_secs_f64() * 1_000.0, "invoker call timed out");
+
+        self.recorder.record_error();
+
+        CallError::TimedOut
+    }
+
+    /// Records that the thread stopped handling a value before responding, returning the matching error.
+    fn dropped(&self) -> CallError<S, R> {
+        self::dropped(&self.recorder)
+    }
+}
+
+impl<S, R> Handle for Invoker<S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    type Output = ();
+
+    fn as_join_handle(&self) -> &std::thread::JoinHandle<Self::Output> {
+        self.consumer.as_join_handle()
+    }
+
+    fn as_join_handle_mut(&mut self) -> &mut std::thread::JoinHandle<Self::Output> {
+        self.consumer.as_join_handle_mut()
+    }
+
+    fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
+        self.consumer.into_join_handle()
+    }
+
+    fn metrics(&self) -> Option<Metrics> {
+        Some(self.recorder.metrics(self.as_sender()))
+    }
+}
+
+impl<S, R> SenderHandle<Tracked<S, R>> for Invoker<S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    fn as_sender(&self) -> &Sender<Tracked<S, R>> {
+        self.consumer.as_sender()
+    }
+
+    fn as_sender_mut(&mut self) -> &mut Sender<Tracked<S, R>> {
+        self.consumer.as_sender_mut()
+    }
+
+    fn into_sender(self) -> Sender<Tracked<S, R>> {
+        self.consumer.into_sender()
+    }
+}
+
+/// A reserved slot within the channel of an [`Invoker<S, R>`].
+///
+/// If this is dropped without sending a value, the slot is released.
+#[derive(Debug)]
+pub struct Reservation<'iv, S, R> {
+    /// The invoker thread that the slot was reserved within.
+    invoker: &'iv Invoker<S, R>,
+    /// The permit to send a value into the reserved slot.
+    permit: Permit<'iv, Tracked<S, R>>,
+}
+
+impl<S, R> Reservation<'_, S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    /// Sends the given value into the reserved slot, returning a reply that resolves to the response when it is
+    /// available.
+    ///
+    /// See [`Invoker::reserve`] for more details.
+    #[tracing::instrument(
+        level = "trace",
+        name = "invoke_thread",
+        skip_all,
+        fields(name = %self.invoker.thread_name(), nonce = tracing::field::Empty)
+    )]
+    pub fn submit(self, value: S) -> Reply<S, R> {
+        let (reply, receiver) = oneshot::channel();
+
+        self.permit.send(self.invoker.track(value, Some(reply)));
+        self.invoker.record_sent(true);
+        trace!("sent input value into reserved slot");
+
+        Reply { receiver, recorder: Arc::clone(&self.invoker.recorder), value: PhantomData }
+    }
+}
+
+/// The pending response of an [`Invoker<S, R>`] to a value that was sent through a [`Reservation<S, R>`].
+///
+/// Waiting for the response fails if the thread stops handling the value before responding, or if a timeout elapses,
+/// either of which is recorded within the thread's metrics.
+#[derive(Debug)]
+pub struct Reply<S, R> {
+    /// The channel that the response is returned through.
+    receiver: oneshot::Receiver<R>,
+    /// The thread's runtime metrics.
+    recorder: Arc<Recorder>,
+    /// The type of the value that was sent.
+    value: PhantomData<fn() -> S>,
+}
+
+impl<S, R> Reply<S, R> {
+    /// Waits for the response.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread stopped handling the value before responding.
+    pub async fn recv(self) -> Result<R, CallError<S, R>> {
+        let Self { receiver, recorder, .. } = self;
+
+        receiver.await.map_err(|_| self::dropped(&recorder))
+    }
+
+    /// Waits for the response, blocking the current thread until it is available.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread stopped handling the value before responding.
+    pub fn blocking_recv(self) -> Result<R, CallError<S, R>> {
+        crate::block_on(self.recv())
+    }
+
+    /// Waits for the response until the given timeout elapses.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::{CallError, Invoker};
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = Invoker::spawn("worker", capacity, |duration| std::thread::sleep(duration))?;
+    ///
+    /// let reservation = thread.reserve().await.expect("the channel should not be closed");
+    /// let reply = reservation.submit(Duration::from_secs(2));
+    /// let response = reply.recv_with_timeout(Duration::from_millis(10)).await;
+    ///
+    /// assert!(matches!(response, Err(CallError::TimedOut)));
+    /// assert_eq!(thread.metrics().expect("invokers record metrics").errors, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of an asynchronous runtime with the time driver enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread stopped handling the value before responding, or if the timeout
+    /// elapsed.
+    pub async fn recv_with_timeout(self, timeout: Duration) -> Result<R, CallError<S, R>> {
+        let recorder = Arc::clone(&self.recorder);
+
+        tokio::time::timeout(timeout, self.recv()).await.unwrap_or_else(|_| Err(self::timed_out(&recorder, timeout)))
+    }
+
+    /// Waits for the response until the given timeout elapses, blocking the current thread until then.
+    ///
+    /// See [`Reply::recv_with_timeout`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread stopped handling the value before responding, or if the timeout
+    /// elapsed.
+    pub fn blocking_recv_with_timeout(self, timeout: Duration) -> Result<R, CallError<S, R>> {
+        let recorder = Arc::clone(&self.recorder);
+
+        crate::block_on_timeout(self.recv(), timeout).unwrap_or_else(|| Err(self::timed_out(&recorder, timeout)))
+    }
+}
+
+/// Records that a call timed out after the given duration within the given recorder, returning the matching error.
+fn timed_out<S, R>(recorder: &Recorder, timeout: Duration) -> CallError<S, R> {
+    warn!(timeout_ms = timeout.as_secs_f64() * 1_000.0, "invoker call timed out");
+
+    recorder.record_error();
+
+    CallError::TimedOut
+}
+
+/// Records that the thread stopped handling a value before responding within the given recorder, returning the matching
+/// error.
+fn dropped<S, R>(recorder: &Recorder) -> CallError<S, R> {
+    warn!("invoker thread stopped handling value before responding");
+
+    recorder.record_error();
+
+    CallError::Dropped
+}
+
+/// Handles each value received from the given channel using the given task, one at a time.
+async fn serve<S, R, F, O>(f: &F, inputs: &mut MeteredReceiver<Tracked<S, R>>, recorder: &Recorder)
+where
+    S: Send,
+    R: Send,
+    F: Fn(S) -> O + Sync,
+    O: Future<Output = R> + Send,
+{
+    while let Some(Tracked { nonce, value, reply }) = inputs.recv().await {
+        trace!(nonce, "received input value");
+
+        let response = async {
+            trace!("started execution");
+
+            let instant = std::time::Instant::now();
+            let response = f(value).await;
+            let elapsed = instant.elapsed();
+            trace!(elapsed_ms = elapsed.as_secs_f64() * 1_000.0, "finished execution");
+
+            recorder.record_handled(elapsed);
+
+            response
+        }
+        .instrument(trace_span!("fn", nonce))
+        .await;
+
+        self::respond(nonce, reply, response);
+    }
+}
+
+/// Handles each value received from the given channel using the given task, admitting values one at a time and then
+/// handling them concurrently.
+async fn serve_concurrent<S, R, F, O, P>(f: &F, inputs: &mut MeteredReceiver<Tracked<S, R>>, recorder: &Arc<Recorder>)
+where
+    S: Send + 'static,
+    R: Send + 'static,
+    F: Fn(S) -> O + Sync,
+    O: Future<Output = P> + Send,
+    P: Future<Output = R> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+
+    while let Some(Tracked { nonce, value, reply }) = inputs.recv().await {
+        trace!(nonce, "received input value");
+
+        let span = trace_span!("fn", nonce);
+        let task = f(value).instrument(span.clone()).await;
+        let recorder = Arc::clone(recorder);
+
+        tasks.spawn(
+            async move {
+                trace!("started execution");
+
+                let instant = std::time::Instant::now();
+                let response = task.await;
+                let elapsed = instant.elapsed();
+                trace!(elapsed_ms = elapsed.as_secs_f64() * 1_000.0, "finished execution");
+
+                recorder.record_handled(elapsed);
+                self::respond(nonce, reply, response);
+            }
+            .instrument(span),
+        );
+
+        // Finished tasks are reaped as values arrive so that their results do not accumulate.
+        while let Some(result) = tasks.try_join_next() {
+            self::join_result(result);
+        }
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        self::join_result(result);
+    }
+}
+
+/// Handles the result of a concurrently handled value, logging any panic that occurred while it was handled.
+///
+/// A panic is not resumed, since that would abort every other value that is still being handled. The value's caller is
+/// instead notified that it was dropped.
+fn join_result(result: Result<(), JoinError>) {
+    match result {
+        Ok(()) => {}
+        Err(error) if error.is_panic() => {
+            let payload = error.into_panic();
+
+            error!(reason = crate::supervisor::panic_message(&*payload), "concurrent task panicked");
+        }
+        Err(error) => warn!(%error, "concurrent task was cancelled"),
+    }
+}
+
+/// Sends the given response through the given channel, if the response is not ignored.
+///
+/// If the value is dropped without a response, such as when the task handling it panics, the channel is closed and
+/// the caller is notified instead.
+pub(super) fn respond<R>(nonce: usize, reply: Option<oneshot::Sender<R>>, response: R) {
+    let Some(reply) = reply else { return };
+
+    // The caller may have stopped waiting for the response, in which case it can be discarded.
+    if reply.send(response).is_ok() {
+        trace!(nonce, "sent return value");
+    } else {
+        trace!(nonce, "discarded return value of abandoned call");
+    }
+}
+
+/// A thread that consumes and returns values like a function.
+///
+/// This is a variant of a typical [`Invoker<S, R>`] that has a "state" value that is shared with
+/// all invocations. The state is owned by the running thread, which bundles it with every value that it receives.
+#[derive(Debug)]
+pub struct StatefulInvoker<T, S, R>
+where
+    T: ?Sized,
+{
+    /// The inner invoker thread.
+    invoker: Invoker<S, R>,
+    /// The type of the thread's state.
+    state: PhantomData<fn() -> Arc<T>>,
+}
+
+impl<T, S, R> StatefulInvoker<T, S, R>
+where
+    T: ?Sized + Send + Sync + 'static,
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
+    ///     // `args` carries both the value and the thread's state.
+    ///     args.value + *args.state
+    /// })?;
+    ///
+    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn<N, F, U>(name: N, capacity: NonZero<usize>, state: U, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(Stateful<T, S>) -> R + Send + 'static,
+        U: Into<Arc<T>>,
+    {
+        let state = state.into();
+        let f = move |value| f(Stateful { state: Arc::clone(&state), value });
+
+        Ok(Self::from_invoker(Invoker::spawn(name, capacity, f)?))
+    }
+
+    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and asynchronous task.
+    ///
+    /// The created runtime has both IO and time drivers enabled, and is configured to only run on the spawned thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = StatefulInvoker::spawn_with_runtime("worker", capacity, 2, |args| async move {
+    ///     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///
+    ///     args.value + *args.state
+    /// })?;
+    ///
+    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_with_runtime<N, F, O, U>(name: N, capacity: NonZero<usize>, state: U, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(Stateful<T, S>) -> O + Send + Sync + 'static,
+        O: Future<Output = R> + Send,
+        U: Into<Arc<T>>,
+    {
+        let state = state.into();
+        let f = move |value| f(Stateful { state: Arc::clone(&state), value });
+
+        Ok(Self::from_invoker(Invoker::spawn_with_runtime(name, capacity, f)?))
+    }
+
+    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and asynchronous task, which handles values
+    /// concurrently.
+    ///
+    /// Each value is passed into the task in the order that it was received, and the returned future is awaited
+    /// before the next value is received. The future that it resolves to is then spawned, running concurrently with
+    /// the handling of other values. See [`Invoker::spawn_concurrent_with_runtime`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread =
+    ///     StatefulInvoker::spawn_concurrent_with_runtime("worker", capacity, 2, |args| async move {
+    ///         async move { args.value + *args.state }
+    ///     })?;
+    ///
+    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_concurrent_with_runtime<N, F, O, P, U>(
+        name: N,
+        capacity: NonZero<usize>,
+        state: U,
+        f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(Stateful<T, S>) -> O + Send + Sync + 'static,
+        O: Future<Output = P> + Send,
+        P: Future<Output = R> + Send + 'static,
+        U: Into<Arc<T>>,
+    {
+        let state = state.into();
+        let f = move |value| f(Stateful { state: Arc::clone(&state), value });
+
+        Ok(Self::from_invoker(Invoker::spawn_concurrent_with_runtime(name, capacity, f)?))
+    }
+
+    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and asynchronous task, which is restarted by the
+    /// given supervisor.
+    ///
+    /// The thread's state is created by calling the given function every time that the task is started, so a restarted
+    /// task never sees state that a panic may have left inconsistent. State may still be kept between restarts by
+    /// returning clones of the same [`Arc<T>`]. See [`Invoker::spawn_supervised_with_runtime`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::sync::Mutex;
+    /// # use std::time::Duration;
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::invoker::{CallError, StatefulInvoker};
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let supervisor = Supervisor::new(Restart::OnPanic).with_backoff(Duration::ZERO, Duration::ZERO);
+    /// let thread = StatefulInvoker::spawn_supervised_with_runtime(
+    ///     "worker",
+    ///     capacity,
+    ///     || Mutex::new(0),
+    ///     supervisor,
+    ///     |args| async move {
+    ///         let mut total = args.state.lock().unwrap();
+    ///
+    ///         *total += args.value;
+    ///
+    ///         assert!(*total < 10, "the total is too large");
+    ///
+    ///         *total
+    ///     },
+    /// )?;
+    ///
+    /// assert_eq!(thread.blocking_call(2).expect("the channel should not be closed"), 2);
+    /// assert!(matches!(thread.blocking_call(10), Err(CallError::Dropped)));
+    ///
+    /// // The restarted task is given new state, rather than the state that the panic poisoned.
+    /// assert_eq!(thread.blocking_call(3).expect("the channel should not be closed"), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the thread fails to spawn.
+    pub fn spawn_supervised_with_runtime<N, F, O, G, U>(
+        name: N,
+        capacity: NonZero<usize>,
+        state: G,
+        supervisor: Supervisor,
+        f: F,
+    ) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(Stateful<T, S>) -> O + Send + Sync + 'static,
+        O: Future<Output = R> + Send,
+        G: Fn() -> U + Send + 'static,
+        U: Into<Arc<T>>,
+    {
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = async move |receiver: &mut MeteredReceiver<Tracked<S, R>>| {
+            let state = state().into();
+            let f = |value| f(Stateful { state: Arc::clone(&state), value });
+            trace!("created thread state");
+
+            self::serve(&f, receiver, &thread_recorder).await;
+        };
+        let consumer = Consumer::spawn_supervised_with_runtime(name, capacity, supervisor, f)?;
+
+        Ok(Self::from_invoker(Invoker::from_consumer(consumer, recorder)))
     }
 
-    /// Spawns a new [`Invoker<S, R>`] with the given name and asynchronous task.
+    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and asynchronous task, which handles values
+    /// concurrently and is restarted by the given supervisor.
     ///
-    /// The created runtime has both IO and time drivers enabled, and is configured to only run on the spawned thread.
+    /// The thread's state is created by calling the given function every time that the task is started. See
+    /// [`StatefulInvoker::spawn_supervised_with_runtime`] and [`Invoker::spawn_concurrent_supervised_with_runtime`] for
+    /// more details.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
-    /// # use ina_threading::threads::invoker::Invoker;
+    /// # use ina_threading::supervisor::{Restart, Supervisor};
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn_with_runtime("worker", capacity, |n| async move {
-    ///     assert_eq!(n, 123);
-    ///
-    ///     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    /// let supervisor = Supervisor::new(Restart::OnPanic);
+    /// let thread = StatefulInvoker::spawn_concurrent_supervised_with_runtime(
+    ///     "worker",
+    ///     capacity,
+    ///     || 2,
+    ///     supervisor,
+    ///     |args| async move { async move { args.value + *args.state } },
+    /// )?;
     ///
-    ///     456
-    /// })?;
-    ///
-    /// let response = thread.blocking_call(123).expect("the channel should not be closed");
+    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
     ///
-    /// assert_eq!(response, 456);
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
     /// # Ok(())
     /// # }
     /// ```
@@ -170,41 +1378,39 @@ where
     /// # Errors
     ///
     /// This function will return an error if the thread fails to spawn.
-    pub fn spawn_with_runtime<N, F, O>(name: N, capacity: NonZero<usize>, f: F) -> Result<Self>
+    pub fn spawn_concurrent_supervised_with_runtime<N, F, O, P, G, U>(
+        name: N,
+        capacity: NonZero<usize>,
+        state: G,
+        supervisor: Supervisor,
+        f: F,
+    ) -> Result<Self>
     where
         N: AsRef<str>,
-        F: Fn(S) -> O + Send + Sync + 'static,
-        O: Future<Output = R> + Send,
+        F: Fn(Stateful<T, S>) -> O + Send + Sync + 'static,
+        O: Future<Output = P> + Send,
+        P: Future<Output = R> + Send + 'static,
+        G: Fn() -> U + Send + 'static,
+        U: Into<Arc<T>>,
     {
-        let f = move |sender: Sender<Tracked<R>>, mut receiver: Receiver<Tracked<S>>| async move {
-            loop {
-                let Some(received) = receiver.recv().await else { return Ok(()) };
-                trace!(nonce = received.nonce, "received input value");
-
-                let response = async {
-                    trace!("started execution");
-
-                    let instant = std::time::Instant::now();
-                    let response = Tracked { nonce: received.nonce, value: f(received.value).await };
-                    trace!(elapsed_ms = instant.elapsed().as_secs_f64() * 1_000.0, "finished execution");
-
-                    response
-                }
-                .instrument(trace_span!("fn", nonce = received.nonce))
-                .await;
-
-                if received.nonce.is_some() {
-                    sender.send(response).await.map_err(CallError::SendFrom)?;
-                    trace!(nonce = received.nonce, "sent return value");
-                }
-            }
+        let recorder = Arc::new(Recorder::default());
+        let thread_recorder = Arc::clone(&recorder);
+        let f = async move |receiver: &mut MeteredReceiver<Tracked<S, R>>| {
+            let state = state().into();
+            let f = |value| f(Stateful { state: Arc::clone(&state), value });
+            trace!("created thread state");
+
+            self::serve_concurrent(&f, receiver, &thread_recorder).await;
         };
+        let consumer = Consumer::spawn_supervised_with_runtime(name, capacity, supervisor, f)?;
+
+        Ok(Self::from_invoker(Invoker::from_consumer(consumer, recorder)))
+    }
 
-        Ok(Self {
-            exchanger: Exchanger::spawn_with_runtime(name, capacity, f)?,
-            completed: BTreeMap::new(),
-            sequence: AtomicUsize::new(0),
-        })
+    /// Creates a new [`StatefulInvoker<T, S, R>`] from the given invoker thread, which bundles its state with the
+    /// values that it receives.
+    const fn from_invoker(invoker: Invoker<S, R>) -> Self {
+        Self { invoker, state: PhantomData }
     }
 
     /// Invokes the thread, returning the response of the inner function when available.
@@ -214,13 +1420,16 @@ where
     /// ```
     /// # use std::num::NonZero;
     /// # use ina_threading::Handle;
-    /// # use ina_threading::threads::invoker::Invoker;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # #[tokio::main]
     /// # async fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
+    ///     // `args` carries both the value and the thread's state.
+    ///     args.value + *args.state
+    /// })?;
     ///
-    /// let response = thread.call((2, 2)).await.expect("the channel should not be closed");
+    /// let response = thread.call(2).await.expect("the channel should not be closed");
     ///
     /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
     /// assert_eq!(response, 4);
@@ -229,64 +1438,17 @@ where
     /// # }
     /// ```
     ///
-    /// # Panics
+    /// # Cancel safety
     ///
-    /// Panics if [`usize::MAX`] tasks have their responses queued, causing a response to be overwritten.
+    /// This method is cancel safe. If it is cancelled after the value has been sent, the value is still handled, but
+    /// its response is discarded.
     ///
     /// # Errors
     ///
-    /// This function will return an error if either of the thread's sender or receiver channels are closed.
-    #[tracing::instrument(
-        level = "trace",
-        name = "invoke_thread",
-        skip_all,
-        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
-    )]
-    pub async fn call(&mut self, value: S) -> Result<R, CallError<S, R>> {
-        let nonce = self.sequence.fetch_add(1, Ordering::AcqRel);
-        tracing::Span::current().record("nonce", nonce);
-        let value = Tracked { nonce: Some(nonce), value };
-        trace!("created tracked value");
-
-        self.as_sender().send(value).await.map_err(CallError::SendInto)?;
-        trace!("sent input value");
-
-        async move {
-            loop {
-                if let Some(completed) = self.completed.remove(&nonce) {
-                    trace!("found return value stored by separate task");
-
-                    return Ok(completed);
-                }
-
-                match self.as_receiver_mut().recv().await {
-                    // If the value was returned by the task triggered above, return it.
-                    Some(Tracked { nonce: Some(completed_nonce), value }) if completed_nonce == nonce => {
-                        trace!("received return value");
-
-                        return Ok(value);
-                    }
-                    // If the value was returned by another task, store it so that it can still be consumed.
-                    Some(Tracked { nonce: Some(completed_nonce), value }) => {
-                        trace!(found = completed_nonce, "found return value with mis-matched nonce");
-
-                        // A panic here would require that enough tasks ([`usize::MAX`] to be exact) are triggered to
-                        // cause a task to receive the same sequence ID as another pending task.
-                        assert!(self.completed.insert(completed_nonce, value).is_none());
-                    }
-                    Some(Tracked { nonce: None, value: _ }) => {
-                        unreachable!("values with no nonce should not be returned")
-                    }
-                    None => {
-                        warn!("invoker thread closed while polling");
-
-                        return Err(CallError::Closed);
-                    }
-                }
-            }
-        }
-        .instrument(trace_span!("poll"))
-        .await
+    /// This function will return an error if the thread's receiving channel is closed, or if the thread stopped
+    /// handling the value before responding.
+    pub async fn call(&self, value: S) -> Result<R, CallError<S, R>> {
+        self.invoker.call(value).await
     }
 
     /// Invokes the thread, blocking the current thread until the response of the inner function is available.
@@ -296,12 +1458,15 @@ where
     /// ```
     /// # use std::num::NonZero;
     /// # use ina_threading::Handle;
-    /// # use ina_threading::threads::invoker::Invoker;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn("worker", capacity, |(a, b)| a + b)?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
+    ///     // `args` carries both the value and the thread's state.
+    ///     args.value + *args.state
+    /// })?;
     ///
-    /// let response = thread.blocking_call((2, 2)).expect("the channel should not be closed");
+    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
     ///
     /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
     /// assert_eq!(response, 4);
@@ -312,367 +1477,197 @@ where
     ///
     /// # Panics
     ///
-    /// Panics if [`usize::MAX`] tasks have their responses queued, causing a response to be overwritten, or if this is
-    /// called from within an asynchronous runtime.
+    /// Panics if this is called from within an asynchronous runtime.
     ///
     /// # Errors
     ///
-    /// This function will return an error if either of the thread's sender or receiver channels are closed.
-    #[tracing::instrument(
-        level = "trace",
-        name = "invoke_thread",
-        skip_all,
-        fields(name = %self.thread_name(), nonce = tracing::field::Empty)
-    )]
-    pub fn blocking_call(&mut self, value: S) -> Result<R, CallError<S, R>> {
-        let nonce = self.sequence.fetch_add(1, Ordering::AcqRel);
-        tracing::Span::current().record("nonce", nonce);
-        let value = Tracked { nonce: Some(nonce), value };
-        trace!("created tracked value");
-
-        self.as_sender().blocking_send(value).map_err(CallError::SendInto)?;
-        trace!("sent input value");
-
-        let _span = trace_span!("poll").entered();
-
-        loop {
-            if let Some(completed) = self.completed.remove(&nonce) {
-                trace!("found return value stored by separate task");
-
-                return Ok(completed);
-            }
-
-            match self.as_receiver_mut().blocking_recv() {
-                // If the value was returned by the task triggered above, return it.
-                Some(Tracked { nonce: Some(completed_nonce), value }) if completed_nonce == nonce => {
-                    trace!("received return value");
-
-                    return Ok(value);
-                }
-                // If the value was returned by another task, store it so that it can still be consumed.
-                Some(Tracked { nonce: Some(completed_nonce), value }) => {
-                    trace!(found = completed_nonce, "found return value with mis-matched nonce");
-
-                    // A panic here would require that enough tasks ([`usize::MAX`] to be exact) are triggered to cause
-                    // a task to receive the same sequence ID as another pending task.
-                    assert!(self.completed.insert(completed_nonce, value).is_none());
-                }
-                Some(Tracked { nonce: None, value: _ }) => unreachable!("values with no nonce should not be returned"),
-                None => {
-                    warn!("invoker thread closed while polling");
-
-                    return Err(CallError::Closed);
-                }
-            }
-        }
+    /// This function will return an error if the thread's receiving channel is closed, or if the thread stopped
+    /// handling the value before responding.
+    pub fn blocking_call(&self, value: S) -> Result<R, CallError<S, R>> {
+        self.invoker.blocking_call(value)
     }
 
-    /// Invokes the thread, executing the method but ignoring the return value.
+    /// Invokes the thread, returning the response of the inner function if it is available before the given timeout.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
-    /// # use ina_threading::threads::invoker::Invoker;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # #[tokio::main]
     /// # async fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn("worker", capacity, |(a, b)| {
-    ///     println!("{a} + {b} = {}", a + b);
-    /// })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// thread.call_and_forget((2, 2)).await.expect("the channel should not be closed");
+    /// let response = thread.call_with_timeout(2, Duration::from_secs(5)).await;
     ///
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(response.expect("the call should not time out"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of an asynchronous runtime with the time driver enabled.
+    ///
     /// # Errors
     ///
-    /// This function will return an error if the thread's receiving channel is closed.
-    #[tracing::instrument(level = "trace", name = "invoke_thread", skip_all, fields(name = %self.thread_name()))]
-    pub async fn call_and_forget(&mut self, value: S) -> Result<(), CallError<S, R>> {
-        let result = self.as_sender().send(Tracked { nonce: None, value }).await;
-
-        trace!("sent input value without waiting for return");
-
-        result.map_err(CallError::SendInto)
+    /// This function will return an error if the thread's receiving channel is closed, if the thread stopped handling
+    /// the value before responding, or if the timeout elapsed.
+    pub async fn call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        self.invoker.call_with_timeout(value, timeout).await
     }
 
-    /// Invokes the thread, executing the method but ignoring the return value.
+    /// Invokes the thread, blocking the current thread until the response of the inner function is available or the
+    /// given timeout elapses.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
-    /// # use ina_threading::threads::invoker::Invoker;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = Invoker::spawn("worker", capacity, |(a, b)| {
-    ///     println!("{a} + {b} = {}", a + b);
-    /// })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// thread.blocking_call_and_forget((2, 2)).expect("the channel should not be closed");
+    /// let response = thread.blocking_call_with_timeout(2, Duration::from_secs(5));
     ///
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(response.expect("the call should not time out"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// Panics if this is called from within an asynchronous runtime.
-    ///
     /// # Errors
     ///
-    /// This function will return an error if the thread's receiving channel is closed.
-    #[tracing::instrument(level = "trace", name = "invoke_thread", skip_all, fields(name = %self.thread_name()))]
-    pub fn blocking_call_and_forget(&mut self, value: S) -> Result<(), CallError<S, R>> {
-        let result = self.as_sender().blocking_send(Tracked { nonce: None, value });
-
-        trace!("sent input value without waiting for return");
-
-        result.map_err(CallError::SendInto)
-    }
-}
-
-impl<S, R> Handle for Invoker<S, R>
-where
-    S: Send + 'static,
-    R: Send + 'static,
-{
-    type Output = Result<(), CallError<S, R>>;
-
-    fn as_join_handle(&self) -> &std::thread::JoinHandle<Self::Output> {
-        self.exchanger.as_join_handle()
-    }
-
-    fn as_join_handle_mut(&mut self) -> &mut std::thread::JoinHandle<Self::Output> {
-        self.exchanger.as_join_handle_mut()
-    }
-
-    fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
-        self.exchanger.into_join_handle()
-    }
-}
-
-impl<S, R> SenderHandle<Tracked<S>> for Invoker<S, R>
-where
-    S: Send + 'static,
-    R: Send + 'static,
-{
-    fn as_sender(&self) -> &tokio::sync::mpsc::Sender<Tracked<S>> {
-        self.exchanger.as_sender()
-    }
-
-    fn as_sender_mut(&mut self) -> &mut tokio::sync::mpsc::Sender<Tracked<S>> {
-        self.exchanger.as_sender_mut()
-    }
-
-    fn into_sender(self) -> tokio::sync::mpsc::Sender<Tracked<S>> {
-        self.exchanger.into_sender()
-    }
-}
-
-impl<S, R> ReceiverHandle<Tracked<R>> for Invoker<S, R>
-where
-    S: Send + 'static,
-    R: Send + 'static,
-{
-    fn as_receiver(&self) -> &tokio::sync::mpsc::Receiver<Tracked<R>> {
-        self.exchanger.as_receiver()
+    /// This function will return an error if the thread's receiving channel is closed, if the thread stopped handling
+    /// the value before responding, or if the timeout elapsed.
+    pub fn blocking_call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        self.invoker.blocking_call_with_timeout(value, timeout)
     }
 
-    fn as_receiver_mut(&mut self) -> &mut tokio::sync::mpsc::Receiver<Tracked<R>> {
-        self.exchanger.as_receiver_mut()
-    }
-
-    fn into_receiver(self) -> tokio::sync::mpsc::Receiver<Tracked<R>> {
-        self.exchanger.into_receiver()
-    }
-}
-
-/// A thread that consumes and returns values like a function.
-///
-/// This is a variant of a typical [`Invoker<S, R>`] that has a "state" value that is shared with
-/// all invocations.
-#[derive(Debug)]
-pub struct StatefulInvoker<T, S, R>
-where
-    T: ?Sized,
-{
-    /// The inner invoker thread.
-    invoker: Invoker<Stateful<T, S>, R>,
-    /// The thread's canonical state.
-    state: Arc<T>,
-}
-
-impl<T, S, R> StatefulInvoker<T, S, R>
-where
-    T: ?Sized + Send + Sync + 'static,
-    S: Send + 'static,
-    R: Send + 'static,
-{
-    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and task.
+    /// Invokes the thread without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
+    ///
+    /// See [`Invoker::submit`] for more details.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
     /// # use ina_threading::threads::invoker::StatefulInvoker;
-    /// # fn main() -> ina_threading::Result<()> {
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
-    ///     // `args` carries both the value and the thread's state.
-    ///     args.value + *args.state
-    /// })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    /// let receiver = thread.submit(2).await.expect("the channel should not be closed");
     ///
-    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
-    /// assert_eq!(response, 4);
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(receiver.await.expect("the value should be handled"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
     /// # Errors
     ///
-    /// This function will return an error if the thread fails to spawn.
-    pub fn spawn<N, F, U>(name: N, capacity: NonZero<usize>, state: U, f: F) -> Result<Self>
-    where
-        N: AsRef<str>,
-        F: Fn(Stateful<T, S>) -> R + Send + 'static,
-        U: Into<Arc<T>>,
-    {
-        Ok(Self { invoker: Invoker::spawn(name, capacity, f)?, state: state.into() })
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub async fn submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        self.invoker.submit(value).await
     }
 
-    /// Spawns a new [`StatefulInvoker<T, S, R>`] with the given name and asynchronous task.
+    /// Invokes the thread without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
     ///
-    /// The created runtime has both IO and time drivers enabled, and is configured to only run on the spawned thread.
+    /// See [`Invoker::submit`] for more details.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
     /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread =
-    ///     StatefulInvoker::spawn_with_runtime("worker", capacity, 2, |args| async move {
-    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-    ///
-    ///         args.value + *args.state
-    ///     })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    /// let receiver = thread.blocking_submit(2).expect("the channel should not be closed");
     ///
-    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
-    /// assert_eq!(response, 4);
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(receiver.blocking_recv().expect("the value should be handled"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
     /// # Errors
     ///
-    /// This function will return an error if the thread fails to spawn.
-    pub fn spawn_with_runtime<N, F, O, U>(name: N, capacity: NonZero<usize>, state: U, f: F) -> Result<Self>
-    where
-        N: AsRef<str>,
-        F: Fn(Stateful<T, S>) -> O + Send + Sync + 'static,
-        O: Future<Output = R> + Send,
-        U: Into<Arc<T>>,
-    {
-        Ok(Self { invoker: Invoker::spawn_with_runtime(name, capacity, f)?, state: state.into() })
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub fn blocking_submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        self.invoker.blocking_submit(value)
     }
 
-    /// Invokes the thread, returning the response of the inner function when available.
+    /// Reserves a slot within the thread's channel, returning a reservation that sends a value into the slot.
+    ///
+    /// See [`Invoker::reserve`] for more details.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
     /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # #[tokio::main]
     /// # async fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
-    ///     // `args` carries both the value and the thread's state.
-    ///     args.value + *args.state
-    /// })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// let response = thread.call(2).await.expect("the channel should not be closed");
+    /// let reservation = thread.reserve().await.expect("the channel should not be closed");
+    /// let reply = reservation.submit(2);
     ///
-    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
-    /// assert_eq!(response, 4);
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(reply.recv().await.expect("the value should be handled"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// Panics if [`usize::MAX`] tasks have their responses queued, causing a response to be overwritten.
-    ///
     /// # Errors
     ///
-    /// This function will return an error if either of the thread's sender or receiver channels are closed.
-    pub async fn call(&mut self, value: S) -> Result<R, CallError<Stateful<T, S>, R>> {
-        let stateful_value = Stateful { state: Arc::clone(&self.state), value };
-        trace_span!("invoke_thread", name = %self.thread_name()).in_scope(|| {
-            trace!("bundled value and shared thread state");
-        });
-
-        self.invoker.call(stateful_value).await
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub async fn reserve(&self) -> Result<Reservation<'_, S, R>, CallError<S, R>> {
+        self.invoker.reserve().await
     }
 
-    /// Invokes the thread, blocking the current thread until the response of the inner function is available.
+    /// Reserves a slot within the thread's channel, blocking the current thread until one is available and returning a
+    /// reservation that sends a value into the slot.
+    ///
+    /// See [`Invoker::reserve`] for more details.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::num::NonZero;
-    /// # use ina_threading::Handle;
     /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
-    ///     // `args` carries both the value and the thread's state.
-    ///     args.value + *args.state
-    /// })?;
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| args.value + *args.state)?;
     ///
-    /// let response = thread.blocking_call(2).expect("the channel should not be closed");
+    /// let reservation = thread.blocking_reserve().expect("the channel should not be closed");
+    /// let reply = reservation.submit(2);
     ///
-    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
-    /// assert_eq!(response, 4);
-    /// assert!(thread.into_join_handle().join().is_ok());
+    /// assert_eq!(reply.blocking_recv().expect("the value should be handled"), 4);
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// Panics if [`usize::MAX`] tasks have their responses queued, causing a response to be overwritten, or if this is
-    /// called from within an asynchronous runtime.
-    ///
     /// # Errors
     ///
-    /// This function will return an error if either of the thread's sender or receiver channels are closed.
-    pub fn blocking_call(&mut self, value: S) -> Result<R, CallError<Stateful<T, S>, R>> {
-        let stateful_value = Stateful { state: Arc::clone(&self.state), value };
-        trace_span!("invoke_thread", name = %self.thread_name()).in_scope(|| {
-            trace!("bundled value and shared thread state");
-        });
-
-        self.invoker.blocking_call(stateful_value)
+    /// This function will return an error if the thread's receiving channel is closed.
+    pub fn blocking_reserve(&self) -> Result<Reservation<'_, S, R>, CallError<S, R>> {
+        self.invoker.blocking_reserve()
     }
 
     /// Invokes the thread, executing the method but ignoring the return value.
@@ -686,7 +1681,7 @@ where
     /// # #[tokio::main]
     /// # async fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
     ///     println!("{} + {} = {}", args.value, args.state, args.value + *args.state);
     /// })?;
     ///
@@ -700,13 +1695,8 @@ where
     /// # Errors
     ///
     /// This function will return an error if the thread's receiving channel is closed.
-    pub async fn call_and_forget(&mut self, value: S) -> Result<(), CallError<Stateful<T, S>, R>> {
-        let stateful_value = Stateful { state: Arc::clone(&self.state), value };
-        trace_span!("invoke_thread", name = %self.thread_name()).in_scope(|| {
-            trace!("bundled value and shared thread state");
-        });
-
-        self.invoker.call_and_forget(stateful_value).await
+    pub async fn call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        self.invoker.call_and_forget(value).await
     }
 
     /// Invokes the thread, executing the method but ignoring the return value.
@@ -719,7 +1709,7 @@ where
     /// # use ina_threading::threads::invoker::StatefulInvoker;
     /// # fn main() -> ina_threading::Result<()> {
     /// let capacity = NonZero::<usize>::new(1).unwrap();
-    /// let mut thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
+    /// let thread = StatefulInvoker::spawn("worker", capacity, 2, |args| {
     ///     println!("{} + {} = {}", args.value, args.state, args.value + *args.state);
     /// })?;
     ///
@@ -737,13 +1727,8 @@ where
     /// # Errors
     ///
     /// This function will return an error if the thread's receiving channel is closed.
-    pub fn blocking_call_and_forget(&mut self, value: S) -> Result<(), CallError<Stateful<T, S>, R>> {
-        let stateful_value = Stateful { state: Arc::clone(&self.state), value };
-        trace_span!("invoke_thread", name = %self.thread_name()).in_scope(|| {
-            trace!("bundled value and shared thread state");
-        });
-
-        self.invoker.blocking_call_and_forget(stateful_value)
+    pub fn blocking_call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        self.invoker.blocking_call_and_forget(value)
     }
 }
 
@@ -753,7 +1738,7 @@ where
     S: Send + 'static,
     R: Send + 'static,
 {
-    type Output = Result<(), CallError<Stateful<T, S>, R>>;
+    type Output = ();
 
     fn as_join_handle(&self) -> &std::thread::JoinHandle<Self::Output> {
         self.invoker.as_join_handle()
@@ -766,42 +1751,27 @@ where
     fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
         self.invoker.into_join_handle()
     }
+
+    fn metrics(&self) -> Option<Metrics> {
+        self.invoker.metrics()
+    }
 }
 
-impl<T, S, R> SenderHandle<Tracked<Stateful<T, S>>> for StatefulInvoker<T, S, R>
+impl<T, S, R> SenderHandle<Tracked<S, R>> for StatefulInvoker<T, S, R>
 where
     T: ?Sized + Send + Sync + 'static,
     S: Send + 'static,
     R: Send + 'static,
 {
-    fn as_sender(&self) -> &Sender<Tracked<Stateful<T, S>>> {
+    fn as_sender(&self) -> &Sender<Tracked<S, R>> {
         self.invoker.as_sender()
     }
 
-    fn as_sender_mut(&mut self) -> &mut Sender<Tracked<Stateful<T, S>>> {
+    fn as_sender_mut(&mut self) -> &mut Sender<Tracked<S, R>> {
         self.invoker.as_sender_mut()
     }
 
-    fn into_sender(self) -> Sender<Tracked<Stateful<T, S>>> {
+    fn into_sender(self) -> Sender<Tracked<S, R>> {
         self.invoker.into_sender()
     }
 }
-
-impl<T, S, R> ReceiverHandle<Tracked<R>> for StatefulInvoker<T, S, R>
-where
-    T: ?Sized + Send + Sync + 'static,
-    S: Send + 'static,
-    R: Send + 'static,
-{
-    fn as_receiver(&self) -> &Receiver<Tracked<R>> {
-        self.invoker.as_receiver()
-    }
-
-    fn as_receiver_mut(&mut self) -> &mut Receiver<Tracked<R>> {
-        self.invoker.as_receiver_mut()
-    }
-
-    fn into_receiver(self) -> Receiver<Tracked<R>> {
-        self.invoker.into_receiver()
-    }
-}
@@ -0,0 +1,592 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::num::NonZero;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::{Mutex, oneshot};
+use tracing::{Instrument, trace, trace_span};
+
+use super::consumer::Consumer;
+use super::invoker::{CallError, Invoker, Tracked};
+use crate::metrics::{Metrics, Recorder};
+use crate::{Handle, Result, SenderHandle, Thread};
+
+/// The receiving channel that is shared between the workers of a [`PoolInvoker<S, R>`].
+type Queue<S, R> = Arc<Mutex<Receiver<Tracked<S, R>>>>;
+
+/// A pool of threads that consume and return values like a function.
+///
+/// Values are sent into a single queue that is shared by every worker, and each value is handled by whichever worker
+/// receives it first. This allows work that would otherwise block a single thread, such as key derivation, to be
+/// spread across multiple threads.
+///
+/// The pool's handle refers to a thread that waits for every worker to exit, so the pool may be stored and closed
+/// like any other thread.
+///
+/// # Examples
+///
+/// ```
+/// # use std::num::NonZero;
+/// # use ina_threading::statics::Static;
+/// # use ina_threading::threads::pool::PoolInvoker;
+/// # #[tokio::main]
+/// # async fn main() -> ina_threading::Result<()> {
+/// static POOL: Static<PoolInvoker<u64, u64>> = Static::new();
+///
+/// let workers = NonZero::<usize>::new(4).unwrap();
+/// let capacity = NonZero::<usize>::new(16).unwrap();
+///
+/// POOL.async_api().initialize(PoolInvoker::spawn("pool", workers, capacity, |n| n * 2)?).await;
+///
+/// let response = POOL.async_api().get().await.call(123).await;
+///
+/// assert_eq!(response.expect("the channel should not be closed"), 246);
+///
+/// POOL.async_api().close().await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PoolInvoker<S, R> {
+    /// The inner invoker, which sends values into the shared queue.
+    invoker: Invoker<S, R>,
+    /// The number of workers that were spawned.
+    workers: NonZero<usize>,
+}
+
+impl<S, R> PoolInvoker<S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    /// Spawns a new [`PoolInvoker<S, R>`] with the given name, number of workers, and task.
+    ///
+    /// Each worker is named after the pool, followed by its index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |n| {
+    ///     assert_eq!(n, 123);
+    ///     456
+    /// })?;
+    ///
+    /// let response = thread.blocking_call(123).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(response, 456);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any thread fails to spawn.
+    pub fn spawn<N, F>(name: N, workers: NonZero<usize>, capacity: NonZero<usize>, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> R + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+
+        Self::spawn_workers(name, workers, capacity, |name, queue, recorder| {
+            let f = Arc::clone(&f);
+
+            Thread::spawn(name, move || self::work(&*f, &queue, &recorder))
+        })
+    }
+
+    /// Spawns a new [`PoolInvoker<S, R>`] with the given name, number of workers, and asynchronous task.
+    ///
+    /// Each worker is named after the pool, followed by its index, and creates its own runtime. The created runtimes
+    /// have both IO and time drivers enabled, and are configured to only run on their worker's thread. Every worker
+    /// handles one value at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn_with_runtime("pool", workers, capacity, |n| async move {
+    ///     assert_eq!(n, 123);
+    ///
+    ///     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///
+    ///     456
+    /// })?;
+    ///
+    /// let response = thread.blocking_call(123).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(response, 456);
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any thread fails to spawn.
+    pub fn spawn_with_runtime<N, F, O>(name: N, workers: NonZero<usize>, capacity: NonZero<usize>, f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: Fn(S) -> O + Send + Sync + 'static,
+        O: Future<Output = R> + Send,
+    {
+        let f = Arc::new(f);
+
+        Self::spawn_workers(name, workers, capacity, |name, queue, recorder| {
+            let f = Arc::clone(&f);
+
+            Thread::spawn_with_runtime(name, move || async move { self::work_async(&*f, &queue, &recorder).await })
+        })
+    }
+
+    /// Spawns the pool's workers using the given function, alongside the thread that waits for them to exit.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any thread fails to spawn.
+    fn spawn_workers<N, F>(name: N, workers: NonZero<usize>, capacity: NonZero<usize>, mut f: F) -> Result<Self>
+    where
+        N: AsRef<str>,
+        F: FnMut(String, Queue<S, R>, Arc<Recorder>) -> Result<Thread<()>>,
+    {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity.get());
+        trace!(name = %name.as_ref(), capacity, "opened mpsc channel (client->pool)");
+
+        let queue = Arc::new(Mutex::new(receiver));
+        let recorder = Arc::new(Recorder::default());
+        let threads = (0 .. workers.get())
+            .map(|index| f(format!("{}-{index}", name.as_ref()), Arc::clone(&queue), Arc::clone(&recorder)))
+            .collect::<Result<Box<[_]>>>()?;
+
+        // Only the workers should hold the queue, so that it is closed once all of them have exited.
+        drop(queue);
+
+        let thread = Thread::spawn(name, move || self::join_workers(threads))?;
+
+        Ok(Self { invoker: Invoker::from_consumer(Consumer::from_parts(thread, sender), recorder), workers })
+    }
+
+    /// Returns the number of workers that were spawned.
+    #[must_use]
+    pub const fn workers(&self) -> NonZero<usize> {
+        self.workers
+    }
+
+    /// Invokes a worker, returning the response of the inner function when available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(2).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| a + b)?;
+    ///
+    /// // Both calls may be handled at the same time by separate workers.
+    /// let (first, second) = tokio::join!(thread.call((2, 2)), thread.call((3, 3)));
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(first.expect("the channel should not be closed"), 4);
+    /// assert_eq!(second.expect("the channel should not be closed"), 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. If it is cancelled after the value has been sent, the value is still handled, but
+    /// its response is discarded.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited, or if the worker stopped handling the value
+    /// before responding.
+    pub async fn call(&self, value: S) -> Result<R, CallError<S, R>> {
+        self.invoker.call(value).await
+    }
+
+    /// Invokes a worker, blocking the current thread until the response of the inner function is available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| a + b)?;
+    ///
+    /// let response = thread.blocking_call((2, 2)).expect("the channel should not be closed");
+    ///
+    /// // Unfortunately, Rust is incorrect and thinks that `2 + 2 != 5`.
+    /// assert_eq!(response, 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited, or if the worker stopped handling the value
+    /// before responding.
+    pub fn blocking_call(&self, value: S) -> Result<R, CallError<S, R>> {
+        self.invoker.blocking_call(value)
+    }
+
+    /// Invokes a worker, returning the response of the inner function if it is available before the given timeout.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::CallError;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, std::thread::sleep)?;
+    ///
+    /// let response =
+    ///     thread.call_with_timeout(Duration::from_secs(2), Duration::from_millis(10)).await;
+    ///
+    /// assert!(matches!(response, Err(CallError::TimedOut)));
+    ///
+    /// // The other worker remains available while the first is busy.
+    /// let response = thread.call_with_timeout(Duration::ZERO, Duration::from_secs(1)).await;
+    ///
+    /// assert!(response.is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of an asynchronous runtime with the time driver enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited, if the worker stopped handling the value before
+    /// responding, or if the timeout elapsed.
+    pub async fn call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        self.invoker.call_with_timeout(value, timeout).await
+    }
+
+    /// Invokes a worker, blocking the current thread until the response of the inner function is available or the
+    /// given timeout elapses.
+    ///
+    /// If the timeout elapses, the value may still be handled, but its response is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use std::time::Duration;
+    /// # use ina_threading::threads::invoker::CallError;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, std::thread::sleep)?;
+    ///
+    /// let response =
+    ///     thread.blocking_call_with_timeout(Duration::from_secs(2), Duration::from_millis(10));
+    ///
+    /// assert!(matches!(response, Err(CallError::TimedOut)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime, or if a runtime could not be created to wait for
+    /// the timeout.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited, if the worker stopped handling the value before
+    /// responding, or if the timeout elapsed.
+    pub fn blocking_call_with_timeout(&self, value: S, timeout: Duration) -> Result<R, CallError<S, R>> {
+        self.invoker.blocking_call_with_timeout(value, timeout)
+    }
+
+    /// Invokes a worker without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
+    ///
+    /// See [`Invoker::submit`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| a + b)?;
+    ///
+    /// let receiver = thread.submit((2, 2)).await.expect("the channel should not be closed");
+    ///
+    /// assert_eq!(receiver.await.expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited.
+    pub async fn submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        self.invoker.submit(value).await
+    }
+
+    /// Invokes a worker without waiting for the response of the inner function, returning a receiver that resolves
+    /// to the response when it is available.
+    ///
+    /// See [`Invoker::submit`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| a + b)?;
+    ///
+    /// let receiver = thread.blocking_submit((2, 2)).expect("the channel should not be closed");
+    ///
+    /// assert_eq!(receiver.blocking_recv().expect("the value should be handled"), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited.
+    pub fn blocking_submit(&self, value: S) -> Result<oneshot::Receiver<R>, CallError<S, R>> {
+        self.invoker.blocking_submit(value)
+    }
+
+    /// Invokes a worker, executing the method but ignoring the return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # #[tokio::main]
+    /// # async fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| {
+    ///     println!("{a} + {b} = {}", a + b);
+    /// })?;
+    ///
+    /// thread.call_and_forget((2, 2)).await.expect("the channel should not be closed");
+    ///
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited.
+    pub async fn call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        self.invoker.call_and_forget(value).await
+    }
+
+    /// Invokes a worker, executing the method but ignoring the return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZero;
+    /// # use ina_threading::Handle;
+    /// # use ina_threading::threads::pool::PoolInvoker;
+    /// # fn main() -> ina_threading::Result<()> {
+    /// let workers = NonZero::<usize>::new(2).unwrap();
+    /// let capacity = NonZero::<usize>::new(1).unwrap();
+    /// let thread = PoolInvoker::spawn("pool", workers, capacity, |(a, b)| {
+    ///     println!("{a} + {b} = {}", a + b);
+    /// })?;
+    ///
+    /// thread.blocking_call_and_forget((2, 2)).expect("the channel should not be closed");
+    ///
+    /// assert!(thread.into_join_handle().join().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within an asynchronous runtime.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every worker has exited.
+    pub fn blocking_call_and_forget(&self, value: S) -> Result<(), CallError<S, R>> {
+        self.invoker.blocking_call_and_forget(value)
+    }
+}
+
+impl<S, R> Handle for PoolInvoker<S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    type Output = ();
+
+    fn as_join_handle(&self) -> &std::thread::JoinHandle<Self::Output> {
+        self.invoker.as_join_handle()
+    }
+
+    fn as_join_handle_mut(&mut self) -> &mut std::thread::JoinHandle<Self::Output> {
+        self.invoker.as_join_handle_mut()
+    }
+
+    fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
+        self.invoker.into_join_handle()
+    }
+
+    fn metrics(&self) -> Option<Metrics> {
+        self.invoker.metrics()
+    }
+}
+
+impl<S, R> SenderHandle<Tracked<S, R>> for PoolInvoker<S, R>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    fn as_sender(&self) -> &Sender<Tracked<S, R>> {
+        self.invoker.as_sender()
+    }
+
+    fn as_sender_mut(&mut self) -> &mut Sender<Tracked<S, R>> {
+        self.invoker.as_sender_mut()
+    }
+
+    fn into_sender(self) -> Sender<Tracked<S, R>> {
+        self.invoker.into_sender()
+    }
+}
+
+/// Handles values received from the given shared queue using the given task, one at a time.
+fn work<S, R, F>(f: &F, queue: &Queue<S, R>, recorder: &Recorder)
+where
+    F: Fn(S) -> R,
+{
+    // The queue is only held while waiting for a value, so that other workers may receive values in the meantime.
+    loop {
+        let Some(Tracked { nonce, value, reply }) = queue.blocking_lock().blocking_recv() else { break };
+
+        trace!(nonce, "received input value");
+
+        let span = trace_span!("fn", nonce).entered();
+        trace!("started execution");
+
+        let instant = std::time::Instant::now();
+        let response = f(value);
+        let elapsed = instant.elapsed();
+        trace!(elapsed_ms = elapsed.as_secs_f64() * 1_000.0, "finished execution");
+
+        drop(span);
+
+        recorder.record_handled(elapsed);
+        super::invoker::respond(nonce, reply, response);
+    }
+}
+
+/// Handles values received from the given shared queue using the given asynchronous task, one at a time.
+async fn work_async<S, R, F, O>(f: &F, queue: &Queue<S, R>, recorder: &Recorder)
+where
+    S: Send,
+    R: Send,
+    F: Fn(S) -> O + Sync,
+    O: Future<Output = R> + Send,
+{
+    // The queue is only held while waiting for a value, so that other workers may receive values in the meantime.
+    loop {
+        let Some(Tracked { nonce, value, reply }) = queue.lock().await.recv().await else { break };
+
+        trace!(nonce, "received input value");
+
+        let response = async {
+            trace!("started execution");
+
+            let instant = std::time::Instant::now();
+            let response = f(value).await;
+            let elapsed = instant.elapsed();
+            trace!(elapsed_ms = elapsed.as_secs_f64() * 1_000.0, "finished execution");
+
+            recorder.record_handled(elapsed);
+
+            response
+        }
+        .instrument(trace_span!("fn", nonce))
+        .await;
+
+        super::invoker::respond(nonce, reply, response);
+    }
+}
+
+/// Waits for every given worker to exit, resuming the first panic that occurred within any of them.
+fn join_workers(workers: Box<[Thread<()>]>) {
+    let mut panic = None;
+
+    for worker in workers {
+        if let Err(payload) = worker.into_join_handle().join() {
+            panic.get_or_insert(payload);
+        }
+    }
+
+    if let Some(payload) = panic {
+        std::panic::resume_unwind(payload);
+    }
+}
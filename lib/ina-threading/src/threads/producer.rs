@@ -19,6 +19,7 @@ use std::num::NonZero;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tracing::trace;
 
+use crate::metrics::Metrics;
 use crate::{Handle, ReceiverHandle, Result, Thread};
 
 /// A thread that accepts values through a receiver channel.
@@ -128,6 +129,10 @@ where
     fn into_join_handle(self) -> std::thread::JoinHandle<Self::Output> {
         self.thread.into_join_handle()
     }
+
+    fn metrics(&self) -> Option<Metrics> {
+        Some(Metrics::from_receiver(&self.receiver))
+    }
 }
 
 impl<R, T> ReceiverHandle<R> for Producer<R, T>
@@ -26,7 +26,7 @@ use twilight_gateway::{Event, ShardId};
 use twilight_mention::Mention;
 use twilight_model::application::interaction::{Interaction, InteractionData, InteractionType};
 use twilight_model::channel::message::MessageFlags;
-use twilight_model::gateway::payload::incoming::{InteractionCreate, Ready};
+use twilight_model::gateway::payload::incoming::{InteractionCreate, Ready, RoleDelete};
 use twilight_model::http::attachment::Attachment;
 use twilight_model::http::interaction::InteractionResponseType;
 use twilight_util::builder::message::{
@@ -88,6 +88,11 @@ pub async fn on_event(api: Api, event: Event, shard_id: ShardId) -> EventResult
 
             Box::pin(self::on_interaction(api, *event)).await
         }
+        Event::RoleDelete(event) => {
+            debug!(guild = %event.guild_id, role = %event.role_id, "received role deletion event");
+
+            self::on_role_delete(event).await
+        }
         Event::Resumed => {
             info!("successfully resumed");
 
@@ -144,6 +149,9 @@ pub async fn on_ready(api: Api, event: Ready, shard_id: ShardId) -> EventResult
 
     crate::command::registry::initialize().await?;
 
+    let count = crate::command::definition::poll::schedule_stored_closes(&api).await?;
+    debug!(count, "scheduled stored poll closures");
+
     if api.settings.skip_command_patch {
         info!("skipped command patching");
 
@@ -172,6 +180,22 @@ pub async fn on_ready(api: Api, event: Ready, shard_id: ShardId) -> EventResult
     self::pass()
 }
 
+/// Handles a [`RoleDelete`] event.
+///
+/// # Errors
+///
+/// This function will return an error if the event could not be handled.
+#[tracing::instrument(level = "debug", name = "role_delete", skip_all, fields(role = %event.role_id))]
+pub async fn on_role_delete(event: RoleDelete) -> EventResult {
+    let count = crate::command::definition::role::remove_deleted_role(event.role_id).await?;
+
+    if count > 0 {
+        info!(count, "removed deleted role from role selectors");
+    }
+
+    self::pass()
+}
+
 /// Handles an [`InteractionCreate`] event.
 ///
 /// # Errors
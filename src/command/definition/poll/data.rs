@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+use ina_localizing::locale::Locale;
+use ina_localizing::localize;
+use ina_macro::Stored;
+use ina_storage::format::{Compress, Messagepack};
+use rand::{RngExt, rng};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use twilight_mention::Mention;
+use twilight_model::channel::message::Component;
+use twilight_model::channel::message::component::ButtonStyle;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{GuildMarker, UserMarker};
+use twilight_util::builder::message::{
+    ActionRowBuilder, ButtonBuilder, ContainerBuilder, SeparatorBuilder, TextDisplayBuilder,
+};
+use twilight_validate::component::{ACTION_ROW_COMPONENT_COUNT, BUTTON_LABEL_LENGTH};
+
+use crate::command::registry::CommandEntry;
+use crate::utility::category;
+use crate::utility::types::anchor::Anchor;
+use crate::utility::types::builder::{MediaGalleryBuilder, MediaGalleryItemBuilder, ValidatedBuilder};
+
+/// The minimum number of choices that a poll may have.
+pub const MIN_CHOICES: usize = 2;
+/// The maximum number of choices that a poll may have.
+pub const MAX_CHOICES: usize = ACTION_ROW_COMPONENT_COUNT * 2;
+/// The maximum length of a poll choice.
+pub const CHOICE_LENGTH: usize = BUTTON_LABEL_LENGTH;
+/// The maximum length of a poll's displayed results, leaving room for the rest of the message's content.
+pub const RESULTS_LENGTH: usize = 3000;
+
+/// A poll's type.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PollKind {
+    /// Users select one of a list of choices.
+    MultipleChoice = 0,
+    /// Users submit a written response.
+    OpenResponse = 1,
+    /// Users select one of a list of choices and may submit a written response.
+    Hybrid = 2,
+    /// Users enter a drawing, and a winner is randomly selected when the poll closes.
+    Raffle = 3,
+}
+
+impl PollKind {
+    /// Returns whether this poll type presents a list of choices.
+    #[must_use]
+    pub const fn has_choices(self) -> bool {
+        matches!(self, Self::MultipleChoice | Self::Hybrid)
+    }
+
+    /// Returns whether this poll type accepts written responses.
+    #[must_use]
+    pub const fn has_responses(self) -> bool {
+        matches!(self, Self::OpenResponse | Self::Hybrid)
+    }
+}
+
+impl TryFrom<i64> for PollKind {
+    type Error = anyhow::Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::MultipleChoice,
+            1 => Self::OpenResponse,
+            2 => Self::Hybrid,
+            3 => Self::Raffle,
+            _ => bail!("invalid poll type '{value}'"),
+        })
+    }
+}
+
+/// A poll's displayed content.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollContent {
+    /// The poll's title.
+    pub title: Box<str>,
+    /// The poll's body text.
+    pub description: Option<Box<str>>,
+    /// The poll's image URL.
+    pub image: Option<Box<str>>,
+}
+
+/// A poll.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize, Stored)]
+#[data_format(kind = Compress<Messagepack>, from = Compress::new_fast(Messagepack))]
+#[data_path(fmt = "poll/{}/{}", args = [Id<GuildMarker>, Id<UserMarker>], from = [guild_id, user_id])]
+pub struct Poll {
+    /// The user identifier.
+    pub user_id: Id<UserMarker>,
+    /// The guild identifier.
+    pub guild_id: Id<GuildMarker>,
+    /// The poll's type.
+    pub kind: PollKind,
+    /// The poll's duration in minutes.
+    pub duration: u32,
+    /// The poll's content.
+    pub content: PollContent,
+    /// The poll's choices.
+    pub choices: Vec<Box<str>>,
+    /// The poll's sent message, or [`None`] if the poll has not been sent.
+    pub anchor: Option<Anchor>,
+    /// The unix timestamp at which the poll closes, or [`None`] if the poll has not been sent.
+    pub closes_at: Option<i64>,
+    /// The choice indices selected by each voting user.
+    pub votes: BTreeMap<Id<UserMarker>, usize>,
+    /// The written responses submitted by each user.
+    pub responses: BTreeMap<Id<UserMarker>, Box<str>>,
+    /// The users that have entered the raffle.
+    pub entrants: BTreeSet<Id<UserMarker>>,
+}
+
+impl Poll {
+    /// Creates a new unsent [`Poll`].
+    pub const fn new(
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        kind: PollKind,
+        duration: u32,
+        content: PollContent,
+        choices: Vec<Box<str>>,
+    ) -> Self {
+        Self {
+            user_id,
+            guild_id,
+            kind,
+            duration,
+            content,
+            choices,
+            anchor: None,
+            closes_at: None,
+            votes: BTreeMap::new(),
+            responses: BTreeMap::new(),
+            entrants: BTreeSet::new(),
+        }
+    }
+
+    /// Returns whether this poll has been sent.
+    #[must_use]
+    pub const fn is_sent(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Returns whether this poll's duration has elapsed.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.closes_at.is_some_and(|closes_at| closes_at <= OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Builds the header of this poll's message into a container.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a component could not be created.
+    fn build_header(&self, accent: u32) -> Result<ContainerBuilder> {
+        let PollContent { title, description, image } = &self.content;
+
+        let mut container = ContainerBuilder::new()
+            .accent_color(Some(accent))
+            .component(TextDisplayBuilder::new(format!("### {title}")).try_build()?);
+
+        if let Some(description) = description {
+            container = container.component(TextDisplayBuilder::new(description.to_string()).try_build()?);
+        }
+        if let Some(image) = image {
+            let gallery = MediaGalleryBuilder::new().item(MediaGalleryItemBuilder::url(image.to_string()));
+
+            container = container.component(gallery.try_build()?);
+        }
+
+        Ok(container.component(SeparatorBuilder::new().try_build()?))
+    }
+
+    /// Builds this poll into a list of components that allow users to participate.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a component could not be created.
+    pub async fn build(
+        &self,
+        entry: &CommandEntry,
+        locale: Option<Locale>,
+        disabled: bool,
+    ) -> Result<Box<[Component]>> {
+        let closes_at = self
+            .closes_at
+            .unwrap_or_else(|| OffsetDateTime::now_utc().unix_timestamp() + (i64::from(self.duration) * 60));
+        let closes = localize!(async(try in locale) category::UI, "poll-closes").await?;
+
+        let container = self
+            .build_header(crate::utility::color::BRANDING.rgb())?
+            .component(TextDisplayBuilder::new(format!("-# {closes} <t:{closes_at}:R>")).try_build()?);
+
+        let mut components = Vec::<Component>::with_capacity(4);
+
+        components.push(container.try_build()?.into());
+
+        if self.kind.has_choices() {
+            for (row, chunk) in self.choices.chunks(ACTION_ROW_COMPONENT_COUNT).enumerate() {
+                let mut action_row = ActionRowBuilder::new();
+
+                for (index, choice) in chunk.iter().enumerate() {
+                    let index = (row * ACTION_ROW_COMPONENT_COUNT) + index;
+                    let custom_id = entry
+                        .id(super::component::vote::NAME)?
+                        .with_str(self.user_id.to_string())?
+                        .with_str(index.to_string())?;
+
+                    action_row = action_row.component(
+                        ButtonBuilder::new(ButtonStyle::Secondary)
+                            .custom_id(custom_id)
+                            .disabled(disabled)
+                            .label(choice.as_ref())
+                            .try_build()?,
+                    );
+                }
+
+                components.push(action_row.try_build()?.into());
+            }
+        }
+
+        let (kind, key) = match self.kind {
+            PollKind::OpenResponse | PollKind::Hybrid => (super::component::respond::NAME, "poll-respond"),
+            PollKind::Raffle => (super::component::enter::NAME, "poll-enter"),
+            PollKind::MultipleChoice => return Ok(components.into_boxed_slice()),
+        };
+
+        let button = ButtonBuilder::new(ButtonStyle::Primary)
+            .custom_id(entry.id(kind)?.with_str(self.user_id.to_string())?)
+            .disabled(disabled)
+            .label(localize!(async(try in locale) category::UI_BUTTON, key).await?.to_string())
+            .try_build()?;
+
+        components.push(ActionRowBuilder::new().component(button).try_build()?.into());
+
+        Ok(components.into_boxed_slice())
+    }
+
+    /// Builds this poll's results into a list of components.
+    ///
+    /// If this is a raffle, the winner is randomly selected from the poll's entrants.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a component could not be created.
+    pub async fn build_results(&self, locale: Option<Locale>) -> Result<Box<[Component]>> {
+        let results = localize!(async(try in locale) category::UI, "poll-results").await?;
+
+        let mut container = self.build_header(crate::utility::color::BACKDROP.rgb())?;
+        let mut content = format!("**{results}:**\n");
+
+        if self.kind.has_choices() {
+            let mut counts = vec![0_usize; self.choices.len()];
+
+            for index in self.votes.values().filter(|index| **index < counts.len()) {
+                counts[*index] += 1;
+            }
+
+            let total = counts.iter().sum::<usize>();
+
+            if total == 0 {
+                let text = localize!(async(try in locale) category::UI, "poll-no-votes").await?;
+
+                writeln!(&mut content, "> *{text}*")?;
+            } else {
+                for (choice, count) in self.choices.iter().zip(counts) {
+                    writeln!(&mut content, "- {choice}: **{count}** ({}%)", (count * 100) / total)?;
+                }
+            }
+        }
+
+        if self.kind.has_responses() {
+            if self.kind.has_choices() {
+                container = container.component(TextDisplayBuilder::new(content).try_build()?);
+
+                let responses = localize!(async(try in locale) category::UI, "poll-responses").await?;
+
+                content = format!("**{responses}:**\n");
+            }
+
+            if self.responses.is_empty() {
+                let text = localize!(async(try in locale) category::UI, "poll-no-responses").await?;
+
+                writeln!(&mut content, "> *{text}*")?;
+            } else {
+                for (user_id, response) in &self.responses {
+                    let mut line = response.lines().fold(String::new(), |quote, line| quote + "> " + line + "\n");
+
+                    writeln!(&mut line, "-# {}", user_id.mention())?;
+
+                    // Responses that no longer fit within the text display are omitted.
+                    if content.len() + line.len() > RESULTS_LENGTH {
+                        content.push('…');
+
+                        break;
+                    }
+
+                    content.push_str(&line);
+                }
+            }
+        }
+
+        if self.kind == PollKind::Raffle {
+            let index = (!self.entrants.is_empty()).then(|| rng().random_range(0 .. self.entrants.len()));
+
+            if let Some(winner) = index.and_then(|index| self.entrants.iter().nth(index)) {
+                let text = localize!(async(try in locale) category::UI, "poll-winner").await?;
+
+                writeln!(&mut content, "{text}: {}", winner.mention())?;
+            } else {
+                let text = localize!(async(try in locale) category::UI, "poll-no-entrants").await?;
+
+                writeln!(&mut content, "> *{text}*")?;
+            }
+        }
+
+        container = container.component(TextDisplayBuilder::new(content).try_build()?);
+
+        Ok(Box::new([container.try_build()?.into()]))
+    }
+}
@@ -0,0 +1,869 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of 1N4.
+//
+// 1N4 is free software: you can redistribute it and/or modify it under the terms of the GNU Affero General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// 1N4 is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
+// <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, Weak};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use data::{CHOICE_LENGTH, MAX_CHOICES, MIN_CHOICES, Poll, PollContent, PollKind};
+use ina_localizing::locale::Locale;
+use ina_localizing::localize;
+use ina_storage::stored::Stored;
+use time::OffsetDateTime;
+use tokio::sync::OwnedMutexGuard;
+use tracing::{Instrument, debug, trace, trace_span, warn};
+use twilight_model::application::command::CommandType;
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::CommandData;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::channel::message::component::{ButtonStyle, Label, TextInputStyle};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{GuildMarker, MessageMarker, UserMarker};
+use twilight_util::builder::message::{ActionRowBuilder, ButtonBuilder, LabelBuilder};
+
+use crate::client::api::{Api, ApiRef};
+use crate::client::event::EventResult;
+use crate::command::context::{Context, Visibility};
+use crate::command::registry::CommandEntry;
+use crate::command::resolver::{CommandOptionResolver, ModalComponentResolver};
+use crate::utility::category;
+use crate::utility::traits::convert::AsLocale;
+use crate::utility::types::anchor::Anchor;
+use crate::utility::types::builder::{TextInputBuilder, ValidatedBuilder};
+use crate::utility::types::custom_id::CustomId;
+use crate::utility::types::modal::ModalBuilder;
+
+/// The command's data.
+mod data;
+
+/// The locks that serialize changes to each stored poll, keyed by the poll's guild and owner.
+static LOCKS: LazyLock<Mutex<HashMap<(Id<GuildMarker>, Id<UserMarker>), Weak<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(Mutex::default);
+/// Whether the closures of stored polls have been scheduled.
+static CLOSES_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// The numeric identifier of the title input's label.
+const TITLE_ID: i32 = 1;
+/// The numeric identifier of the body text input's label.
+const DESCRIPTION_ID: i32 = 3;
+/// The numeric identifier of the image URL input's label.
+const IMAGE_ID: i32 = 5;
+/// The numeric identifier of the choices input's label.
+const CHOICES_ID: i32 = 7;
+/// The numeric identifier of the response input's label.
+const RESPONSE_ID: i32 = 1;
+
+crate::define_entry!("poll", CommandType::ChatInput, struct {
+    contexts: [InteractionContextType::Guild],
+}, struct {
+    command: on_command,
+    component: on_component,
+    modal: on_modal,
+}, struct {
+    create: SubCommand {
+        type: Integer {
+            required: true,
+            choices: [("multiple-choice", 0), ("open-response", 1), ("hybrid", 2), ("raffle", 3)],
+        },
+        duration: Integer {
+            required: true,
+            minimum: 1,
+            maximum: 10_080,
+        },
+    },
+    close: SubCommand {},
+});
+
+crate::define_commands! {
+    self => {
+        create => on_create_command;
+        close => on_close_command;
+    }
+}
+
+crate::define_components! {
+    send => on_send_component;
+    vote => on_vote_component;
+    respond => on_respond_component;
+    enter => on_enter_component;
+}
+
+crate::define_modals! {
+    create => on_create_modal;
+    respond => on_respond_modal;
+}
+
+/// Executes the create command.
+///
+/// # Errors
+///
+/// This function will return an error if the command could not be executed.
+async fn on_create_command<'ap: 'ev, 'ev>(
+    entry: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev CommandData>,
+    resolver: CommandOptionResolver<'ev>,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this command must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this command must be used by a user");
+    };
+    let kind = PollKind::try_from(*resolver.integer("type")?)?;
+    let duration = u32::try_from(*resolver.integer("duration")?)?;
+    trace!(?kind, duration, "resolved poll type and duration");
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    if Poll::async_api().exists((guild_id, user_id)).await? {
+        debug!("a poll has already been created");
+
+        let title = localize!(async(try in locale) category::UI, "poll-exists").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+
+    let title = localize!(async(try in locale) category::UI, "poll-create-title").await?;
+    let custom_id =
+        entry.id(modal::create::NAME)?.with_str((kind as u8).to_string())?.with_str(duration.to_string())?;
+
+    let title_input = TextInputBuilder::new("title", TextInputStyle::Short).max_length(256).required(true);
+    let description_input =
+        TextInputBuilder::new("description", TextInputStyle::Paragraph).max_length(2048).required(false);
+    let image_input = TextInputBuilder::new("image", TextInputStyle::Short).max_length(512).required(false);
+
+    let mut modal = ModalBuilder::new(title.to_string(), custom_id)
+        .component(self::create_label(locale, "poll-create-title", TITLE_ID, title_input).await?)
+        .component(self::create_label(locale, "poll-create-description", DESCRIPTION_ID, description_input).await?)
+        .component(self::create_label(locale, "poll-create-image", IMAGE_ID, image_input).await?);
+
+    if kind.has_choices() {
+        let choices_input = TextInputBuilder::new("choices", TextInputStyle::Paragraph).max_length(1024).required(true);
+
+        modal = modal.component(self::create_label(locale, "poll-create-choices", CHOICES_ID, choices_input).await?);
+    }
+    debug!("created modal components");
+
+    context.modal(modal.try_build()?).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the close command.
+///
+/// # Errors
+///
+/// This function will return an error if the command could not be executed.
+async fn on_close_command<'ap: 'ev, 'ev>(
+    _: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev CommandData>,
+    _: CommandOptionResolver<'ev>,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this command must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this command must be used by a user");
+    };
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let guard = self::lock_poll(guild_id, user_id).await;
+
+    if !Poll::async_api().exists((guild_id, user_id)).await? {
+        debug!("no poll has been created");
+
+        let title = localize!(async(try in locale) category::UI, "poll-missing").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+
+    let Ok(poll) = Poll::async_api().read((guild_id, user_id)).await else {
+        debug!("the poll could not be loaded");
+
+        let title = localize!(async(try in locale) category::UI, "poll-load-failed").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    };
+
+    let title = if poll.is_sent() {
+        self::close_poll(context.api, guild_id, user_id, None).await?;
+        debug!("closed active poll");
+
+        localize!(async(try in locale) category::UI, "poll-closed").await?
+    } else {
+        poll.as_async_api().delete().await?;
+        debug!("removed unsent poll file");
+
+        localize!(async(try in locale) category::UI, "poll-discarded").await?
+    };
+
+    drop(guard);
+
+    context.success_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the send component.
+///
+/// # Errors
+///
+/// This function will return an error if the component could not be executed.
+async fn on_send_component<'ap: 'ev, 'ev>(
+    entry: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev MessageComponentInteractionData>,
+    _: CustomId,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this component must be used in a guild");
+    };
+    let Some(channel_id) = context.interaction.channel.as_ref().map(|c| c.id) else {
+        bail!("this component must be used in a channel");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this component must be used by a user");
+    };
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let guard = self::lock_poll(guild_id, user_id).await;
+
+    let Ok(mut poll) = Poll::async_api().read((guild_id, user_id)).await else {
+        debug!("the poll could not be loaded");
+
+        let title = localize!(async(try in locale) category::UI, "poll-missing").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    };
+
+    if poll.is_sent() {
+        debug!("the poll has already been sent");
+
+        let title = localize!(async(try in locale) category::UI, "poll-already-sent").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+
+    poll.closes_at = Some(OffsetDateTime::now_utc().unix_timestamp() + (i64::from(poll.duration) * 60));
+
+    let components = poll.build(entry, self::guild_locale(context.api, guild_id), false).await?;
+    debug!("created message components");
+
+    let client = &context.api.client;
+    let message =
+        client.create_message(channel_id).flags(MessageFlags::IS_COMPONENTS_V2).components(&components).await?;
+    debug!("created message");
+
+    poll.anchor = Some(Anchor::from(message.model().await?));
+    poll.as_async_api().write().await?;
+    debug!("wrote poll file");
+
+    drop(guard);
+
+    self::schedule_close(context.api.into_owned(), &poll);
+
+    let title = localize!(async(try in locale) category::UI, "poll-started").await?;
+
+    context.success_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the vote component.
+///
+/// # Errors
+///
+/// This function will return an error if the component could not be executed.
+async fn on_vote_component<'ap: 'ev, 'ev>(
+    _: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev MessageComponentInteractionData>,
+    custom_id: CustomId,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this component must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this component must be used by a user");
+    };
+    let Some(owner_id) = custom_id.get::<Id<UserMarker>>(0).transpose()? else {
+        bail!("missing poll owner identifier data");
+    };
+    let Some(index) = custom_id.get::<usize>(1).transpose()? else {
+        bail!("missing choice index data");
+    };
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let guard = self::lock_poll(guild_id, owner_id).await;
+
+    let Some(mut poll) = self::load_active(&mut context, locale, guild_id, owner_id).await? else {
+        return crate::client::event::pass();
+    };
+
+    if index >= poll.choices.len() {
+        bail!("invalid choice index '{index}'");
+    }
+
+    let title = if poll.votes.get(&user_id).is_some_and(|choice| *choice == index) {
+        poll.votes.remove(&user_id);
+        debug!(user = %user_id, index, "removed vote");
+
+        localize!(async(try in locale) category::UI, "poll-unvoted").await?
+    } else {
+        poll.votes.insert(user_id, index);
+        debug!(user = %user_id, index, "recorded vote");
+
+        localize!(async(try in locale) category::UI, "poll-voted").await?
+    };
+
+    poll.as_async_api().write().await?;
+    debug!("wrote poll file");
+
+    drop(guard);
+
+    context.success_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the respond component.
+///
+/// # Errors
+///
+/// This function will return an error if the component could not be executed.
+async fn on_respond_component<'ap: 'ev, 'ev>(
+    entry: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev MessageComponentInteractionData>,
+    custom_id: CustomId,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this component must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this component must be used by a user");
+    };
+    let Some(owner_id) = custom_id.get::<Id<UserMarker>>(0).transpose()? else {
+        bail!("missing poll owner identifier data");
+    };
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    // This intentionally does not defer, as modals cannot be sent in response to a deferred interaction.
+    let guard = self::lock_poll(guild_id, owner_id).await;
+    let Some(poll) = self::load_active(&mut context, locale, guild_id, owner_id).await? else {
+        return crate::client::event::pass();
+    };
+
+    drop(guard);
+
+    let mut input = TextInputBuilder::new("response", TextInputStyle::Paragraph).max_length(512).required(true);
+
+    if let Some(response) = poll.responses.get(&user_id) {
+        input = input.value(response.as_ref());
+    }
+
+    let title = localize!(async(try in locale) category::UI, "poll-respond-title").await?;
+    let custom_id = entry.id(modal::respond::NAME)?.with_str(owner_id.to_string())?;
+    let modal = ModalBuilder::new(title.to_string(), custom_id)
+        .component(self::create_label(locale, "poll-respond", RESPONSE_ID, input).await?);
+    debug!("created modal components");
+
+    context.modal(modal.try_build()?).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the enter component.
+///
+/// # Errors
+///
+/// This function will return an error if the component could not be executed.
+async fn on_enter_component<'ap: 'ev, 'ev>(
+    _: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev MessageComponentInteractionData>,
+    custom_id: CustomId,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this component must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this component must be used by a user");
+    };
+    let Some(owner_id) = custom_id.get::<Id<UserMarker>>(0).transpose()? else {
+        bail!("missing poll owner identifier data");
+    };
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let guard = self::lock_poll(guild_id, owner_id).await;
+
+    let Some(mut poll) = self::load_active(&mut context, locale, guild_id, owner_id).await? else {
+        return crate::client::event::pass();
+    };
+
+    let title = if poll.entrants.remove(&user_id) {
+        debug!(user = %user_id, "removed raffle entrant");
+
+        localize!(async(try in locale) category::UI, "poll-left").await?
+    } else {
+        poll.entrants.insert(user_id);
+        debug!(user = %user_id, "added raffle entrant");
+
+        localize!(async(try in locale) category::UI, "poll-entered").await?
+    };
+
+    poll.as_async_api().write().await?;
+    debug!("wrote poll file");
+
+    drop(guard);
+
+    context.success_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the create modal.
+///
+/// # Errors
+///
+/// This function will return an error if the modal could not be executed.
+async fn on_create_modal<'ap: 'ev, 'ev>(
+    entry: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev ModalInteractionData>,
+    custom_id: CustomId,
+    resolver: ModalComponentResolver<'ev>,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this modal must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this modal must be used by a user");
+    };
+    let Some(kind) = custom_id.get::<i64>(0).transpose()? else {
+        bail!("missing poll type data");
+    };
+    let Some(duration) = custom_id.get::<u32>(1).transpose()? else {
+        bail!("missing poll duration data");
+    };
+    let kind = PollKind::try_from(kind)?;
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    if Poll::async_api().exists((guild_id, user_id)).await? {
+        debug!("a poll has already been created");
+
+        let title = localize!(async(try in locale) category::UI, "poll-exists").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+
+    let Some(title) = self::input_value(&resolver, TITLE_ID)? else {
+        bail!("missing required poll title");
+    };
+    let description = self::input_value(&resolver, DESCRIPTION_ID)?;
+    let image = self::input_value(&resolver, IMAGE_ID)?;
+    trace!("resolved poll content");
+
+    if image.as_deref().is_some_and(|url| !self::is_valid_url(url)) {
+        debug!("an invalid image url was provided");
+
+        let title = localize!(async(try in locale) category::UI, "poll-invalid-url").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+    trace!("validated poll image");
+
+    let choices = if kind.has_choices() {
+        let choices = self::input_value(&resolver, CHOICES_ID)?.unwrap_or_default();
+
+        choices.lines().map(str::trim).filter(|s| !s.is_empty()).map(Into::into).collect::<Vec<Box<str>>>()
+    } else {
+        Vec::new()
+    };
+
+    if kind.has_choices()
+        && (!(MIN_CHOICES ..= MAX_CHOICES).contains(&choices.len())
+            || choices.iter().any(|choice| choice.chars().count() > CHOICE_LENGTH))
+    {
+        debug!("an invalid list of choices was provided");
+
+        let title = localize!(async(try in locale) category::UI, "poll-invalid-choices").await?;
+
+        context.failure_message(title, None::<&str>).await?;
+        debug!("completed interaction");
+
+        return crate::client::event::pass();
+    }
+    trace!(count = choices.len(), "validated poll choices");
+
+    let poll = Poll::new(guild_id, user_id, kind, duration, PollContent { title, description, image }, choices);
+
+    poll.as_async_api().write().await?;
+    debug!("wrote poll file");
+
+    let send_button = ButtonBuilder::new(ButtonStyle::Success)
+        .custom_id(entry.id(component::send::NAME)?)
+        .label(localize!(async(try in locale) category::UI_BUTTON, "poll-send").await?.to_string())
+        .try_build()?;
+
+    let mut components = poll.build(entry, locale, true).await?.into_vec();
+
+    components.push(ActionRowBuilder::new().component(send_button).try_build()?.into());
+    debug!("created message components");
+
+    context.components(components, Visibility::Ephemeral).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Executes the respond modal.
+///
+/// # Errors
+///
+/// This function will return an error if the modal could not be executed.
+async fn on_respond_modal<'ap: 'ev, 'ev>(
+    _: &CommandEntry,
+    mut context: Context<'ap, 'ev, &'ev ModalInteractionData>,
+    custom_id: CustomId,
+    resolver: ModalComponentResolver<'ev>,
+) -> EventResult {
+    let Some(guild_id) = context.interaction.guild_id else {
+        bail!("this modal must be used in a guild");
+    };
+    let Some(user_id) = context.interaction.author_id() else {
+        bail!("this modal must be used by a user");
+    };
+    let Some(owner_id) = custom_id.get::<Id<UserMarker>>(0).transpose()? else {
+        bail!("missing poll owner identifier data");
+    };
+    let Some(response) = self::input_value(&resolver, RESPONSE_ID)? else {
+        bail!("missing required poll response");
+    };
+
+    context.defer(Visibility::Ephemeral).await?;
+
+    let locale = match context.as_locale() {
+        Ok(locale) => Some(locale),
+        Err(ina_localizing::Error::MissingLocale) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let guard = self::lock_poll(guild_id, owner_id).await;
+
+    let Some(mut poll) = self::load_active(&mut context, locale, guild_id, owner_id).await? else {
+        return crate::client::event::pass();
+    };
+
+    poll.responses.insert(user_id, response);
+    debug!(user = %user_id, "recorded response");
+    poll.as_async_api().write().await?;
+    debug!("wrote poll file");
+
+    drop(guard);
+
+    let title = localize!(async(try in locale) category::UI, "poll-responded").await?;
+
+    context.success_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    crate::client::event::pass()
+}
+
+/// Wraps the given text input within a localized label for use within a modal.
+///
+/// The input itself is assigned the numeric identifier directly following the label's identifier.
+///
+/// # Errors
+///
+/// This function will return an error if the label could not be created.
+async fn create_label(locale: Option<Locale>, key: &str, id: i32, input: TextInputBuilder) -> Result<Label> {
+    let label = localize!(async(try in locale) category::UI_INPUT, key).await?;
+
+    Ok(LabelBuilder::new(label.to_string(), input.id(id + 1).try_build()?).id(id).try_build()?)
+}
+
+/// Returns the trimmed value of the labelled text input with the given label identifier, or [`None`] if it is empty.
+///
+/// # Errors
+///
+/// This function will return an error if the input could not be resolved.
+fn input_value(resolver: &ModalComponentResolver<'_>, id: i32) -> Result<Option<Box<str>>> {
+    let label = resolver.label(id)?;
+    let value = label.text_input(id + 1)?.value.trim();
+
+    Ok((!value.is_empty()).then(|| value.into()))
+}
+
+/// Returns whether the given string looks like a valid web URL.
+fn is_valid_url(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) else {
+        return false;
+    };
+
+    !rest.is_empty() && !rest.contains(char::is_whitespace)
+}
+
+/// Returns the preferred locale of the given guild, if it is cached.
+fn guild_locale(api: ApiRef<'_>, guild_id: Id<GuildMarker>) -> Option<Locale> {
+    api.cache.guild(guild_id).and_then(|guild| guild.as_locale().ok())
+}
+
+/// Loads the sent poll owned by the given user, returning [`None`] and informing the user if it has ended.
+///
+/// If the poll's duration has elapsed but it has not yet been closed, it is closed before returning. The caller must
+/// hold the poll's lock, acquired through [`lock_poll`].
+///
+/// # Errors
+///
+/// This function will return an error if the poll could not be loaded or closed.
+async fn load_active<'ap: 'ev, 'ev, T: Send>(
+    context: &mut Context<'ap, 'ev, T>,
+    locale: Option<Locale>,
+    guild_id: Id<GuildMarker>,
+    owner_id: Id<UserMarker>,
+) -> Result<Option<Poll>> {
+    let poll = if Poll::async_api().exists((guild_id, owner_id)).await? {
+        Some(Poll::async_api().read((guild_id, owner_id)).await?)
+    } else {
+        None
+    };
+
+    match poll {
+        Some(poll) if poll.is_sent() && !poll.is_expired() => return Ok(Some(poll)),
+        Some(poll) if poll.is_expired() => {
+            self::close_poll(context.api, guild_id, owner_id, None).await?;
+            debug!("closed expired poll");
+        }
+        _ => debug!("the poll has already ended"),
+    }
+
+    let title = localize!(async(try in locale) category::UI, "poll-ended").await?;
+
+    context.failure_message(title, None::<&str>).await?;
+    debug!("completed interaction");
+
+    Ok(None)
+}
+
+/// Closes the given user's sent poll, replacing its message with the poll's results and removing its stored data.
+///
+/// If a message identifier is provided, the poll is only closed if it is attached to that message. Returns whether a
+/// poll was closed. The caller must hold the poll's lock, acquired through [`lock_poll`].
+///
+/// # Errors
+///
+/// This function will return an error if the poll could not be loaded, displayed, or removed.
+async fn close_poll(
+    api: ApiRef<'_>,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    message_id: Option<Id<MessageMarker>>,
+) -> Result<bool> {
+    if !Poll::async_api().exists((guild_id, user_id)).await? {
+        return Ok(false);
+    }
+
+    let poll = Poll::async_api().read((guild_id, user_id)).await?;
+
+    let Some(anchor) = poll.anchor else { return Ok(false) };
+
+    if message_id.is_some_and(|message_id| message_id != anchor.message_id) {
+        return Ok(false);
+    }
+
+    let components = poll.build_results(self::guild_locale(api, guild_id)).await?;
+    debug!("created result components");
+
+    // The message may have been deleted, in which case the poll should still be cleaned up.
+    if let Err(error) = anchor.update(api).components(Some(&*components)).await {
+        warn!(%error, "failed to display poll results");
+    }
+
+    poll.as_async_api().delete().await?;
+    debug!("removed poll file");
+
+    Ok(true)
+}
+
+/// Schedules the automatic closure of every stored poll that has been sent, returning the number of scheduled polls.
+///
+/// Scheduled closures only live in memory, so this must be called whenever the client starts. Polls that cannot be
+/// loaded are skipped.
+///
+/// Closures are only scheduled once per process, so later calls, such as those made when the client reconnects, do
+/// nothing and return `0`. If the stored polls could not be listed, a later call may try again.
+///
+/// # Errors
+///
+/// This function will return an error if the stored polls could not be listed.
+pub async fn schedule_stored_closes(api: &Api) -> Result<usize> {
+    if CLOSES_SCHEDULED.swap(true, Ordering::AcqRel) {
+        trace!("skipped already scheduled poll closures");
+
+        return Ok(0);
+    }
+
+    let paths =
+        Poll::async_api().list("poll").await.inspect_err(|_| CLOSES_SCHEDULED.store(false, Ordering::Release))?;
+    let mut count = 0;
+
+    for path in paths {
+        let Some((guild_id, user_id)) = self::poll_ids(&path) else {
+            warn!(?path, "skipped poll with an invalid path");
+
+            continue;
+        };
+
+        match Poll::async_api().read((guild_id, user_id)).await {
+            Ok(poll) if poll.is_sent() => {
+                self::schedule_close(api.clone(), &poll);
+
+                count += 1;
+            }
+            Ok(_) => trace!(?path, "skipped unsent poll"),
+            Err(error) => warn!(?path, %error, "failed to load stored poll"),
+        }
+    }
+
+    Ok(count)
+}
+
+/// Returns the guild and owner identifiers of the poll stored at the given path.
+fn poll_ids(path: &Path) -> Option<(Id<GuildMarker>, Id<UserMarker>)> {
+    let mut components = path.strip_prefix("poll").ok()?.iter().map(|component| component.to_str());
+    let guild_id = components.next()??.parse().ok()?;
+    let user_id = components.next()??.parse().ok()?;
+
+    components.next().is_none().then_some((guild_id, user_id))
+}
+
+/// Spawns a task that automatically closes the given poll once its duration has elapsed.
+fn schedule_close(api: Api, poll: &Poll) {
+    let (Some(anchor), Some(closes_at)) = (poll.anchor, poll.closes_at) else { return };
+    let (guild_id, user_id) = (poll.guild_id, poll.user_id);
+
+    let remaining = closes_at.saturating_sub(OffsetDateTime::now_utc().unix_timestamp());
+    let remaining = Duration::from_secs(u64::try_from(remaining).unwrap_or(0));
+    let span = trace_span!("poll", guild = %guild_id, user = %user_id);
+
+    tokio::spawn(
+        async move {
+            tokio::time::sleep(remaining).await;
+
+            let _guard = self::lock_poll(guild_id, user_id).await;
+
+            match self::close_poll(api.as_ref(), guild_id, user_id, Some(anchor.message_id)).await {
+                Ok(true) => debug!("automatically closed poll"),
+                Ok(false) => trace!("poll was closed before its duration elapsed"),
+                Err(error) => warn!(%error, "failed to automatically close poll"),
+            }
+        }
+        .instrument(span),
+    );
+    debug!(seconds = remaining.as_secs(), "scheduled poll closure");
+}
+
+/// Acquires the lock of the given user's poll, waiting for any other change to the poll to complete.
+///
+/// Every change to a stored poll must be made while holding this lock, as each one reads the poll before writing it.
+async fn lock_poll(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Locks are only kept alive by their guards, so any that cannot be upgraded are no longer in use.
+        locks.retain(|_, lock| lock.strong_count() > 0);
+
+        let lock = locks.get(&(guild_id, user_id)).and_then(Weak::upgrade).unwrap_or_default();
+
+        locks.insert((guild_id, user_id), Arc::downgrade(&lock));
+
+        lock
+    };
+
+    lock.lock_owned().await
+}
@@ -60,9 +60,14 @@ impl Selector {
 }
 
 /// A list of role selector entries.
+///
+/// Lists are drafts that are deleted once finished, so abandoned lists expire a week after they were last modified.
+/// Lists are indexed by their roles, so that deleted roles can be removed from them.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize, Stored)]
 #[data_format(kind = Compress<Messagepack>, from = Compress::new_fast(Messagepack))]
 #[data_path(fmt = "role/{}/{}", args = [Id<GuildMarker>, Id<UserMarker>], from = [guild_id, user_id])]
+#[data_expiry(days = 7)]
+#[data_index(role_ids)]
 pub struct SelectorList {
     /// The user identifier.
     pub user_id: Id<UserMarker>,
@@ -78,6 +83,11 @@ impl SelectorList {
         Self { user_id, guild_id, inner: Vec::new() }
     }
 
+    /// Returns the identifiers of the list's roles.
+    pub fn role_ids(&self) -> impl Iterator<Item = Id<RoleMarker>> {
+        self.inner.iter().map(|selector| selector.id)
+    }
+
     /// Builds the selector entry list into a list of components.
     ///
     /// # Errors
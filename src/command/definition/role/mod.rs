@@ -479,3 +479,30 @@ async fn on_remove_component<'ap: 'ev, 'ev>(
 
     crate::client::event::pass()
 }
+
+/// Removes the given deleted role from every stored role selector list, returning the number of lists that changed.
+///
+/// # Errors
+///
+/// This function will return an error if the lists could not be found or updated.
+pub async fn remove_deleted_role(role_id: Id<RoleMarker>) -> anyhow::Result<usize> {
+    let lists = SelectorList::async_api().find_by_index("role_ids", role_id).await?;
+    let count = lists.len();
+
+    for mut selectors in lists {
+        selectors.inner.retain(|e| e.id != role_id);
+
+        if selectors.inner.is_empty() {
+            selectors.as_async_api().delete().await?;
+        } else if let Some(lifetime) = selectors.as_async_api().lifetime().await? {
+            // Removing a deleted role is not user activity, so the list should still expire when it would have.
+            selectors.as_async_api().write_for(lifetime).await?;
+        } else {
+            selectors.as_async_api().write().await?;
+        }
+
+        debug!(guild = %selectors.guild_id, user = %selectors.user_id, "removed deleted role from selector list");
+    }
+
+    Ok(count)
+}
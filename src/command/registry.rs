@@ -392,7 +392,7 @@ macro_rules! define_entry {
                     resolver: $crate::command::resolver::ModalComponentResolver<'ev>,
                 ) -> $crate::client::event::EventResult
                 {
-                    let result = $modal_callback(entry, context, custom_id).await;
+                    let result = $modal_callback(entry, context, custom_id, resolver).await;
 
                     if result.is_ok() {
                         ::tracing::debug!(name = %$name, "executed modal callback for command");
@@ -18,12 +18,15 @@
 //! Your resident M41D Unit, here to help with your server.
 
 use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use ina_macro::optional;
+use ina_storage::format::encryption::{EncryptionKey, KeyRing};
 use serde::Serialize;
 use time::OffsetDateTime;
 use time::format_description::FormatItem;
@@ -34,6 +37,9 @@ use tracing_subscriber::fmt::writer::Tee;
 
 use crate::client::Instance;
 
+/// The storage encryption keys, resolved from the environment during startup.
+static ENCRYPTION_KEYS: OnceLock<KeyRing> = OnceLock::new();
+
 /// The bot's client implementation.
 pub mod client;
 /// The bot's commands and command registry.
@@ -47,8 +53,7 @@ pub mod utility;
     keep_annotations = [non_exhaustive],
     apply_derives = [Clone, Debug, Hash, PartialEq, Eq],
 )]
-#[derive(Clone, Debug, Hash, PartialEq, Eq, Parser, Serialize)]
-#[command(about, version)]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Args, Serialize)]
 pub struct Arguments {
     /// The bot's settings.
     #[option(flatten)]
@@ -64,6 +69,60 @@ pub struct Arguments {
     pub lang_settings: ina_localizing::settings::Settings,
 }
 
+/// The application's command-line interface.
+#[derive(Clone, Debug, PartialEq, Eq, Parser)]
+#[command(about, version)]
+struct Interface {
+    /// The application's command-line arguments.
+    #[command(flatten)]
+    arguments: OptionalArguments,
+    /// The maintenance command to run instead of starting the bot.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// A maintenance command that runs instead of starting the bot.
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Re-encrypts all stored data using the current encryption key.
+    ///
+    /// Data encrypted using any key listed within `PREVIOUS_ENCRYPTION_KEYS` is rewritten using `ENCRYPTION_KEY`.
+    Reencrypt,
+    /// Exports all stored data into a portable archive.
+    Export {
+        /// The path of the archive to create.
+        path: PathBuf,
+        /// Whether to store encrypted data in plain text, allowing it to be imported using a different key.
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// Imports all data from a portable archive into the storage system.
+    Import {
+        /// The path of the archive to import.
+        path: PathBuf,
+        /// Whether to re-encrypt data that is still encrypted using the current encryption key.
+        #[arg(long)]
+        reencrypt: bool,
+    },
+    /// Replaces all stored data with the contents of a snapshot.
+    ///
+    /// Data that is not contained within the snapshot is deleted.
+    Restore {
+        /// The path of the snapshot to restore, relative to the snapshot directory. If unset, the most recent snapshot
+        /// is restored.
+        snapshot: Option<PathBuf>,
+    },
+    /// Checks whether all stored data can be decoded, reporting any corrupt or orphaned data.
+    ///
+    /// Exits with a failure code if any problems are found.
+    Fsck {
+        /// Whether to move corrupt or orphaned data into the quarantine directory.
+        #[arg(long)]
+        quarantine: bool,
+    },
+}
+
 /// The application's main entry-point.
 ///
 /// # Errors
@@ -85,7 +144,7 @@ pub fn main() -> Result<ExitCode> {
         std::env::set_var("RUST_BACKTRACE", "1");
     }
 
-    let arguments = get_config();
+    let (arguments, command) = get_config();
 
     self::initialize_logger(&arguments)?;
     info!("initialized logging subscriber");
@@ -103,7 +162,7 @@ pub fn main() -> Result<ExitCode> {
     let id = runtime.handle().id();
     debug!(%id, workers = runtime.metrics().num_workers(), "spawned asynchronous runtime");
 
-    let code = runtime.block_on(self::async_main(arguments))?;
+    let code = runtime.block_on(self::async_main(arguments, command))?;
     debug!(%id, "exited asynchronous runtime");
 
     runtime.shutdown_timeout(timeout);
@@ -120,7 +179,7 @@ pub fn main() -> Result<ExitCode> {
 ///
 /// This function will return an error if the program's execution fails.
 #[tracing::instrument(level = "trace", name = "rt_m", skip_all)]
-pub async fn async_main(arguments: Arguments) -> Result<ExitCode> {
+pub async fn async_main(arguments: Arguments, command: Option<Command>) -> Result<ExitCode> {
     let runtime = tokio::runtime::Handle::current();
     info!(id = %runtime.id(), workers = runtime.metrics().num_workers(), "entered asynchronous runtime");
 
@@ -130,12 +189,26 @@ pub async fn async_main(arguments: Arguments) -> Result<ExitCode> {
     let count = ina_localizing::thread::load(None::<[_; 0]>).await?;
     info!(count, "loaded localizer locales");
 
-    ina_storage::format::encryption::set_password_resolver(|| {
-        crate::utility::secret::encryption_key().map(|v| v.to_string()).ok()
-    });
+    if let Some(keys) = self::resolve_encryption_keys()? {
+        ENCRYPTION_KEYS.get_or_init(|| keys);
+    }
+
+    ina_storage::format::encryption::set_key_resolver(|| ENCRYPTION_KEYS.get().cloned());
     ina_storage::thread::start(arguments.data_settings).await?;
     info!("initialized storage thread");
 
+    if let Some(command) = command {
+        let code = self::run_command(command).await?;
+
+        ina_storage::thread::close().await;
+        info!("closed storage thread");
+
+        ina_localizing::thread::close().await;
+        info!("closed localization thread");
+
+        return Ok(code);
+    }
+
     let instance = Instance::new(arguments.bot_settings).await?;
     info!("initialized client instance");
 
@@ -174,12 +247,73 @@ pub async fn async_main(arguments: Arguments) -> Result<ExitCode> {
     Ok(code)
 }
 
+/// Runs the given maintenance command, returning the program's exit code.
+///
+/// # Errors
+///
+/// This function will return an error if the command fails.
+async fn run_command(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Reencrypt => {
+            let count = ina_storage::thread::reencrypt(Path::new("").into()).await?;
+            info!(count, "re-encrypted stored data");
+        }
+        Command::Export { path, decrypt } => {
+            let manifest = ina_storage::archive::export(&path, decrypt).await?;
+            info!(?path, count = manifest.entries.len(), "exported stored data");
+        }
+        Command::Import { path, reencrypt } => {
+            let manifest = ina_storage::archive::import(&path, reencrypt).await?;
+            info!(?path, count = manifest.entries.len(), "imported stored data");
+        }
+        Command::Restore { snapshot } => {
+            let manifest = ina_storage::thread::restore(snapshot.map(PathBuf::into_boxed_path)).await?;
+            info!(count = manifest.entries.len(), "restored stored data");
+        }
+        Command::Fsck { quarantine } => {
+            let report = ina_storage::fsck::check(quarantine).await?;
+            info!(checked = report.checked, problems = report.problems.len(), "checked stored data");
+
+            for path in &report.quarantined {
+                info!(?path, "quarantined data");
+            }
+
+            if !report.is_healthy() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Resolves the storage encryption keys from the environment, returning [`None`] if no key is configured.
+///
+/// The current key is identified by `ENCRYPTION_KEY_ID`, defaulting to `0` if unset.
+///
+/// # Errors
+///
+/// This function will return an error if `PREVIOUS_ENCRYPTION_KEYS` is malformed.
+fn resolve_encryption_keys() -> Result<Option<KeyRing>> {
+    use crate::utility::secret;
+
+    let Ok(password) = secret::encryption_key() else { return Ok(None) };
+    let id = secret::encryption_key_id().map_or_else(|_| "0".into(), |v| Box::from(&*v));
+    let current = EncryptionKey::new(id, password.to_string());
+    let previous = secret::previous_encryption_keys()?;
+
+    Ok(Some(previous.iter().fold(KeyRing::new(current), |keys, (id, password)| {
+        keys.with_previous(EncryptionKey::new(&**id, password.to_string()))
+    })))
+}
+
 /// Resolve command-line arguments.
 ///
-/// This is distinct from just running [`OptionalArguments::fill_defaults`] on [`OptionalArguments::parse`] because it
-/// applies extra changes on top.
-fn get_config() -> Arguments {
-    let mut args = OptionalArguments::parse().fill_defaults();
+/// This is distinct from just running [`OptionalArguments::fill_defaults`] on the parsed arguments because it applies
+/// extra changes on top. The maintenance command, if any, is returned alongside the arguments.
+fn get_config() -> (Arguments, Option<Command>) {
+    let Interface { arguments, command } = Interface::parse();
+    let mut args = arguments.fill_defaults();
 
     if args.bot_settings.quiet {
         args.bot_settings.disable_file_logging = true;
@@ -187,7 +321,7 @@ fn get_config() -> Arguments {
     }
     args.bot_settings.quiet = args.bot_settings.disable_file_logging && args.bot_settings.disable_console_logging;
 
-    args
+    (args, command)
 }
 
 /// Initializes the logging subscriber.
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU Affero General Public License along with 1N4. If not, see
 // <https://www.gnu.org/licenses/>.
 
+use std::env::VarError;
 use std::num::NonZero;
 use std::sync::Arc;
 
@@ -72,7 +73,7 @@ pub fn development_channel_id() -> Result<Id<ChannelMarker>> {
     self::get_id("DEVELOPMENT_CHANNEL_ID")
 }
 
-/// Returns the Discord token environment variable, if present.
+/// Returns the encryption key environment variable, if present.
 ///
 /// This can be configured using `ENCRYPTION_KEY`.
 ///
@@ -82,3 +83,39 @@ pub fn development_channel_id() -> Result<Id<ChannelMarker>> {
 pub fn encryption_key() -> Result<Arc<str>> {
     self::get("ENCRYPTION_KEY")
 }
+
+/// Returns the encryption key identifier environment variable, if present.
+///
+/// This can be configured using `ENCRYPTION_KEY_ID`.
+///
+/// # Errors
+///
+/// This function will return an error if the environment variable is not defined.
+pub fn encryption_key_id() -> Result<Arc<str>> {
+    self::get("ENCRYPTION_KEY_ID")
+}
+
+/// Returns the previous encryption keys environment variable as a list of identifier and password pairs.
+///
+/// This can be configured using `PREVIOUS_ENCRYPTION_KEYS`, as a whitespace-separated list of `id:password` entries.
+/// If the environment variable is not defined, an empty list is returned.
+///
+/// # Errors
+///
+/// This function will return an error if the environment variable is not valid unicode or if an entry is missing its
+/// identifier.
+pub fn previous_encryption_keys() -> Result<Box<[(Arc<str>, Arc<str>)]>> {
+    let entries = match std::env::var("PREVIOUS_ENCRYPTION_KEYS") {
+        Err(VarError::NotPresent) => return Ok(Box::default()),
+        result => result?,
+    };
+
+    entries
+        .split_whitespace()
+        .map(|entry| {
+            let (id, password) = entry.split_once(':').ok_or_else(|| anyhow::anyhow!("missing encryption key id"))?;
+
+            Ok((id.into(), password.into()))
+        })
+        .collect()
+}